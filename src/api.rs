@@ -0,0 +1,539 @@
+//! API HTTP local e dashboard.
+
+use super::*;
+
+/// Estado publicado em `/status` e em `status.json`.
+pub(crate) fn status_snapshot(s: &PingerState) -> serde_json::Value {
+    serde_json::json!({
+        "version": APP_VERSION,
+        "last_update": s.last_update_text,
+        "cycle": s.update_counter,
+        "all_up": s.all_up,
+        "paused_until": s.paused_until.map(|t| t.to_rfc3339()),
+        "muted": s.active_mute().is_some(),
+        "config_error": s.config_error,
+        "targets": s.results,
+    })
+}
+
+/// `$XDG_RUNTIME_DIR/cosmic_pinger/status.json`; `None` fora do Linux ou sem a variável.
+pub(crate) fn status_file_path() -> Option<PathBuf> {
+    let dirs = directories::BaseDirs::new()?;
+    Some(dirs.runtime_dir()?.join("cosmic_pinger").join("status.json"))
+}
+
+/// Regrava o `status.json` via arquivo temporário + rename, para quem lê
+/// (conky, scripts) nunca pegar um JSON pela metade.
+pub(crate) fn write_status_file(state: &PingerState) {
+    let Some(path) = status_file_path() else {
+        return;
+    };
+    let json = status_snapshot(state).to_string();
+    let tmp = path.with_extension("json.tmp");
+    let written = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(&tmp, json))
+        .and_then(|_| fs::rename(&tmp, &path));
+    if let Err(e) = written {
+        warn!("[STATUS] Erro ao gravar {:?}: {}", path, e);
+    }
+}
+
+pub(crate) const API_MAX_REQUEST_BYTES: usize = 64 * 1024;
+/// Pausa mais longa aceita por `POST /pause` (30 dias).
+pub(crate) const API_MAX_PAUSE_MINUTES: u64 = 30 * 24 * 60;
+pub(crate) const DASHBOARD_HTML: &str = include_str!("../assets/dashboard.html");
+/// Validade de um código de entrada no dashboard (ver `issue_dashboard_code`).
+pub(crate) const DASHBOARD_CODE_TTL_SECS: u64 = 60;
+
+/// Códigos de uso único que a bandeja põe na URL do dashboard no lugar do
+/// token, que assim não passa pela linha de comando do navegador nem fica no histórico.
+pub(crate) static DASHBOARD_CODES: Mutex<Vec<(String, Instant)>> = Mutex::new(Vec::new());
+
+/// Novo código de entrada no dashboard: aleatório, trocado uma única vez pelo
+/// token em `POST /dashboard/session` e válido por `DASHBOARD_CODE_TTL_SECS`.
+pub fn issue_dashboard_code() -> Option<String> {
+    let mut bytes = [0u8; 16];
+    rustls::crypto::ring::default_provider().secure_random.fill(&mut bytes).ok()?;
+    let code: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let mut codes = DASHBOARD_CODES.lock().ok()?;
+    codes.retain(|(_, issued)| issued.elapsed() < Duration::from_secs(DASHBOARD_CODE_TTL_SECS));
+    codes.push((code.clone(), Instant::now()));
+    Some(code)
+}
+
+/// Consome o código; `false` se ele não existe, já foi usado ou venceu.
+pub(crate) fn redeem_dashboard_code(code: &str) -> bool {
+    let Ok(mut codes) = DASHBOARD_CODES.lock() else { return false };
+    codes.retain(|(_, issued)| issued.elapsed() < Duration::from_secs(DASHBOARD_CODE_TTL_SECS));
+    match codes.iter().position(|(known, _)| known == code) {
+        Some(index) => {
+            codes.swap_remove(index);
+            true
+        }
+        None => false,
+    }
+}
+
+pub(crate) struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: HashMap<String, String>,
+    pub headers: HashMap<String, String>,
+    pub body: String,
+}
+
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn json(status: u16, value: serde_json::Value) -> Self {
+        Self { status, content_type: "application/json", body: value.to_string() }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self::json(status, serde_json::json!({ "error": message }))
+    }
+}
+
+pub(crate) fn start_api_server(cfg: ApiConfig, state: StateReceiver, commands: mpsc::Sender<MonitorCommand>) {
+    if cfg.token.trim().is_empty() {
+        warn!("[API] Token vazio; API local desativada");
+        return;
+    }
+    let listener = match std::net::TcpListener::bind(&cfg.bind) {
+        Ok(listener) => listener,
+        Err(e) => {
+            warn!("[API] Não foi possível escutar em {}: {}", cfg.bind, e);
+            return;
+        }
+    };
+    info!("[API] Escutando em http://{}", cfg.bind);
+    let token = Arc::new(cfg.token);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let (state, commands, token) = (state.clone(), commands.clone(), token.clone());
+            thread::spawn(move || handle_api_connection(stream, &state, &commands, &token));
+        }
+    });
+}
+
+pub(crate) fn handle_api_connection(
+    mut stream: std::net::TcpStream,
+    state: &StateReceiver,
+    commands: &mpsc::Sender<MonitorCommand>,
+    token: &str,
+) {
+    use std::io::Write;
+
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+    let response = match read_http_request(&mut stream) {
+        // A página é estática; os dados que ela busca continuam exigindo o token
+        Ok(req) if req.method == "GET" && (req.path == "/" || req.path == "/dashboard") => HttpResponse {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: DASHBOARD_HTML.to_string(),
+        },
+        // Troca o código de uso único da URL aberta pela bandeja pelo token
+        Ok(req) if req.method == "POST" && req.path == "/dashboard/session" => {
+            let code = serde_json::from_str::<serde_json::Value>(&req.body)
+                .ok()
+                .and_then(|body| body.get("code").and_then(|c| c.as_str()).map(str::to_string));
+            match code {
+                Some(code) if redeem_dashboard_code(&code) => HttpResponse::json(200, serde_json::json!({ "token": token })),
+                _ => HttpResponse::error(401, "código inválido ou vencido"),
+            }
+        }
+        Ok(req) => {
+            let authorized = req
+                .headers
+                .get("authorization")
+                .and_then(|h| h.strip_prefix("Bearer "))
+                .or(req.query.get("token").map(String::as_str))
+                == Some(token);
+            if authorized {
+                route_api(&req, state, commands)
+            } else {
+                HttpResponse::error(401, "token inválido")
+            }
+        }
+        Err(e) => HttpResponse::error(400, &e),
+    };
+
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Error",
+    };
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    );
+    let _ = stream.write_all(head.as_bytes()).and_then(|_| stream.write_all(response.body.as_bytes()));
+}
+
+pub(crate) fn read_http_request(stream: &mut std::net::TcpStream) -> Result<HttpRequest, String> {
+    use std::io::Read;
+
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).map_err(|e| e.to_string())?;
+        if n == 0 {
+            return Err("conexão encerrada".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > API_MAX_REQUEST_BYTES {
+            return Err("requisição muito grande".to_string());
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default().to_string();
+    let target = request_line.next().ok_or("linha de requisição inválida")?;
+
+    let headers: HashMap<String, String> = lines
+        .filter_map(|l| l.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+
+    let content_length: usize = headers.get("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+    if content_length > API_MAX_REQUEST_BYTES {
+        return Err("corpo muito grande".to_string());
+    }
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+
+    let url = reqwest::Url::parse(&format!("http://localhost{}", target)).map_err(|e| e.to_string())?;
+    Ok(HttpRequest {
+        method,
+        path: url.path().to_string(),
+        query: url.query_pairs().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
+}
+
+pub(crate) fn route_api(req: &HttpRequest, state: &StateReceiver, commands: &mpsc::Sender<MonitorCommand>) -> HttpResponse {
+    match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/status") => HttpResponse::json(200, status_snapshot(&current_state(state))),
+        ("GET", "/history") => {
+            let hours = req.query.get("hours").and_then(|h| h.parse::<i64>().ok()).unwrap_or(24);
+            let since = Local::now().timestamp() - hours.clamp(1, 24 * 30) * 3600;
+            let mut samples = HistoryStore::read_since(since);
+            if let Some(host) = req.query.get("host") {
+                samples.retain(|s| &s.host == host);
+            }
+            HttpResponse::json(200, serde_json::json!(samples))
+        }
+        ("GET", "/heatmap") => {
+            let days = req.query.get("days").and_then(|d| d.parse::<i64>().ok()).unwrap_or(7).clamp(1, 30);
+            // Alinha as colunas na hora cheia para que não mudem a cada consulta
+            let to = (Local::now().timestamp() / HEATMAP_COLUMN_SECS + 1) * HEATMAP_COLUMN_SECS;
+            let from = to - days * DAY_SECS;
+            let mut samples = HistoryStore::read_since(from);
+            if let Some(host) = req.query.get("host") {
+                samples.retain(|s| &s.host == host);
+            }
+            HttpResponse::json(200, serde_json::json!(latency_heatmap(&samples, from, to)))
+        }
+        ("GET", "/targets") => match load_config() {
+            Ok(cfg) => HttpResponse::json(200, serde_json::json!(cfg.targets)),
+            Err(e) => HttpResponse::error(500, &e),
+        },
+        ("POST", "/targets") => {
+            // Aceita `{"address": "..."}` ou o endereço em texto puro
+            let body = serde_json::from_str::<serde_json::Value>(&req.body).ok();
+            // Script e hook executam comandos no host: só pelo sites.json
+            if body.as_ref().is_some_and(|v| v.get("script").is_some() || v.get("hook").is_some()) {
+                return HttpResponse::error(400, "'script' e 'hook' não são aceitos pela API");
+            }
+            let address = body
+                .and_then(|v| v.get("address").and_then(|a| a.as_str()).map(str::to_string))
+                .unwrap_or_else(|| req.body.clone());
+            let Some(cleaned) = normalize_target(&address) else {
+                return HttpResponse::error(400, "endereço vazio");
+            };
+            if !api_address_allowed(&cleaned) {
+                return HttpResponse::error(400, "esquema não permitido pela API");
+            }
+            update_config(|cfg| {
+                if !cfg.targets.iter().any(|t| t.address == cleaned) {
+                    cfg.targets.push(TargetConfig::new(&cleaned));
+                }
+            })
+        }
+        ("DELETE", "/targets") => {
+            let Some(address) = req.query.get("address") else {
+                return HttpResponse::error(400, "parâmetro 'address' obrigatório");
+            };
+            update_config(|cfg| cfg.targets.retain(|t| &t.address != address))
+        }
+        ("GET", "/probe") => {
+            let Some(target) = req.query.get("target").map(|t| t.trim()).filter(|t| !t.is_empty()) else {
+                return HttpResponse::error(400, "parâmetro 'target' obrigatório");
+            };
+            let module = req.query.get("module").map_or("http_2xx", String::as_str);
+            let Some(address) = blackbox_address(module, target) else {
+                return HttpResponse::error(400, &format!("módulo desconhecido: {}", module));
+            };
+            if !api_address_allowed(&address) {
+                return HttpResponse::error(400, "esquema não permitido pela API");
+            }
+            // O Prometheus informa o próprio timeout; a resposta precisa chegar antes dele
+            let timeout = req
+                .headers
+                .get("x-prometheus-scrape-timeout-seconds")
+                .and_then(|t| t.parse::<f64>().ok())
+                .map(|secs| Duration::from_secs_f64((secs - 0.5).max(1.0)))
+                .unwrap_or(Duration::from_secs(HTTP_TIMEOUT_SECS));
+            let started = Instant::now();
+            let outcome = check_target(&address, 1, 1, build_http_client(timeout).as_ref(), None);
+            HttpResponse {
+                status: 200,
+                content_type: "text/plain; version=0.0.4; charset=utf-8",
+                body: render_probe_metrics(&outcome, started.elapsed()),
+            }
+        }
+        ("POST", "/check") => send_command(commands, MonitorCommand::CheckNow),
+        ("POST", "/pause") => {
+            let minutes = match req.query.get("minutes") {
+                Some(m) => m.parse::<u64>().ok(),
+                None => Some(60),
+            };
+            match minutes.filter(|m| *m <= API_MAX_PAUSE_MINUTES).and_then(|m| m.checked_mul(60)) {
+                Some(secs) => send_command(commands, MonitorCommand::Pause(Duration::from_secs(secs))),
+                None => HttpResponse::error(400, &format!("'minutes' deve ser um inteiro entre 0 e {}", API_MAX_PAUSE_MINUTES)),
+            }
+        }
+        ("POST", "/resume") => send_command(commands, MonitorCommand::Resume),
+        (_, "/status" | "/history" | "/heatmap" | "/probe" | "/targets" | "/check" | "/pause" | "/resume") => HttpResponse::error(405, "método não permitido"),
+        _ => HttpResponse::error(404, "rota desconhecida"),
+    }
+}
+
+/// Esquemas que a API aceita em `/probe` e `POST /targets`. `script://` roda
+/// um arquivo Rhai do host e, como os hooks, só entra pelo `sites.json`.
+pub(crate) const API_CHECK_SCHEMES: [&str; 6] = ["http", "https", "tcp", "ping", "dns", "ports"];
+
+pub(crate) fn api_address_allowed(address: &str) -> bool {
+    address
+        .split_once("://")
+        .is_none_or(|(scheme, _)| API_CHECK_SCHEMES.contains(&scheme.to_ascii_lowercase().as_str()))
+}
+
+/// Endereço checado para um `module` do blackbox_exporter em `/probe`.
+/// Os módulos usuais (`http_2xx`, `tcp_connect`, `icmp`, `dns`) viram o
+/// esquema equivalente; `auto` usa o endereço como na lista de destinos,
+/// desde que o esquema esteja em `API_CHECK_SCHEMES`.
+pub(crate) fn blackbox_address(module: &str, target: &str) -> Option<String> {
+    let has_scheme = target.contains("://");
+    let strip = |t: &str| t.split_once("://").map_or(t, |(_, rest)| rest).to_string();
+    match module {
+        "auto" => api_address_allowed(target).then(|| target.to_string()),
+        // Como no blackbox_exporter, alvo HTTP sem esquema vira http://
+        m if m.starts_with("http") => Some(if has_scheme { target.to_string() } else { format!("http://{}", target) }),
+        m if m.starts_with("tcp") => Some(format!("tcp://{}", strip(target))),
+        m if m.starts_with("icmp") || m == "ping" => Some(format!("ping://{}", strip(target))),
+        m if m.starts_with("dns") => Some(format!("dns://{}", strip(target))),
+        "ports" => Some(format!("ports://{}", strip(target))),
+        _ => None,
+    }
+}
+
+/// Métricas de uma sonda no formato texto do Prometheus, com os nomes do
+/// blackbox_exporter e dois extras do cosmic_pinger.
+pub(crate) fn render_probe_metrics(outcome: &CheckOutcome, elapsed: Duration) -> String {
+    let mut body = String::new();
+    let mut gauge = |name: &str, help: &str, value: String| {
+        body.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n"));
+    };
+    gauge("probe_success", "Displays whether or not the probe was a success", u8::from(outcome.success).to_string());
+    gauge("probe_duration_seconds", "Returns how long the probe took to complete in seconds", format!("{:.6}", elapsed.as_secs_f64()));
+    gauge("cosmic_pinger_probe_degraded", "Whether the target answered but is degraded", u8::from(outcome.degraded).to_string());
+    if let Some(latency) = outcome.latency_ms {
+        gauge("cosmic_pinger_probe_latency_seconds", "Latency measured by the check", format!("{:.6}", latency / 1000.0));
+    }
+    body
+}
+
+/// Checagem avulsa com as opções do destino configurado de mesmo endereço
+/// (ou as padrão), pelo mesmo caminho do ciclo: extras, script, limites.
+pub fn run_single_check(address: &str, config: &AppConfig) -> Result<(TargetConfig, CheckOutcome), String> {
+    let cleaned = normalize_target(address).ok_or_else(|| tr("check.no_valid_targets").to_string())?;
+    let target = config
+        .targets
+        .iter()
+        .find(|t| t.address.trim() == cleaned)
+        .cloned()
+        .unwrap_or_else(|| TargetConfig::new(&cleaned));
+    let job = CheckJob::from_target(cleaned, &target);
+    job.probe.as_ref().map_err(Clone::clone)?;
+    let client = build_http_client(config.http_timeout());
+    Ok((target, run_check_job(&job, client.as_ref(), config.check_limits())))
+}
+
+/// Linha de plugin Nagios/Icinga e o código de saída (0 OK, 1 WARNING,
+/// 2 CRITICAL). O limite de aviso da latência é o `degraded_ms` do destino.
+pub fn nagios_output(target: &TargetConfig, outcome: &CheckOutcome) -> (i32, String) {
+    let (code, label) = if !outcome.success {
+        (2, "CRITICAL")
+    } else if outcome.degraded {
+        (1, "WARNING")
+    } else {
+        (0, "OK")
+    };
+    let mut perfdata = Vec::new();
+    if let Some(latency) = outcome.latency_ms {
+        let warn = target.degraded_ms.map(|ms| ms.to_string()).unwrap_or_default();
+        perfdata.push(format!("latency={:.3}ms;{};;0", latency, warn));
+    }
+    let loss = outcome.loss_pct.or_else(|| {
+        let is_ping = target.probe_address().is_ok_and(|address| check_name(&address) == "ping");
+        is_ping.then_some(if outcome.success { 0.0 } else { 100.0 })
+    });
+    if let Some(loss) = loss {
+        perfdata.push(format!("loss={:.0}%;;;0;100", loss));
+    }
+    let mut line = format!("{} - {}: {}", label, target.address.trim(), outcome.message);
+    if !perfdata.is_empty() {
+        line.push_str(&format!(" | {}", perfdata.join(" ")));
+    }
+    (code, line)
+}
+
+pub(crate) fn send_command(commands: &mpsc::Sender<MonitorCommand>, command: MonitorCommand) -> HttpResponse {
+    match commands.send(command) {
+        Ok(()) => HttpResponse::json(200, serde_json::json!({ "ok": true })),
+        Err(_) => HttpResponse::error(500, "monitor indisponível"),
+    }
+}
+
+/// Aplica uma alteração sobre a configuração em disco; o monitor a lê no próximo ciclo.
+pub(crate) fn update_config(change: impl FnOnce(&mut AppConfig)) -> HttpResponse {
+    if let Some(found) = config_newer_than_supported() {
+        return HttpResponse::error(409, &format!("sites.json v{} é mais novo que o suportado (v{})", found, CONFIG_VERSION));
+    }
+    match load_config() {
+        Ok(mut cfg) => {
+            change(&mut cfg);
+            save_config(&cfg);
+            HttpResponse::json(200, serde_json::json!(cfg.targets))
+        }
+        Err(e) => HttpResponse::error(500, &e),
+    }
+}
+
+/// Endereço base da API para clientes locais.
+pub fn api_base_url(api: &ApiConfig) -> String {
+    // Um bind em todas as interfaces continua acessível pelo loopback
+    format!("http://{}", api.bind.replace("0.0.0.0", "127.0.0.1").replace("[::]", "[::1]"))
+}
+
+/// URL local do dashboard com um código de uso único no lugar do token, que
+/// ficaria visível na linha de comando do `xdg-open` e no histórico do navegador.
+/// Sem código, a página pede o token.
+pub fn dashboard_url(api: &ApiConfig) -> String {
+    let base = api_base_url(api);
+    match issue_dashboard_code() {
+        Some(code) => format!("{}/dashboard?code={}", base, code),
+        None => format!("{}/dashboard", base),
+    }
+}
+
+/// Resposta de `/status` vista por um cliente (a janela de configuração).
+#[derive(Deserialize, Debug, Clone)]
+pub struct LiveStatus {
+    pub last_update: String,
+    pub paused_until: Option<String>,
+    pub config_error: Option<String>,
+    pub targets: Vec<LiveTarget>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct LiveTarget {
+    pub host: String,
+    pub is_up: bool,
+    pub detail: String,
+    #[serde(default)]
+    pub flapping: bool,
+    #[serde(default)]
+    pub paused_until: Option<i64>,
+    #[serde(default)]
+    pub next_check: Option<i64>,
+    #[serde(default)]
+    pub network: Option<NetworkInfo>,
+    #[serde(default)]
+    pub hostname: Option<String>,
+    #[serde(default)]
+    pub dns_ms: Option<f64>,
+}
+
+/// Consulta o estado da instância em execução pela API local.
+pub async fn fetch_live_status(api: ApiConfig) -> Result<LiveStatus, String> {
+    reqwest::Client::new()
+        .get(format!("{}/status", api_base_url(&api)))
+        .bearer_auth(&api.token)
+        .timeout(Duration::from_secs(LIVE_STATUS_TIMEOUT_SECS))
+        .send()
+        .await
+        .and_then(|resp| resp.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json::<LiveStatus>()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_schemes() {
+        assert!(api_address_allowed("1.1.1.1"));
+        assert!(api_address_allowed("HTTPS://h"));
+        assert!(!api_address_allowed("script://x.rhai"));
+        assert!(!api_address_allowed("file:///etc/passwd"));
+    }
+
+    #[test]
+    fn blackbox_modules() {
+        assert_eq!(blackbox_address("http_2xx", "h.com").as_deref(), Some("http://h.com"));
+        assert_eq!(blackbox_address("tcp_connect", "http://h:22").as_deref(), Some("tcp://h:22"));
+        assert_eq!(blackbox_address("icmp", "h").as_deref(), Some("ping://h"));
+        assert_eq!(blackbox_address("auto", "dns://h").as_deref(), Some("dns://h"));
+        assert_eq!(blackbox_address("auto", "script://x"), None);
+        assert_eq!(blackbox_address("smtp", "h"), None);
+    }
+
+    #[test]
+    fn dashboard_codes_are_single_use() {
+        let code = issue_dashboard_code().unwrap();
+        assert_eq!(code.len(), 32);
+        assert!(redeem_dashboard_code(&code));
+        assert!(!redeem_dashboard_code(&code));
+        assert!(!redeem_dashboard_code("0123"));
+    }
+}
@@ -0,0 +1,105 @@
+//! Linha de comando: modo de execução e overrides.
+
+use super::*;
+
+pub(crate) fn exit_with_usage(err: &str) -> ! {
+    eprintln!("Erro: {}", err);
+    eprintln!("Uso: cosmic_pinger [--config | --onboarding | --watch DESTINO | --cert URL | --diagnose DESTINO | --statusbar | --text [--format MODELO] [--color none|polybar|pango|ansi] | check [--nagios] DESTINO | support-bundle [ARQUIVO]] [--interval SEGUNDOS] [--timeout SEGUNDOS] [--config-path ARQUIVO] [--log-level NÍVEL] [--log-file]");
+    process::exit(2);
+}
+
+/// Interface escolhida na linha de comando.
+pub(crate) enum RunMode {
+    Tray,
+    /// Janela de configuração, opcionalmente já num painel.
+    Config(Option<StartPane>),
+    Statusbar,
+    Text(TextOutput),
+    /// `check DESTINO`: uma checagem e sai com o código do resultado.
+    Check { target: String, nagios: bool },
+    /// `support-bundle [ARQUIVO]`: grava o pacote de suporte e sai.
+    SupportBundle(Option<PathBuf>),
+    /// Assistente de primeira execução (`--onboarding`).
+    Onboarding,
+}
+
+/// Painel aberto direto na janela de configuração (`--watch`, `--cert`,
+/// `--diagnose`), usado pelas ações da bandeja.
+pub(crate) enum StartPane {
+    Watch(String),
+    Certificate(String),
+    Diagnose(String),
+}
+
+/// Modo de execução e overrides extraídos da linha de comando.
+pub(crate) struct CliArgs {
+    pub mode: RunMode,
+    pub settings: RuntimeSettings,
+}
+
+pub(crate) fn parse_args(args: &[String]) -> Result<CliArgs, String> {
+    let mut settings = RuntimeSettings::from_env()?;
+    let mut mode = RunMode::Tray;
+    let mut text_format: Option<String> = None;
+    let mut color: Option<ColorStyle> = None;
+    let mut check: Option<Option<String>> = None;
+    let mut nagios = false;
+    let mut bundle: Option<Option<PathBuf>> = None;
+    let mut iter = args.iter().skip(1);
+
+    while let Some(arg) = iter.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| -> Result<String, String> {
+            inline
+                .clone()
+                .or_else(|| iter.next().cloned())
+                .ok_or_else(|| format!("{} requer um valor", name))
+        };
+        match flag {
+            "--config" if !matches!(mode, RunMode::Config(_)) => mode = RunMode::Config(None),
+            "--config" => {}
+            "--onboarding" => mode = RunMode::Onboarding,
+            "--watch" => mode = RunMode::Config(Some(StartPane::Watch(value(flag)?))),
+            "--cert" => mode = RunMode::Config(Some(StartPane::Certificate(value(flag)?))),
+            "--diagnose" => mode = RunMode::Config(Some(StartPane::Diagnose(value(flag)?))),
+            "--statusbar" => mode = RunMode::Statusbar,
+            "--text" => mode = RunMode::Text(TextOutput::default()),
+            "--format" => text_format = Some(value(flag)?),
+            "--color" => color = Some(ColorStyle::parse(&value(flag)?)?),
+            "--interval" => settings.monitor_interval = Some(parse_secs(flag, &value(flag)?)?),
+            "--timeout" => settings.http_timeout = Some(parse_secs(flag, &value(flag)?)?),
+            "--config-path" => settings.config_path = Some(PathBuf::from(value(flag)?)),
+            "--log-level" => settings.log_level = parse_log_level(flag, &value(flag)?)?,
+            "--log-file" => settings.log_file = true,
+            "--nagios" => nagios = true,
+            "check" if check.is_none() => check = Some(None),
+            other if !other.starts_with('-') && check == Some(None) => check = Some(Some(other.to_string())),
+            "support-bundle" if bundle.is_none() => bundle = Some(None),
+            other if !other.starts_with('-') && bundle == Some(None) => bundle = Some(Some(PathBuf::from(other))),
+            other => return Err(format!("Argumento desconhecido: {}", other)),
+        }
+    }
+
+    match check {
+        Some(Some(target)) => mode = RunMode::Check { target, nagios },
+        Some(None) => return Err("check requer um destino".to_string()),
+        None if nagios => return Err("--nagios só vale com check".to_string()),
+        None => {}
+    }
+
+    if let Some(path) = bundle {
+        mode = RunMode::SupportBundle(path);
+    }
+
+    if let RunMode::Text(output) = &mut mode {
+        output.format = text_format.unwrap_or_else(|| DEFAULT_TEXT_FORMAT.to_string());
+        output.color = color.unwrap_or(ColorStyle::None);
+    } else if text_format.is_some() || color.is_some() {
+        return Err("--format e --color só valem com --text".to_string());
+    }
+
+    Ok(CliArgs { mode, settings })
+}
@@ -0,0 +1,543 @@
+//! Ciclo de vida da janela de configuração: mensagens, assinaturas e layout.
+
+use super::*;
+use iced::widget::{button, column, container, row, text, text_editor, text_input};
+
+impl Application for ConfigWindow {
+    type Executor = iced::executor::Default;
+    type Message = Message;
+    type Theme = Theme;
+    type Flags = ConfigFlags;
+
+    fn new(flags: ConfigFlags) -> (Self, Command<Message>) {
+        let (config, load_error) = match load_config() {
+            Ok(cfg) => (cfg, None),
+            Err(e) => {
+                error!("Erro ao ler configuração: {}", e);
+                (load_config_backup().unwrap_or_default(), Some(e))
+            }
+        };
+        let mut window = ConfigWindow {
+            config,
+            input_value: String::new(),
+            load_error,
+            newer_version: config_newer_than_supported(),
+            tab: ConfigTab::Targets,
+            drafts: HashMap::new(),
+            invalid: HashSet::new(),
+            live_status: None,
+            history: None,
+            history_host: None,
+            history_date: String::new(),
+            test_results: HashMap::new(),
+            bulk_input: None,
+            bulk_summary: None,
+            presets: None,
+            target_filter: String::new(),
+            system_dark: system_prefers_dark(),
+            geometry: flags.geometry,
+            last_removed: None,
+            selected: None,
+            editor: None,
+            watch: None,
+            certificate: None,
+            diagnosis: None,
+            support_bundle: None,
+        };
+        let command = match flags.start {
+            Some(StartPane::Watch(address)) => window.update(Message::WatchTarget(address)),
+            Some(StartPane::Certificate(address)) => window.update(Message::ShowCertificate(address)),
+            Some(StartPane::Diagnose(address)) => window.update(Message::Diagnose(address)),
+            None => Command::none(),
+        };
+        (window, command)
+    }
+
+    fn title(&self) -> String { tr("config.title").to_string() }
+
+    fn theme(&self) -> Theme {
+        let dark = match self.config.theme {
+            ThemeChoice::System => self.system_dark,
+            ThemeChoice::Light => false,
+            ThemeChoice::Dark => true,
+        };
+        if dark { Theme::Dark } else { Theme::Light }
+    }
+
+    fn subscription(&self) -> iced::Subscription<Message> {
+        let events = iced::event::listen_with(|event, status| match event {
+            iced::Event::Keyboard(iced::keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                use iced::keyboard::{key::Named, Key};
+                // Ctrl+F vale mesmo com um campo em foco; as demais teclas só
+                // quando nenhum campo de texto as consumiu (Esc primeiro tira o foco)
+                let free = status == iced::event::Status::Ignored;
+                match key.as_ref() {
+                    Key::Character("f") if modifiers.command() => Some(Message::FocusFilter),
+                    Key::Named(Named::Escape) if free => Some(Message::EscapePressed),
+                    Key::Named(Named::Tab) if free => Some(Message::FocusNext(!modifiers.shift())),
+                    Key::Named(Named::ArrowDown) if free => Some(Message::MoveSelection(1)),
+                    Key::Named(Named::ArrowUp) if free => Some(Message::MoveSelection(-1)),
+                    Key::Named(Named::Delete) if free => Some(Message::RemoveSelected),
+                    _ => None,
+                }
+            }
+            iced::Event::Window(_, window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+            iced::Event::Window(_, window::Event::Resized { width, height }) => Some(Message::WindowResized(width, height)),
+            iced::Event::Window(_, window::Event::CloseRequested) => Some(Message::CloseRequested),
+            _ => None,
+        });
+        if self.watch.is_some() {
+            return iced::Subscription::batch([
+                events,
+                iced::time::every(Duration::from_secs(WATCH_INTERVAL_SECS)).map(|_| Message::WatchTick),
+            ]);
+        }
+        // A aba de status é atualizada periodicamente apenas enquanto está aberta
+        if self.tab == ConfigTab::Status && self.config.api.is_some() {
+            iced::Subscription::batch([
+                events,
+                iced::time::every(Duration::from_secs(LIVE_STATUS_REFRESH_SECS)).map(|_| Message::RefreshStatus),
+            ])
+        } else {
+            events
+        }
+    }
+
+    fn update(&mut self, message: Message) -> Command<Message> {
+        match message {
+            Message::InputChanged(val) => {
+                self.input_value = val;
+            },
+            Message::AddSite => {
+                let trimmed = self.input_value.trim();
+                debug!("==> AddSite acionado. Valor: '{}'", trimmed);
+                if let Some(cleaned) = normalize_target(trimmed) {
+                    debug!("==> Adicionando site limpo: '{}'", cleaned);
+                    self.config.targets.push(TargetConfig::new(&cleaned));
+                    self.input_value.clear();
+                    save_config(&self.config);
+                    debug!("==> Site adicionado com sucesso. Total: {}", self.config.targets.len());
+                } else {
+                    debug!("==> Valor vazio ou inválido, não adicionando");
+                }
+            },
+            Message::RemoveSite(idx) => {
+                if idx < self.config.targets.len() {
+                    let removed = self.config.targets.remove(idx);
+                    debug!("==> Removido site: {}", removed.address);
+                    save_config(&self.config);
+                    self.last_removed = Some((idx, removed));
+                    self.selected = None;
+                }
+            },
+            Message::SaveAndClose => {
+                debug!("==> SaveAndClose acionado");
+                save_config(&self.config);
+                self.geometry.save();
+                return window::close(window::Id::MAIN);
+            }
+            Message::UndoRemove => {
+                if let Some((idx, target)) = self.last_removed.take() {
+                    debug!("==> Desfazendo remoção: {}", target.address);
+                    let idx = idx.min(self.config.targets.len());
+                    self.config.targets.insert(idx, target);
+                    save_config(&self.config);
+                }
+            }
+            Message::DismissUndo => self.last_removed = None,
+            Message::EscapePressed => {
+                if self.diagnosis.is_some() {
+                    self.diagnosis = None;
+                } else if self.certificate.is_some() {
+                    self.certificate = None;
+                } else if self.watch.is_some() {
+                    self.watch = None;
+                } else if self.editor.is_some() {
+                    self.editor = None;
+                } else {
+                    return self.update(Message::CloseRequested);
+                }
+            }
+            Message::EditTarget(idx) => {
+                if let Some(target) = self.config.targets.get(idx) {
+                    self.editor = Some(TargetEditor::new(idx, target));
+                }
+            }
+            Message::EditorChanged(field, raw) => {
+                if let Some(editor) = &mut self.editor {
+                    editor.values.insert(field, raw);
+                }
+            }
+            Message::EditorNotifyToggled(enabled) => {
+                if let Some(editor) = &mut self.editor {
+                    editor.notify = enabled;
+                }
+            }
+            Message::EditorSave => {
+                if let Some(editor) = &mut self.editor {
+                    let Some(target) = self.config.targets.get_mut(editor.index) else {
+                        self.editor = None;
+                        return Command::none();
+                    };
+                    // Edita uma cópia para não deixar o destino pela metade em caso de erro
+                    let mut updated = target.clone();
+                    match editor.apply(&mut updated) {
+                        Ok(()) => {
+                            debug!("==> Destino editado: {}", updated.address);
+                            *target = updated;
+                            save_config(&self.config);
+                            self.editor = None;
+                        }
+                        Err(e) => editor.error = Some(e),
+                    }
+                }
+            }
+            Message::EditorCancel => self.editor = None,
+            Message::WatchTarget(address) => {
+                self.watch = Some(WatchView::new(address));
+                return self.update(Message::WatchTick);
+            }
+            Message::WatchTick => {
+                let Some(watch) = self.watch.as_mut().filter(|w| !w.pending) else {
+                    return Command::none();
+                };
+                watch.pending = true;
+                let address = watch.address.clone();
+                let timeout = self.config.http_timeout();
+                // Mesmo esquema do "Testar": a checagem bloqueante roda fora do runtime
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                let target = address.clone();
+                thread::spawn(move || {
+                    let _ = tx.send(test_target(&target, 1, 1, timeout, None));
+                });
+                return Command::perform(
+                    async move {
+                        let outcome = rx.await.unwrap_or_else(|_| CheckOutcome::down(tr("check.error")));
+                        (address, outcome)
+                    },
+                    |(address, outcome)| Message::WatchSample(address, outcome),
+                );
+            }
+            Message::WatchSample(address, outcome) => {
+                // Descarta respostas de um acompanhamento já fechado ou trocado
+                if let Some(watch) = self.watch.as_mut().filter(|w| w.address == address) {
+                    watch.record(outcome);
+                }
+            }
+            Message::WatchClose => self.watch = None,
+            Message::ShowCertificate(address) => {
+                self.certificate = Some(CertView { address: address.clone(), report: None });
+                let timeout = self.config.http_timeout();
+                // Handshake bloqueante, na mesma linha do "Testar"
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                let target = address.clone();
+                thread::spawn(move || {
+                    let _ = tx.send(fetch_certificate_chain(&target, timeout));
+                });
+                return Command::perform(
+                    async move {
+                        let report = rx.await.unwrap_or_else(|_| Err(tr("check.error").to_string()));
+                        (address, report)
+                    },
+                    |(address, report)| Message::CertificateLoaded(address, report),
+                );
+            }
+            Message::CertificateLoaded(address, report) => {
+                if let Some(view) = self.certificate.as_mut().filter(|v| v.address == address) {
+                    view.report = Some(report);
+                }
+            }
+            Message::CloseCertificate => self.certificate = None,
+            Message::Diagnose(address) => {
+                self.diagnosis = Some(DiagnoseView { address: address.clone(), report: None, copied: false });
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                let target = address.clone();
+                let config = self.config.clone();
+                thread::spawn(move || {
+                    let sections = diagnose_target(&target, &config);
+                    let _ = tx.send(render_diagnostics(&target, &sections));
+                });
+                return Command::perform(
+                    async move {
+                        let report = rx.await.unwrap_or_else(|_| tr("check.error").to_string());
+                        (address, report)
+                    },
+                    |(address, report)| Message::DiagnosisLoaded(address, report),
+                );
+            }
+            Message::DiagnosisLoaded(address, report) => {
+                if let Some(view) = self.diagnosis.as_mut().filter(|v| v.address == address) {
+                    view.report = Some(report);
+                }
+            }
+            Message::CopyDiagnosis => {
+                if let Some(view) = self.diagnosis.as_mut() {
+                    if let Some(report) = &view.report {
+                        view.copied = true;
+                        return iced::clipboard::write(report.clone());
+                    }
+                }
+            }
+            Message::CloseDiagnosis => self.diagnosis = None,
+            Message::ExportSupportBundle => {
+                self.support_bundle = Some(None);
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                thread::spawn(move || {
+                    let path = default_support_bundle_path();
+                    let _ = tx.send(write_support_bundle(&path).map(|()| path));
+                });
+                return Command::perform(
+                    async move { rx.await.unwrap_or_else(|_| Err(tr("check.error").to_string())) },
+                    Message::SupportBundleSaved,
+                );
+            }
+            Message::SupportBundleSaved(result) => self.support_bundle = Some(Some(result)),
+            Message::FocusFilter => {
+                self.tab = ConfigTab::Targets;
+                return text_input::focus(text_input::Id::new(FILTER_INPUT_ID));
+            }
+            Message::FocusNext(forward) => {
+                return if forward { iced::widget::focus_next() } else { iced::widget::focus_previous() };
+            }
+            Message::MoveSelection(delta) => {
+                let visible = self.visible_targets();
+                if self.tab != ConfigTab::Targets || visible.is_empty() {
+                    return Command::none();
+                }
+                let current = self.selected.and_then(|sel| visible.iter().position(|&i| i == sel));
+                let next = match current {
+                    Some(pos) => (pos as i64 + delta as i64).clamp(0, visible.len() as i64 - 1) as usize,
+                    None if delta < 0 => visible.len() - 1,
+                    None => 0,
+                };
+                self.selected = Some(visible[next]);
+            }
+            Message::RemoveSelected => {
+                if let Some(idx) = self.selected.take().filter(|_| self.tab == ConfigTab::Targets && self.editor.is_none()) {
+                    return self.update(Message::RemoveSite(idx));
+                }
+            }
+            Message::WindowMoved(x, y) => {
+                self.geometry.x = Some(x);
+                self.geometry.y = Some(y);
+            }
+            Message::WindowResized(width, height) => {
+                self.geometry.width = width as f32;
+                self.geometry.height = height as f32;
+            }
+            Message::CloseRequested => {
+                self.geometry.save();
+                return window::close(window::Id::MAIN);
+            }
+            Message::RestoreBackup => {
+                match restore_config_backup() {
+                    Ok(cfg) => {
+                        self.config = cfg;
+                        self.load_error = None;
+                    }
+                    Err(e) => self.load_error = Some(trf("config.restore_failed", &[("error", &e)])),
+                }
+                self.drafts.clear();
+                self.invalid.clear();
+            }
+            Message::TabSelected(tab) => {
+                self.tab = tab;
+                if tab == ConfigTab::Status {
+                    return self.update(Message::RefreshStatus);
+                }
+                if tab == ConfigTab::History {
+                    let now = Local::now().timestamp();
+                    self.history = Some(HistoryStore::open().state_events(now - HISTORY_RETENTION_SECS, now + 1));
+                }
+            }
+            Message::RefreshStatus => {
+                if let Some(api) = self.config.api.clone() {
+                    return Command::perform(fetch_live_status(api), Message::StatusLoaded);
+                }
+            }
+            Message::StatusLoaded(result) => self.live_status = Some(result),
+            Message::HistoryHostSelected(choice) => {
+                self.history_host = (choice != tr("history.all_targets")).then_some(choice);
+            }
+            Message::HistoryDateChanged(raw) => self.history_date = raw,
+            Message::TestTarget(address) => {
+                let address = address.trim().to_string();
+                if address.is_empty() || self.test_results.get(&address).is_some_and(Option::is_none) {
+                    return Command::none();
+                }
+                let configured = self.config.targets.iter().find(|t| t.address == address);
+                let (attempts, probes) = configured.map_or((PING_ATTEMPTS, 1), |t| (t.attempts(), t.probes()));
+                let target_timeout = configured.and_then(TargetConfig::timeout);
+                // Destinos já configurados respeitam o tipo de checagem forçado
+                let probe = configured.map_or_else(|| Ok(address.clone()), TargetConfig::probe_address);
+                let timeout = self.config.http_timeout();
+                self.test_results.insert(address.clone(), None);
+                // A checagem bloqueia (ping/HTTP síncronos) e o cliente HTTP bloqueante
+                // não pode viver dentro do runtime, então ela roda numa thread própria
+                let (tx, rx) = tokio::sync::oneshot::channel();
+                thread::spawn(move || {
+                    let outcome = match probe {
+                        Ok(target) => test_target(&target, attempts, probes, timeout, target_timeout),
+                        Err(e) => CheckOutcome::down(&e),
+                    };
+                    let _ = tx.send(outcome);
+                });
+                return Command::perform(
+                    async move {
+                        let outcome = rx.await.unwrap_or_else(|_| CheckOutcome::down(tr("check.error")));
+                        (address, outcome)
+                    },
+                    |(address, outcome)| Message::TestFinished(address, outcome),
+                );
+            }
+            Message::TestFinished(address, outcome) => {
+                self.test_results.insert(address, Some(outcome));
+            }
+            Message::FilterChanged(raw) => self.target_filter = raw,
+            Message::ThemeSelected(choice) => self.config.theme = choice,
+            Message::ToggleBulk => {
+                self.bulk_input = match self.bulk_input {
+                    Some(_) => None,
+                    None => Some(text_editor::Content::new()),
+                };
+                self.bulk_summary = None;
+            }
+            Message::BulkEdited(action) => {
+                if let Some(content) = &mut self.bulk_input {
+                    content.perform(action);
+                }
+            }
+            Message::AddBulk => {
+                let Some(content) = &self.bulk_input else {
+                    return Command::none();
+                };
+                let import = parse_target_list(&content.text(), &self.config.targets);
+                debug!(
+                    "==> AddBulk: {} novos, {} repetidos, {} inválidos",
+                    import.added.len(),
+                    import.duplicates,
+                    import.invalid.len()
+                );
+                self.config.targets.extend(import.added.iter().map(|address| TargetConfig::new(address)));
+                if !import.added.is_empty() {
+                    save_config(&self.config);
+                }
+                let mut summary = trf(
+                    "config.bulk_summary",
+                    &[("added", &import.added.len()), ("duplicates", &import.duplicates), ("invalid", &import.invalid.len())],
+                );
+                if !import.invalid.is_empty() {
+                    summary.push_str(&trf("config.bulk_invalid", &[("entries", &import.invalid.join(", "))]));
+                }
+                self.bulk_summary = Some(summary);
+                // Mantém só as linhas inválidas no painel para correção
+                self.bulk_input = Some(text_editor::Content::with_text(&import.invalid.join("\n")));
+            }
+            Message::TogglePresets => {
+                self.presets = match self.presets {
+                    Some(_) => None,
+                    None => Some(target_presets()),
+                };
+            }
+            Message::AddPreset(idx) => {
+                let Some(preset) = self.presets.as_ref().and_then(|p| p.get(idx)) else {
+                    return Command::none();
+                };
+                if !self.config.targets.iter().any(|t| t.address == preset.address) {
+                    debug!("==> AddPreset: {}", preset.address);
+                    let mut target = TargetConfig::new(&preset.address);
+                    target.tags = vec![preset.tag.to_string()];
+                    self.config.targets.push(target);
+                    save_config(&self.config);
+                }
+            }
+            Message::SettingChanged(field, raw) => {
+                if field.apply(&mut self.config, &raw) {
+                    self.invalid.remove(&field);
+                } else {
+                    self.invalid.insert(field);
+                }
+                self.drafts.insert(field, raw);
+            }
+            Message::DesktopToggled(on) => self.config.desktop.enabled = on,
+            Message::GroupingToggled(on) => self.config.group_notifications = on,
+            Message::NotifierToggled(name, on) => {
+                self.config.disabled_notifiers.retain(|n| n != name);
+                if !on {
+                    self.config.disabled_notifiers.push(name.to_string());
+                }
+            }
+        }
+        Command::none()
+    }
+
+    fn view(&self) -> Element<'_, Message> {
+        let tab_button = |label: &'static str, tab: ConfigTab| {
+            let style = if self.tab == tab { iced::theme::Button::Primary } else { iced::theme::Button::Secondary };
+            button(tr(label)).on_press(Message::TabSelected(tab)).style(style).padding(8)
+        };
+        let tabs = row![
+            tab_button("config.tab_targets", ConfigTab::Targets),
+            tab_button("config.tab_settings", ConfigTab::Settings),
+            tab_button("config.tab_notifications", ConfigTab::Notifications),
+            tab_button("config.tab_status", ConfigTab::Status),
+            tab_button("config.tab_history", ConfigTab::History),
+        ].spacing(6);
+
+        let mut content = column![text(tr("config.heading")).size(26)].spacing(20).padding(20);
+
+        if let Some(version) = self.newer_version {
+            let notice = trf("config.newer_version", &[("version", &version), ("supported", &CONFIG_VERSION)]);
+            content = content.push(container(text(format!("⚠️ {}", notice)).size(14)).padding(10).style(iced::theme::Container::Box));
+        }
+
+        if let Some(err) = &self.load_error {
+            content = content.push(
+                container(
+                    column![
+                        text(format!("⚠️ {}", err)).size(14),
+                        text(tr("config.backup_notice")).size(12),
+                        button(tr("config.restore_backup")).on_press(Message::RestoreBackup),
+                    ].spacing(8)
+                )
+                .padding(10)
+                .style(iced::theme::Container::Box)
+            );
+        }
+
+        if let Some(view) = &self.diagnosis {
+            let content = content.push(self.diagnose_view(view));
+            return container(content).width(Length::Fill).height(Length::Fill).into();
+        }
+
+        if let Some(view) = &self.certificate {
+            let content = content.push(self.certificate_view(view));
+            return container(content).width(Length::Fill).height(Length::Fill).into();
+        }
+
+        if let Some(watch) = &self.watch {
+            let content = content.push(self.watch_view(watch));
+            return container(content).width(Length::Fill).height(Length::Fill).into();
+        }
+
+        if let Some(editor) = &self.editor {
+            let content = content.extend([self.editor_view(editor), self.save_button()]);
+            return container(content).width(Length::Fill).height(Length::Fill).into();
+        }
+
+        let body = match self.tab {
+            ConfigTab::Targets => self.targets_tab(),
+            ConfigTab::Settings => self.settings_tab(),
+            ConfigTab::Notifications => self.notifications_tab(),
+            ConfigTab::Status => self.status_tab(),
+            ConfigTab::History => self.history_tab(),
+        };
+        let content = content.extend([
+            tabs.into(),
+            container(body).height(Length::Fill).into(),
+            self.save_button(),
+        ]);
+
+        container(content).width(Length::Fill).height(Length::Fill).into()
+    }
+}
@@ -0,0 +1,508 @@
+//! Janela de configuração (iced): destinos, ajustes, notificações, status
+//! ao vivo, histórico e os painéis abertos pela bandeja.
+
+use super::*;
+use iced::widget::text_editor;
+
+mod app;
+mod panes;
+
+const LIVE_STATUS_REFRESH_SECS: u64 = 5;
+const HISTORY_VIEW_LIMIT: usize = 500;
+const FILTER_INPUT_ID: &str = "target-filter";
+const WATCH_INTERVAL_SECS: u64 = 1;
+/// Amostras mantidas no gráfico do acompanhamento ao vivo (1 por segundo).
+const WATCH_SAMPLES: usize = 60;
+
+/// Abre a janela com a geometria da última sessão, opcionalmente já no painel `start`.
+pub(crate) fn run(start: Option<StartPane>) {
+    let geometry = WindowGeometry::load();
+    let mut settings = Settings::with_flags(ConfigFlags { geometry, start });
+    settings.window = iced::window::Settings {
+        size: iced::Size::new(geometry.width, geometry.height),
+        position: match (geometry.x, geometry.y) {
+            (Some(x), Some(y)) => window::Position::Specific(iced::Point::new(x as f32, y as f32)),
+            _ => window::Position::Default,
+        },
+        // O fechamento passa pela janela para gravar a geometria antes
+        exit_on_close_request: false,
+        ..Default::default()
+    };
+    ConfigWindow::run(settings).unwrap();
+}
+
+/// Tamanho e posição da janela de configuração entre execuções. A posição
+/// fica vazia no Wayland, que não a informa aos clientes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+struct WindowGeometry {
+    width: f32,
+    height: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    x: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    y: Option<i32>,
+}
+
+impl WindowGeometry {
+    const DEFAULT: WindowGeometry = WindowGeometry { width: 560.0, height: 600.0, x: None, y: None };
+    const MIN_SIZE: f32 = 200.0;
+
+    fn path() -> PathBuf {
+        get_data_dir().join("window.json")
+    }
+
+    fn load() -> Self {
+        fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|raw| serde_json::from_str::<WindowGeometry>(&raw).ok())
+            .filter(|g| g.width >= Self::MIN_SIZE && g.height >= Self::MIN_SIZE)
+            .unwrap_or(Self::DEFAULT)
+    }
+
+    fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| fs::write(Self::path(), raw).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("Falha ao gravar a geometria da janela: {}", e);
+        }
+    }
+}
+
+/// Preferência de cor do desktop: o modo do COSMIC, depois o `color-scheme`
+/// do GNOME e por fim o sufixo `:dark` de `GTK_THEME`.
+pub(crate) fn system_prefers_dark() -> bool {
+    let cosmic_mode = directories::BaseDirs::new()
+        .map(|dirs| dirs.config_dir().join("cosmic/com.system76.CosmicTheme.Mode/v1/is_dark"));
+    if let Some(raw) = cosmic_mode.and_then(|path| fs::read_to_string(path).ok()) {
+        return raw.trim() == "true";
+    }
+    let gnome = SysCommand::new("gsettings")
+        .args(["get", "org.gnome.desktop.interface", "color-scheme"])
+        .output()
+        .ok()
+        .filter(|out| out.status.success());
+    if let Some(out) = gnome {
+        return String::from_utf8_lossy(&out.stdout).contains("dark");
+    }
+    std::env::var("GTK_THEME").is_ok_and(|theme| theme.ends_with(":dark"))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigTab {
+    Targets,
+    Settings,
+    Notifications,
+    Status,
+    History,
+}
+
+/// Campos numéricos editáveis na janela, com o texto digitado mantido à parte
+/// até virar um valor válido.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum NumericSetting {
+    Interval,
+    HttpTimeout,
+    OutageCheck,
+    CheckBudget,
+    FailThreshold,
+    ParallelChecks,
+    LatencyFactor,
+    RecentSamples,
+    MaxPerHour,
+}
+
+impl NumericSetting {
+    const SETTINGS_TAB: [NumericSetting; 8] = [
+        NumericSetting::Interval,
+        NumericSetting::HttpTimeout,
+        NumericSetting::OutageCheck,
+        NumericSetting::CheckBudget,
+        NumericSetting::FailThreshold,
+        NumericSetting::ParallelChecks,
+        NumericSetting::LatencyFactor,
+        NumericSetting::RecentSamples,
+    ];
+
+    fn label(self) -> &'static str {
+        tr(match self {
+            NumericSetting::Interval => "config.interval",
+            NumericSetting::HttpTimeout => "config.http_timeout",
+            NumericSetting::OutageCheck => "config.outage_check",
+            NumericSetting::CheckBudget => "config.check_budget",
+            NumericSetting::FailThreshold => "config.fail_threshold",
+            NumericSetting::ParallelChecks => "config.parallel",
+            NumericSetting::LatencyFactor => "config.latency_factor",
+            NumericSetting::RecentSamples => "config.recent_samples",
+            NumericSetting::MaxPerHour => "config.max_per_hour",
+        })
+    }
+
+    fn read(self, cfg: &AppConfig) -> String {
+        match self {
+            NumericSetting::Interval => cfg.interval_secs.to_string(),
+            NumericSetting::HttpTimeout => cfg.http_timeout_secs.to_string(),
+            NumericSetting::OutageCheck => cfg.outage_check_interval.to_string(),
+            NumericSetting::CheckBudget => cfg.check_budget_secs.to_string(),
+            NumericSetting::FailThreshold => cfg.fail_threshold.to_string(),
+            NumericSetting::ParallelChecks => cfg.max_parallel_checks.to_string(),
+            NumericSetting::LatencyFactor => cfg.latency_alert_factor.to_string(),
+            NumericSetting::RecentSamples => cfg.recent_samples.to_string(),
+            NumericSetting::MaxPerHour => cfg.max_notifications_per_hour.to_string(),
+        }
+    }
+
+    /// Grava o valor na configuração; retorna `false` se o texto for inválido.
+    fn apply(self, cfg: &mut AppConfig, raw: &str) -> bool {
+        let raw = raw.trim();
+        let positive = || raw.parse::<u64>().ok().filter(|v| *v > 0);
+        match self {
+            NumericSetting::Interval => positive().map(|v| cfg.interval_secs = v).is_some(),
+            NumericSetting::HttpTimeout => positive().map(|v| cfg.http_timeout_secs = v).is_some(),
+            NumericSetting::OutageCheck => raw.parse().map(|v| cfg.outage_check_interval = v).is_ok(),
+            NumericSetting::CheckBudget => positive().map(|v| cfg.check_budget_secs = v).is_some(),
+            NumericSetting::FailThreshold => {
+                raw.parse::<u8>().ok().filter(|v| *v > 0).map(|v| cfg.fail_threshold = v).is_some()
+            }
+            NumericSetting::ParallelChecks => {
+                raw.parse::<usize>().ok().filter(|v| *v > 0).map(|v| cfg.max_parallel_checks = v).is_some()
+            }
+            NumericSetting::LatencyFactor => raw
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .map(|v| cfg.latency_alert_factor = v)
+                .is_some(),
+            NumericSetting::RecentSamples => raw
+                .parse::<usize>()
+                .ok()
+                .filter(|v| *v >= SPARKLINE_SAMPLES)
+                .map(|v| cfg.recent_samples = v)
+                .is_some(),
+            NumericSetting::MaxPerHour => raw.parse().map(|v| cfg.max_notifications_per_hour = v).is_ok(),
+        }
+    }
+}
+
+/// Campos do editor avançado de um destino.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum EditorField {
+    Address,
+    Tags,
+    Sla,
+    Attempts,
+    Probes,
+    Check,
+    Checks,
+    Policy,
+    DegradedMs,
+    TimeoutMs,
+    FailThreshold,
+    MaxPerHour,
+    Schedule,
+    DisabledNotifiers,
+    Notifiers,
+    Urgency,
+    KumaPushUrl,
+}
+
+impl EditorField {
+    const ALL: [EditorField; 17] = [
+        EditorField::Address,
+        EditorField::Tags,
+        EditorField::Sla,
+        EditorField::Attempts,
+        EditorField::Probes,
+        EditorField::Check,
+        EditorField::Checks,
+        EditorField::Policy,
+        EditorField::DegradedMs,
+        EditorField::TimeoutMs,
+        EditorField::FailThreshold,
+        EditorField::MaxPerHour,
+        EditorField::Schedule,
+        EditorField::DisabledNotifiers,
+        EditorField::Notifiers,
+        EditorField::Urgency,
+        EditorField::KumaPushUrl,
+    ];
+
+    fn label(self) -> &'static str {
+        tr(match self {
+            EditorField::Address => "editor.address",
+            EditorField::Tags => "editor.tags",
+            EditorField::Sla => "editor.sla",
+            EditorField::Attempts => "editor.attempts",
+            EditorField::Probes => "editor.probes",
+            EditorField::Check => "editor.check",
+            EditorField::Checks => "editor.checks",
+            EditorField::Policy => "editor.policy",
+            EditorField::DegradedMs => "editor.degraded_ms",
+            EditorField::TimeoutMs => "editor.timeout_ms",
+            EditorField::FailThreshold => "editor.fail_threshold",
+            EditorField::MaxPerHour => "editor.max_per_hour",
+            EditorField::Schedule => "editor.schedule",
+            EditorField::DisabledNotifiers => "editor.disabled_notifiers",
+            EditorField::Notifiers => "editor.notifiers",
+            EditorField::Urgency => "editor.urgency",
+            EditorField::KumaPushUrl => "editor.kuma",
+        })
+    }
+}
+
+/// Formulário do editor avançado: os valores ficam como texto até salvar.
+struct TargetEditor {
+    /// Posição do destino na lista completa.
+    index: usize,
+    values: HashMap<EditorField, String>,
+    notify: bool,
+    error: Option<String>,
+}
+
+impl TargetEditor {
+    fn new(index: usize, target: &TargetConfig) -> Self {
+        let opt = |value: Option<String>| value.unwrap_or_default();
+        let values = HashMap::from([
+            (EditorField::Address, target.address.clone()),
+            (EditorField::Tags, target.tags.join(", ")),
+            (EditorField::Sla, opt(target.sla.map(|v| v.to_string()))),
+            (EditorField::Attempts, opt(target.attempts.map(|v| v.to_string()))),
+            (EditorField::Probes, opt(target.probes.map(|v| v.to_string()))),
+            (EditorField::Check, opt(target.check.clone())),
+            (EditorField::Checks, target.checks.join(", ")),
+            (EditorField::Policy, opt((!target.policy.is_all()).then(|| format!("{:?}", target.policy).to_lowercase()))),
+            (EditorField::DegradedMs, opt(target.degraded_ms.map(|v| v.to_string()))),
+            (EditorField::TimeoutMs, opt(target.timeout_ms.map(|v| v.to_string()))),
+            (EditorField::FailThreshold, opt(target.fail_threshold.map(|v| v.to_string()))),
+            (EditorField::MaxPerHour, opt(target.max_notifications_per_hour.map(|v| v.to_string()))),
+            (EditorField::Schedule, opt(target.schedule.clone())),
+            (EditorField::DisabledNotifiers, target.disabled_notifiers.join(", ")),
+            (EditorField::Notifiers, target.notifiers.join(", ")),
+            (EditorField::Urgency, opt(target.urgency.map(|v| format!("{:?}", v).to_lowercase()))),
+            (EditorField::KumaPushUrl, opt(target.kuma_push_url.clone())),
+        ]);
+        Self { index, values, notify: target.notify, error: None }
+    }
+
+    fn value(&self, field: EditorField) -> &str {
+        self.values.get(&field).map(String::as_str).unwrap_or_default()
+    }
+
+    /// Valida o formulário e grava os valores no destino. Campos vazios voltam ao padrão.
+    fn apply(&self, target: &mut TargetConfig) -> Result<(), String> {
+        fn number<T: std::str::FromStr>(field: EditorField, raw: &str) -> Result<Option<T>, String> {
+            let raw = raw.trim();
+            if raw.is_empty() {
+                return Ok(None);
+            }
+            raw.parse()
+                .map(Some)
+                .map_err(|_| trf("editor.invalid_number", &[("field", &field.label())]))
+        }
+        let list = |raw: &str| -> Vec<String> {
+            raw.split(',').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect()
+        };
+        let text_value = |field: EditorField| Some(self.value(field).trim().to_string()).filter(|v| !v.is_empty());
+
+        let address = normalize_target(self.value(EditorField::Address)).ok_or_else(|| tr("editor.empty_address").to_string())?;
+        let sla: Option<f64> = number(EditorField::Sla, self.value(EditorField::Sla))?;
+        if sla.is_some_and(|v| !(0.0..=100.0).contains(&v)) {
+            return Err(tr("editor.invalid_sla").to_string());
+        }
+        let urgency = match text_value(EditorField::Urgency) {
+            Some(raw) => Some(Severity::parse(&raw).ok_or_else(|| tr("editor.invalid_urgency").to_string())?),
+            None => None,
+        };
+        let policy = match text_value(EditorField::Policy) {
+            Some(raw) => CheckPolicy::parse(&raw).ok_or_else(|| tr("editor.invalid_policy").to_string())?,
+            None => CheckPolicy::All,
+        };
+        let schedule = text_value(EditorField::Schedule);
+        if let Some(expr) = &schedule {
+            CronSchedule::parse(expr).map_err(|e| trf("editor.invalid_schedule", &[("error", &e)]))?;
+        }
+
+        target.address = address;
+        target.tags = list(self.value(EditorField::Tags));
+        target.sla = sla;
+        target.attempts = number(EditorField::Attempts, self.value(EditorField::Attempts))?;
+        target.probes = number(EditorField::Probes, self.value(EditorField::Probes))?;
+        target.check = text_value(EditorField::Check).map(|kind| kind.to_lowercase());
+        target.probe_address()?;
+        target.checks = list(self.value(EditorField::Checks));
+        target.policy = policy;
+        target.degraded_ms = number(EditorField::DegradedMs, self.value(EditorField::DegradedMs))?;
+        target.timeout_ms = number(EditorField::TimeoutMs, self.value(EditorField::TimeoutMs))?;
+        target.fail_threshold = number(EditorField::FailThreshold, self.value(EditorField::FailThreshold))?;
+        target.max_notifications_per_hour = number(EditorField::MaxPerHour, self.value(EditorField::MaxPerHour))?;
+        target.schedule = schedule;
+        target.disabled_notifiers = list(self.value(EditorField::DisabledNotifiers));
+        target.notifiers = list(self.value(EditorField::Notifiers));
+        target.notify = self.notify;
+        target.urgency = urgency;
+        target.kuma_push_url = text_value(EditorField::KumaPushUrl);
+        Ok(())
+    }
+}
+
+/// Acompanhamento ao vivo de um destino: uma checagem por segundo enquanto
+/// o painel está aberto, sem passar pelo monitor nem gravar histórico.
+struct WatchView {
+    address: String,
+    /// Latências mais recentes no fim; `None` é uma checagem sem resposta.
+    samples: VecDeque<Option<f64>>,
+    last: Option<CheckOutcome>,
+    sent: u32,
+    received: u32,
+    /// Evita sobrepor checagens quando uma demora mais que o intervalo.
+    pending: bool,
+}
+
+impl WatchView {
+    fn new(address: String) -> Self {
+        WatchView { address, samples: VecDeque::new(), last: None, sent: 0, received: 0, pending: false }
+    }
+
+    fn record(&mut self, outcome: CheckOutcome) {
+        self.pending = false;
+        self.sent += 1;
+        if outcome.success {
+            self.received += 1;
+        }
+        if self.samples.len() == WATCH_SAMPLES {
+            self.samples.pop_front();
+        }
+        // Destinos sem latência medida (HTTP "OK") contam como 0 no gráfico
+        self.samples.push_back(outcome.success.then(|| outcome.latency_ms.unwrap_or(0.0)));
+        self.last = Some(outcome);
+    }
+
+    /// Resumo "mín/méd/máx" das respostas que estão no gráfico.
+    fn stats(&self) -> Option<(f64, f64, f64)> {
+        let values: Vec<f64> = self.samples.iter().flatten().copied().collect();
+        if values.is_empty() {
+            return None;
+        }
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(0.0, f64::max);
+        Some((min, values.iter().sum::<f64>() / values.len() as f64, max))
+    }
+}
+
+/// Parâmetros da janela de configuração vindos da linha de comando.
+struct ConfigFlags {
+    geometry: WindowGeometry,
+    start: Option<StartPane>,
+}
+
+/// Painel "Certificado": a cadeia TLS do destino, carregada ao abrir.
+struct CertView {
+    address: String,
+    /// `None` enquanto o handshake não termina.
+    report: Option<Result<CertReport, String>>,
+}
+
+/// Painel "Diagnosticar": relatório de ping, DNS, rota e HTTP de um destino.
+struct DiagnoseView {
+    address: String,
+    /// `None` enquanto o diagnóstico roda.
+    report: Option<String>,
+    copied: bool,
+}
+
+struct ConfigWindow {
+    config: AppConfig,
+    input_value: String,
+    load_error: Option<String>,
+    /// Versão do `sites.json` quando ela é mais nova que a suportada: nada é salvo.
+    newer_version: Option<u32>,
+    tab: ConfigTab,
+    drafts: HashMap<NumericSetting, String>,
+    invalid: HashSet<NumericSetting>,
+    /// Última resposta da instância em execução; `None` até a primeira consulta.
+    live_status: Option<Result<LiveStatus, String>>,
+    /// Eventos reconstruídos do histórico; carregados ao abrir a aba.
+    history: Option<Vec<StateEvent>>,
+    /// Destino escolhido no filtro; `None` mostra todos.
+    history_host: Option<String>,
+    history_date: String,
+    /// Resultado do botão "Testar" por endereço; `None` enquanto a checagem roda.
+    test_results: HashMap<String, Option<CheckOutcome>>,
+    /// Área de colagem em lote; `Some` enquanto o painel está aberto.
+    bulk_input: Option<text_editor::Content>,
+    bulk_summary: Option<String>,
+    /// Destinos prontos, detectados ao abrir o painel.
+    presets: Option<Vec<Preset>>,
+    /// Filtro da lista de destinos (trecho do endereço ou de uma tag).
+    target_filter: String,
+    /// Preferência escura do desktop, lida uma vez ao abrir a janela.
+    system_dark: bool,
+    geometry: WindowGeometry,
+    /// Último destino removido e sua posição, para o "Desfazer".
+    last_removed: Option<(usize, TargetConfig)>,
+    /// Linha escolhida pelo teclado (setas) na lista completa de destinos.
+    selected: Option<usize>,
+    /// Editor avançado aberto; ocupa o lugar das abas enquanto existir.
+    editor: Option<TargetEditor>,
+    /// Acompanhamento ao vivo aberto; também ocupa o lugar das abas.
+    watch: Option<WatchView>,
+    certificate: Option<CertView>,
+    diagnosis: Option<DiagnoseView>,
+    /// Resultado do último "Gerar pacote de suporte"; `Some(None)` enquanto grava.
+    support_bundle: Option<Option<Result<PathBuf, String>>>,
+}
+
+#[derive(Debug, Clone)]
+enum Message {
+    InputChanged(String),
+    AddSite,
+    RemoveSite(usize),
+    SaveAndClose,
+    RestoreBackup,
+    TabSelected(ConfigTab),
+    SettingChanged(NumericSetting, String),
+    DesktopToggled(bool),
+    GroupingToggled(bool),
+    NotifierToggled(&'static str, bool),
+    RefreshStatus,
+    StatusLoaded(Result<LiveStatus, String>),
+    HistoryHostSelected(String),
+    HistoryDateChanged(String),
+    TestTarget(String),
+    TestFinished(String, CheckOutcome),
+    ToggleBulk,
+    BulkEdited(text_editor::Action),
+    AddBulk,
+    TogglePresets,
+    AddPreset(usize),
+    FilterChanged(String),
+    ThemeSelected(ThemeChoice),
+    WindowMoved(i32, i32),
+    WindowResized(u32, u32),
+    CloseRequested,
+    UndoRemove,
+    DismissUndo,
+    FocusFilter,
+    FocusNext(bool),
+    MoveSelection(i32),
+    RemoveSelected,
+    EscapePressed,
+    EditTarget(usize),
+    EditorChanged(EditorField, String),
+    EditorNotifyToggled(bool),
+    EditorSave,
+    EditorCancel,
+    WatchTarget(String),
+    WatchTick,
+    WatchSample(String, CheckOutcome),
+    WatchClose,
+    ShowCertificate(String),
+    CertificateLoaded(String, Result<CertReport, String>),
+    CloseCertificate,
+    Diagnose(String),
+    DiagnosisLoaded(String, String),
+    CopyDiagnosis,
+    CloseDiagnosis,
+    ExportSupportBundle,
+    SupportBundleSaved(Result<PathBuf, String>),
+}
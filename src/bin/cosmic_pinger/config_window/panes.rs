@@ -0,0 +1,485 @@
+//! Abas e painéis da janela de configuração.
+
+use super::*;
+use iced::widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_editor, text_input, Space};
+
+impl ConfigWindow {
+    /// Canais remotos na ordem exibida: (nome usado em `disabled_notifiers`, rótulo, configurado).
+    pub(super) fn remote_notifiers(&self) -> [(&'static str, &'static str, bool); 6] {
+        let cfg = &self.config;
+        [
+            ("matrix", "Matrix", cfg.matrix.is_some()),
+            ("ntfy", "ntfy", cfg.ntfy.is_some()),
+            ("gotify", "Gotify", cfg.gotify.is_some()),
+            ("pushover", "Pushover", cfg.pushover.is_some()),
+            ("webhook", "Webhooks", !cfg.webhooks.is_empty()),
+            ("email", "E-mail", cfg.email.is_some()),
+        ]
+    }
+
+    /// Linha com o resultado do último teste do endereço, se houver.
+    pub(super) fn test_result_text(&self, address: &str) -> Option<Element<'_, Message>> {
+        let label = match self.test_results.get(address)? {
+            None => tr("config.testing").to_string(),
+            Some(outcome) => format!("{} {}", outcome_icon(outcome), outcome.message),
+        };
+        Some(text(label).size(12).into())
+    }
+
+    pub(super) fn numeric_row(&self, field: NumericSetting) -> Element<'_, Message> {
+        let value = self.drafts.get(&field).cloned().unwrap_or_else(|| field.read(&self.config));
+        let mut col = column![row![
+            text(field.label()).width(Length::Fill).size(14),
+            text_input("", &value)
+                .on_input(move |raw| Message::SettingChanged(field, raw))
+                .padding(6)
+                .width(Length::Fixed(90.0)),
+        ]
+        .spacing(10)
+        .align_items(iced::Alignment::Center)];
+        if self.invalid.contains(&field) {
+            col = col.push(text(tr("config.invalid_value")).size(12));
+        }
+        col.into()
+    }
+
+    pub(super) fn save_button(&self) -> Element<'_, Message> {
+        button(tr("config.save_close")).on_press(Message::SaveAndClose).padding(15).width(Length::Fill).into()
+    }
+
+    pub(super) fn editor_view(&self, editor: &TargetEditor) -> Element<'_, Message> {
+        let mut form = column![text(tr("editor.title")).size(20)].spacing(10);
+        for field in EditorField::ALL {
+            form = form.push(
+                row![
+                    text(field.label()).size(14).width(Length::FillPortion(2)),
+                    text_input("", editor.value(field))
+                        .on_input(move |raw| Message::EditorChanged(field, raw))
+                        .on_submit(Message::EditorSave)
+                        .padding(6)
+                        .width(Length::FillPortion(3)),
+                ]
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            );
+        }
+        form = form.push(checkbox(tr("editor.notify"), editor.notify).on_toggle(Message::EditorNotifyToggled));
+        form = form.push(text(tr("editor.hint")).size(12));
+        if let Some(err) = &editor.error {
+            form = form.push(text(format!("⚠️ {}", err)).size(14));
+        }
+        form = form.push(
+            row![
+                button(tr("editor.save")).on_press(Message::EditorSave).padding(8),
+                button(tr("editor.cancel")).on_press(Message::EditorCancel).style(iced::theme::Button::Secondary).padding(8),
+            ]
+            .spacing(10),
+        );
+        scrollable(form).height(Length::Fill).into()
+    }
+
+    /// Painel do acompanhamento ao vivo: gráfico de barras das últimas
+    /// latências (vermelho para perdas) e o resumo da sessão.
+    pub(super) fn watch_view(&self, watch: &WatchView) -> Element<'_, Message> {
+        const GRAPH_HEIGHT: f32 = 140.0;
+        let scale = watch.stats().map_or(1.0, |(_, _, max)| max.max(1.0));
+        let bars = watch.samples.iter().fold(row![].spacing(2).align_items(iced::Alignment::End), |bars, sample| {
+            let (height, color) = match sample {
+                Some(ms) => ((ms / scale) as f32 * GRAPH_HEIGHT, iced::Color::from_rgb8(0x3b, 0xa5, 0x5d)),
+                None => (GRAPH_HEIGHT, iced::Color::from_rgb8(0xd9, 0x48, 0x3b)),
+            };
+            bars.push(
+                container(Space::new(Length::Fixed(6.0), Length::Fixed(height.max(2.0))))
+                    .style(move |_: &Theme| container::Appearance { background: Some(color.into()), ..Default::default() }),
+            )
+        });
+
+        let status = match &watch.last {
+            None => tr("config.testing").to_string(),
+            Some(outcome) => format!("{} {}", outcome_icon(outcome), outcome.message),
+        };
+        let loss = if watch.sent == 0 { 0.0 } else { (watch.sent - watch.received) as f64 * 100.0 / watch.sent as f64 };
+        let mut summary = trf(
+            "watch.summary",
+            &[("sent", &watch.sent), ("received", &watch.received), ("loss", &format!("{:.0}", loss))],
+        );
+        if let Some((min, avg, max)) = watch.stats() {
+            summary.push_str(&trf(
+                "watch.latency",
+                &[("min", &format!("{:.1}", min)), ("avg", &format!("{:.1}", avg)), ("max", &format!("{:.1}", max))],
+            ));
+        }
+
+        column![
+            text(trf("watch.heading", &[("address", &watch.address)])).size(18),
+            text(status).size(14),
+            container(bars).height(Length::Fixed(GRAPH_HEIGHT)).width(Length::Fill).align_y(iced::alignment::Vertical::Bottom).padding(6).style(iced::theme::Container::Box),
+            text(summary).size(12),
+            button(tr("watch.close")).on_press(Message::WatchClose).padding(10),
+        ]
+        .spacing(12)
+        .into()
+    }
+
+    /// Painel "Certificado": veredito da validação e cada certificado da
+    /// cadeia com emissor, validade e, na folha, os nomes alternativos.
+    pub(super) fn certificate_view(&self, view: &CertView) -> Element<'_, Message> {
+        let mut col = column![text(trf("cert.heading", &[("address", &view.address)])).size(18)].spacing(12);
+        match &view.report {
+            None => col = col.push(text(tr("cert.loading")).size(14)),
+            Some(Err(e)) => col = col.push(text(format!("🔴 {}", e)).size(14)),
+            Some(Ok(report)) => {
+                col = col.push(text(match &report.verify_error {
+                    None => tr("cert.trusted").to_string(),
+                    Some(e) => trf("cert.untrusted", &[("error", e)]),
+                }).size(14));
+                let now = Local::now().timestamp();
+                let mut chain = column![].spacing(8);
+                for (i, cert) in report.chain.iter().enumerate() {
+                    let days = (cert.not_after - now) / DAY_SECS;
+                    let remaining = if days >= 0 {
+                        trf("cert.expires_in", &[("days", &days)])
+                    } else {
+                        trf("cert.expired", &[("days", &-days)])
+                    };
+                    let mut card = column![
+                        text(format!("{}. {}", i, cert.subject)).size(14),
+                        text(trf("cert.issuer", &[("issuer", &cert.issuer)])).size(12),
+                        text(trf(
+                            "cert.validity",
+                            &[
+                                ("from", &format_timestamp(cert.not_before, "%d/%m/%Y")),
+                                ("to", &format_timestamp(cert.not_after, "%d/%m/%Y %H:%M")),
+                                ("remaining", &remaining),
+                            ],
+                        ))
+                        .size(12),
+                    ]
+                    .spacing(4);
+                    if !cert.sans.is_empty() {
+                        card = card.push(text(trf("cert.sans", &[("names", &cert.sans.join(", "))])).size(12));
+                    }
+                    chain = chain.push(container(card).padding(8).width(Length::Fill).style(iced::theme::Container::Box));
+                }
+                col = col.push(scrollable(chain).height(Length::Fill));
+            }
+        }
+        col.push(button(tr("watch.close")).on_press(Message::CloseCertificate).padding(10)).into()
+    }
+
+    /// Painel "Diagnosticar": o relatório em texto, pronto para copiar.
+    pub(super) fn diagnose_view(&self, view: &DiagnoseView) -> Element<'_, Message> {
+        let mut col = column![text(trf("diag.heading", &[("address", &view.address)])).size(18)].spacing(12);
+        match &view.report {
+            None => col = col.push(text(tr("diag.running")).size(14)),
+            Some(report) => {
+                col = col.push(
+                    scrollable(container(text(report).size(12).font(iced::Font::MONOSPACE)).padding(8).width(Length::Fill))
+                        .height(Length::Fill),
+                );
+                if view.copied {
+                    col = col.push(text(tr("diag.copied")).size(12));
+                }
+            }
+        }
+        let mut copy = button(tr("diag.copy")).padding(10);
+        if view.report.is_some() {
+            copy = copy.on_press(Message::CopyDiagnosis);
+        }
+        col.push(row![copy, button(tr("watch.close")).on_press(Message::CloseDiagnosis).padding(10)].spacing(10)).into()
+    }
+
+    /// Índices (na lista completa) dos destinos que passam pelo filtro.
+    pub(super) fn visible_targets(&self) -> Vec<usize> {
+        let query = self.target_filter.trim().to_lowercase();
+        self.config
+            .targets
+            .iter()
+            .enumerate()
+            .filter(|(_, site)| {
+                query.is_empty()
+                    || site.address.to_lowercase().contains(&query)
+                    || site.tags.iter().any(|tag| tag.to_lowercase().contains(&query))
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub(super) fn targets_tab(&self) -> Element<'_, Message> {
+        let input_row = row![
+            text_input(tr("config.placeholder"), &self.input_value)
+                .on_input(Message::InputChanged)
+                .on_submit(Message::AddSite)
+                .padding(10)
+                .width(Length::Fill),
+            button(tr("config.test")).on_press(Message::TestTarget(self.input_value.clone())).padding(10),
+            button(tr("config.add")).on_press(Message::AddSite).padding(10)
+        ].spacing(10);
+        let mut input_col = column![input_row].spacing(6);
+        if let Some(result) = self.test_result_text(self.input_value.trim()) {
+            input_col = input_col.push(result);
+        }
+        input_col = input_col.push(
+            button(tr(if self.bulk_input.is_some() { "config.bulk_close" } else { "config.bulk_open" }))
+                .on_press(Message::ToggleBulk)
+                .style(iced::theme::Button::Text),
+        );
+        if let Some(content) = &self.bulk_input {
+            input_col = input_col.push(
+                text_editor(content)
+                    .on_action(Message::BulkEdited)
+                    .height(Length::Fixed(140.0))
+                    .padding(8),
+            );
+            input_col = input_col.push(button(tr("config.bulk_add")).on_press(Message::AddBulk).padding(8));
+        }
+        if let Some(summary) = &self.bulk_summary {
+            input_col = input_col.push(text(summary).size(12));
+        }
+        input_col = input_col.push(
+            button(tr(if self.presets.is_some() { "presets.close" } else { "presets.open" }))
+                .on_press(Message::TogglePresets)
+                .style(iced::theme::Button::Text),
+        );
+        if let Some(presets) = &self.presets {
+            let mut group = "";
+            for (i, preset) in presets.iter().enumerate() {
+                if preset.group != group {
+                    group = preset.group;
+                    input_col = input_col.push(text(tr(group)).size(14));
+                }
+                let monitored = self.config.targets.iter().any(|t| t.address == preset.address);
+                let action: Element<'_, Message> = if monitored {
+                    text(tr("presets.added")).size(12).into()
+                } else {
+                    button(tr("config.add")).on_press(Message::AddPreset(i)).padding(4).into()
+                };
+                input_col = input_col.push(
+                    row![text(format!("{} — {}", preset.name, preset.address)).size(13).width(Length::Fill), action]
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center),
+                );
+            }
+        }
+
+        let mut list_col = column![].spacing(10);
+        
+        let visible = self.visible_targets();
+        let mut count_label = trf("config.count", &[("count", &self.config.targets.len())]);
+        if !self.target_filter.trim().is_empty() {
+            count_label.push_str(&trf("config.count_filtered", &[("count", &visible.len())]));
+        }
+        let count_row = row![
+            text(count_label).size(14).width(Length::Fill),
+            text_input(tr("config.filter_placeholder"), &self.target_filter)
+                .id(text_input::Id::new(FILTER_INPUT_ID))
+                .on_input(Message::FilterChanged)
+                .padding(6)
+                .width(Length::Fixed(200.0)),
+        ]
+        .spacing(10)
+        .align_items(iced::Alignment::Center);
+
+        // Os índices continuam os da lista completa, para remover o destino certo
+        for i in visible {
+            let site = &self.config.targets[i];
+            let label = if self.selected == Some(i) { format!("▸ {}", site.address) } else { site.address.clone() };
+            let mut address_col = column![text(label).size(16)].spacing(4).width(Length::Fill);
+            if let Some(result) = self.test_result_text(&site.address) {
+                address_col = address_col.push(result);
+            }
+            list_col = list_col.push(
+                container(
+                    row![
+                        address_col,
+                        button(tr("config.test")).on_press(Message::TestTarget(site.address.clone())).style(iced::theme::Button::Secondary),
+                        button(tr("config.watch")).on_press(Message::WatchTarget(site.address.clone())).style(iced::theme::Button::Secondary),
+                    ]
+                    .push_maybe(site.address.starts_with("https://").then(|| {
+                        button(tr("config.certificate"))
+                            .on_press(Message::ShowCertificate(site.address.clone()))
+                            .style(iced::theme::Button::Secondary)
+                    }))
+                    .push(button(tr("config.diagnose")).on_press(Message::Diagnose(site.address.clone())).style(iced::theme::Button::Secondary))
+                    .push(button(tr("config.edit")).on_press(Message::EditTarget(i)).style(iced::theme::Button::Secondary))
+                    .push(button(tr("config.remove")).on_press(Message::RemoveSite(i)).style(iced::theme::Button::Destructive))
+                    .spacing(6)
+                    .align_items(iced::Alignment::Center)
+                )
+                .padding(10)
+                .style(iced::theme::Container::Box)
+            );
+        }
+
+        let mut col = column![input_col, count_row].spacing(20);
+        if let Some((_, removed)) = &self.last_removed {
+            col = col.push(
+                container(
+                    row![
+                        text(trf("config.removed", &[("address", &removed.address)])).size(14).width(Length::Fill),
+                        button(tr("config.undo")).on_press(Message::UndoRemove),
+                        button("✕").on_press(Message::DismissUndo).style(iced::theme::Button::Text),
+                    ]
+                    .spacing(6)
+                    .align_items(iced::Alignment::Center),
+                )
+                .padding(8)
+                .style(iced::theme::Container::Box),
+            );
+        }
+        col.push(scrollable(list_col).height(Length::Fill)).into()
+    }
+
+    pub(super) fn settings_tab(&self) -> Element<'_, Message> {
+        let mut col = column![row![
+            text(tr("config.theme")).width(Length::Fill).size(14),
+            pick_list(ThemeChoice::ALL, Some(self.config.theme), Message::ThemeSelected),
+        ]
+        .spacing(10)
+        .align_items(iced::Alignment::Center)]
+        .spacing(12);
+        for field in NumericSetting::SETTINGS_TAB {
+            col = col.push(self.numeric_row(field));
+        }
+        col = col.push(text(tr("config.settings_hint")).size(12));
+        let mut export = button(tr("bundle.button")).style(iced::theme::Button::Secondary).padding(8);
+        if !matches!(self.support_bundle, Some(None)) {
+            export = export.on_press(Message::ExportSupportBundle);
+        }
+        col = col.push(export);
+        match &self.support_bundle {
+            None => {}
+            Some(None) => col = col.push(text(tr("bundle.running")).size(12)),
+            Some(Some(Ok(path))) => col = col.push(text(trf("bundle.saved", &[("path", &path.display())])).size(12)),
+            Some(Some(Err(e))) => col = col.push(text(trf("bundle.failed", &[("error", e)])).size(12)),
+        }
+        scrollable(col).height(Length::Fill).into()
+    }
+
+    pub(super) fn notifications_tab(&self) -> Element<'_, Message> {
+        let mut col = column![
+            checkbox(tr("config.desktop"), self.config.desktop.enabled).on_toggle(Message::DesktopToggled),
+            checkbox(tr("config.group"), self.config.group_notifications).on_toggle(Message::GroupingToggled),
+            self.numeric_row(NumericSetting::MaxPerHour),
+            text(tr("config.remote_channels")).size(16),
+        ]
+        .spacing(12);
+        for (name, label, configured) in self.remote_notifiers() {
+            let item = if configured {
+                let enabled = !self.config.disabled_notifiers.iter().any(|n| n == name);
+                checkbox(label, enabled).on_toggle(move |on| Message::NotifierToggled(name, on))
+            } else {
+                // Sem `on_toggle` o checkbox aparece desabilitado
+                checkbox(format!("{} ({})", label, tr("config.not_configured")), false)
+            };
+            col = col.push(item);
+        }
+        col = col.push(text(tr("config.remote_hint")).size(12));
+        scrollable(col).height(Length::Fill).into()
+    }
+
+    pub(super) fn status_tab(&self) -> Element<'_, Message> {
+        if self.config.api.is_none() {
+            return text(tr("config.status_no_api")).size(14).into();
+        }
+        let status = match &self.live_status {
+            None => return text(tr("config.status_loading")).size(14).into(),
+            Some(Err(e)) => return text(trf("config.status_error", &[("error", e)])).size(14).into(),
+            Some(Ok(status)) => status,
+        };
+
+        let mut header = trf("tray.last_check", &[("time", &status.last_update)]);
+        if let Some(until) = status.paused_until.as_deref().and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok()) {
+            header.push_str(&trf("tray.paused_until", &[("time", &until.with_timezone(&Local).format("%H:%M"))]));
+        }
+        let mut col = column![text(header).size(14)].spacing(8);
+        if let Some(err) = &status.config_error {
+            col = col.push(text(format!("{}: {}", tr("tray.config_invalid"), err)).size(12));
+        }
+        for target in &status.targets {
+            let icon = if target.paused_until.is_some() {
+                "⏸️"
+            } else if target.next_check.is_some() {
+                "🕒"
+            } else if target.flapping {
+                "🟠"
+            } else if target.is_up {
+                "🟢"
+            } else {
+                "🔴"
+            };
+            let host = match &target.hostname {
+                Some(name) => format!("{} · {}", target.host, name),
+                None => target.host.clone(),
+            };
+            let mut label = format!("{} {} ({})", icon, host, target.detail);
+            if let Some(until) = target.paused_until {
+                label.push_str(&trf("tray.note_paused", &[("time", &format_timestamp(until, "%H:%M"))]));
+            } else if let Some(next) = target.next_check {
+                label.push_str(&trf("tray.note_next_check", &[("time", &format_timestamp(next, "%d/%m %H:%M"))]));
+            }
+            let mut card = column![text(label).size(14)].spacing(4);
+            if let Some(ms) = target.dns_ms {
+                card = card.push(text(trf("tray.dns_time", &[("ms", &format!("{:.1}", ms))])).size(12));
+            }
+            if let Some(network) = target.network.as_ref().and_then(|n| n.label()) {
+                card = card.push(text(trf("tray.network", &[("network", &network)])).size(12));
+            }
+            col = col.push(container(card).padding(8).width(Length::Fill).style(iced::theme::Container::Box));
+        }
+        scrollable(col).height(Length::Fill).into()
+    }
+
+    pub(super) fn history_tab(&self) -> Element<'_, Message> {
+        let events = self.history.as_deref().unwrap_or_default();
+        let mut hosts: Vec<String> = events.iter().map(|e| e.host.clone()).collect::<HashSet<_>>().into_iter().collect();
+        hosts.sort();
+        hosts.insert(0, tr("history.all_targets").to_string());
+        let selected = self.history_host.clone().unwrap_or_else(|| tr("history.all_targets").to_string());
+
+        // Aceita tanto AAAA-MM-DD quanto DD/MM/AAAA
+        let date_filter = self.history_date.trim();
+        let day = ["%Y-%m-%d", "%d/%m/%Y"]
+            .iter()
+            .find_map(|fmt| chrono::NaiveDate::parse_from_str(date_filter, fmt).ok());
+
+        let filters = row![
+            pick_list(hosts, Some(selected), Message::HistoryHostSelected).width(Length::Fill),
+            text_input(tr("history.date_placeholder"), &self.history_date)
+                .on_input(Message::HistoryDateChanged)
+                .padding(6)
+                .width(Length::Fixed(170.0)),
+        ]
+        .spacing(10)
+        .align_items(iced::Alignment::Center);
+        let mut col = column![filters].spacing(8);
+        if !date_filter.is_empty() && day.is_none() {
+            col = col.push(text(tr("config.invalid_value")).size(12));
+        }
+
+        let matching: Vec<&StateEvent> = events
+            .iter()
+            .filter(|e| self.history_host.as_ref().is_none_or(|host| &e.host == host))
+            .filter(|e| day.is_none_or(|day| format_timestamp(e.ts, "%Y-%m-%d") == day.format("%Y-%m-%d").to_string()))
+            .collect();
+        col = col.push(text(trf("history.count", &[("count", &matching.len())])).size(12));
+        if matching.is_empty() {
+            col = col.push(text(tr("tray.no_events")).size(14));
+        }
+
+        let mut list = column![].spacing(4);
+        for event in matching.iter().take(HISTORY_VIEW_LIMIT) {
+            let mut label = format!(
+                "{} {} {}",
+                format_timestamp(event.ts, "%d/%m/%Y %H:%M"),
+                if event.is_up { "🟢" } else { "🔴" },
+                event.host
+            );
+            if let Some(secs) = event.outage_secs {
+                label.push_str(&trf("tray.event_outage", &[("duration", &format_duration(secs))]));
+            }
+            list = list.push(text(label).size(14));
+        }
+        col.push(scrollable(list).height(Length::Fill)).into()
+    }
+}
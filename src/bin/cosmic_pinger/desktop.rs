@@ -0,0 +1,172 @@
+//! Integração com o desktop usada pelas bandejas (ksni no Linux, tray-icon no
+//! Windows e no macOS): janelas em processos filhos, navegador, área de
+//! transferência e as ações do menu.
+
+use super::*;
+
+/// Abre o assistente num processo filho e espera: o `sites.json` precisa
+/// existir antes de o monitor começar.
+pub(crate) fn run_onboarding() {
+    if let Ok(exe) = std::env::current_exe() {
+        let mut cmd = SysCommand::new(exe);
+        cmd.arg("--onboarding");
+        if let Some(path) = &runtime().config_path {
+            cmd.arg("--config-path").arg(path);
+        }
+        if let Err(e) = cmd.status() {
+            warn!("[ONBOARDING] Falha ao abrir o assistente: {}", e);
+        }
+    }
+    // Janela fechada sem escolha (ou sem tela): fica o padrão, sem perguntar de novo
+    if needs_onboarding() {
+        save_config(&AppConfig::default());
+    }
+}
+
+/// Abre a janela de configuração em um processo filho, opcionalmente já
+/// no painel `start`.
+pub(crate) fn open_config_window(start: Option<StartPane>) {
+    if let Ok(exe) = std::env::current_exe() {
+        std::thread::spawn(move || {
+            let mut cmd = SysCommand::new(exe);
+            match &start {
+                Some(StartPane::Watch(host)) => cmd.arg("--watch").arg(host),
+                Some(StartPane::Certificate(address)) => cmd.arg("--cert").arg(address),
+                Some(StartPane::Diagnose(address)) => cmd.arg("--diagnose").arg(address),
+                None => cmd.arg("--config"),
+            };
+            // Mantém o mesmo arquivo de configuração na janela filha
+            if let Some(path) = &runtime().config_path {
+                cmd.arg("--config-path").arg(path);
+            }
+            let _ = cmd.spawn();
+        });
+    }
+}
+
+/// Abre uma URL ou arquivo no programa padrão do desktop.
+pub(crate) fn open_in_desktop(target: impl AsRef<std::ffi::OsStr>) -> std::io::Result<()> {
+    #[cfg(windows)]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let mut cmd = SysCommand::new("cmd");
+        // O primeiro argumento entre aspas do `start` é o título da janela
+        cmd.args(["/C", "start", ""]).creation_flags(CREATE_NO_WINDOW);
+        cmd
+    };
+    #[cfg(target_os = "macos")]
+    let mut cmd = SysCommand::new("open");
+    #[cfg(not(any(windows, target_os = "macos")))]
+    let mut cmd = SysCommand::new("xdg-open");
+    cmd.arg(target).spawn().map(|_| ())
+}
+
+/// Clique no ícone: abre o dashboard se a API estiver ativa, senão a configuração.
+pub(crate) fn open_dashboard() {
+    match load_config().ok().and_then(|cfg| cfg.api) {
+        Some(api) => {
+            if let Err(e) = open_in_desktop(dashboard_url(&api)) {
+                warn!("Falha ao abrir o dashboard: {}", e);
+                open_config_window(None);
+            }
+        }
+        None => open_config_window(None),
+    }
+}
+
+/// Copia o texto para a área de transferência usando `wl-copy` (Wayland) ou
+/// `xclip`; `clip` no Windows e `pbcopy` no macOS. Um utilitário que falha
+/// (ex.: `wl-copy` instalado numa sessão X11) não encerra a busca: tenta o
+/// próximo e só devolve erro se todos falharem.
+pub(crate) fn copy_to_clipboard(text: &str) -> Result<(), String> {
+    use std::io::Write;
+
+    let candidates: &[(&str, &[&str])] = if cfg!(windows) {
+        &[("clip", &[])]
+    } else if cfg!(target_os = "macos") {
+        &[("pbcopy", &[])]
+    } else {
+        &[("wl-copy", &[]), ("xclip", &["-selection", "clipboard"])]
+    };
+    let mut last_error = None;
+    for &(program, args) in candidates {
+        let Ok(mut child) = SysCommand::new(program)
+            .args(args)
+            .stdin(process::Stdio::piped())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            if let Err(e) = stdin.write_all(text.as_bytes()) {
+                // Fecha o stdin antes de esperar, senão o filho fica aguardando entrada
+                drop(stdin);
+                let _ = child.wait();
+                last_error = Some(format!("{}: {}", program, e));
+                continue;
+            }
+        }
+        match child.wait() {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => last_error = Some(format!("{} terminou com {}", program, status)),
+            Err(e) => last_error = Some(format!("{}: {}", program, e)),
+        }
+    }
+    let tried: Vec<&str> = candidates.iter().map(|(program, _)| *program).collect();
+    Err(last_error.unwrap_or_else(|| format!("nenhum utilitário de área de transferência encontrado ({})", tried.join(", "))))
+}
+
+/// Executa uma ação do menu da bandeja.
+pub(crate) fn perform_tray_action(action: TrayAction, commands: &mpsc::Sender<MonitorCommand>) {
+    let send = |command| {
+        let _ = commands.send(command);
+    };
+    match action {
+        TrayAction::CheckNow => send(MonitorCommand::CheckNow),
+        TrayAction::CheckTarget(host) => send(MonitorCommand::CheckTarget(host)),
+        TrayAction::RestoreBackup => match restore_config_backup() {
+            // O próximo ciclo relê o arquivo e limpa o erro
+            Ok(_) => send(MonitorCommand::CheckNow),
+            Err(e) => warn!("Falha ao restaurar backup: {}", e),
+        },
+        TrayAction::Configure => open_config_window(None),
+        TrayAction::OpenDashboard => open_dashboard(),
+        TrayAction::ShowCertificate(url) => open_config_window(Some(StartPane::Certificate(url))),
+        TrayAction::Watch(host) => open_config_window(Some(StartPane::Watch(host))),
+        TrayAction::Diagnose(host) => open_config_window(Some(StartPane::Diagnose(host))),
+        TrayAction::OpenUrl(url) => {
+            if let Err(e) = open_in_desktop(&url) {
+                warn!("Falha ao abrir {}: {}", url, e);
+            }
+        }
+        TrayAction::CopyAddress(address) => {
+            if let Err(e) = copy_to_clipboard(&address) {
+                warn!("Falha ao copiar endereço: {}", e);
+            }
+        }
+        TrayAction::PauseTarget(host) => {
+            send(MonitorCommand::PauseTarget(host, Local::now() + chrono::Duration::hours(1)));
+        }
+        TrayAction::ResumeTarget(host) => send(MonitorCommand::ResumeTarget(host)),
+        // Lê o histórico fora da thread do menu
+        TrayAction::ShowHistory(host) => {
+            thread::spawn(move || show_notification(&target_history_text(&host), "dialog-information", Severity::Low));
+        }
+        TrayAction::Acknowledge(host) => send(MonitorCommand::Acknowledge(host)),
+        TrayAction::MuteHour => send(MonitorCommand::Mute(Some(Mute::Until(Local::now() + chrono::Duration::hours(1))))),
+        TrayAction::MuteUntilResumed => send(MonitorCommand::Mute(Some(Mute::UntilResumed))),
+        TrayAction::Unmute => send(MonitorCommand::Mute(None)),
+        TrayAction::OpenEventLog => {
+            let path = event_log_path();
+            // Garante que o arquivo exista antes do primeiro evento
+            let _ = fs::OpenOptions::new().create(true).append(true).open(&path);
+            if let Err(e) = open_in_desktop(&path) {
+                warn!("Falha ao abrir o log de eventos: {}", e);
+            }
+        }
+        TrayAction::Quit => {
+            let _ = request_shutdown(commands);
+        }
+    }
+}
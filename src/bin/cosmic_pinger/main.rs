@@ -0,0 +1,132 @@
+//! Binário do Cosmic Pinger: bandeja, modos texto e janelas sobre a
+//! biblioteca `cosmic_pinger_core`. Cada frontend fica no seu módulo; aqui
+//! só se escolhe qual rodar.
+
+use iced::{Application, Command, Element, Length, Settings, Theme};
+use iced::window;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use std::process::{self, Command as SysCommand};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use tracing::{debug, error, info, warn};
+use cosmic_pinger_core::*;
+
+mod cli;
+mod config_window;
+#[cfg(any(target_os = "linux", windows, target_os = "macos"))]
+mod desktop;
+#[cfg(any(windows, target_os = "macos"))]
+mod native_tray;
+mod onboarding_window;
+#[cfg(target_os = "linux")]
+mod sni_tray;
+
+use cli::*;
+#[cfg(any(target_os = "linux", windows, target_os = "macos"))]
+use desktop::*;
+
+// --- MAIN ---
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let cli = parse_args(&args).unwrap_or_else(|err| exit_with_usage(&err));
+    let _ = RUNTIME.set(cli.settings);
+    init_logging();
+
+    match cli.mode {
+        RunMode::Config(start) => config_window::run(start),
+        RunMode::Statusbar => run_statusbar(),
+        RunMode::Text(output) => run_text_output(output),
+        RunMode::Tray => run_tray(),
+        RunMode::Check { target, nagios } => run_check(&target, nagios),
+        RunMode::SupportBundle(path) => run_support_bundle(path),
+        RunMode::Onboarding => onboarding_window::run(),
+    }
+}
+
+/// `cosmic_pinger check [--nagios] DESTINO`: checa uma vez com as opções do
+/// destino na configuração e sai com 0 (online), 1 (degradado) ou 2 (offline);
+/// 3 quando o destino é inválido, como um plugin Nagios.
+fn run_check(target: &str, nagios: bool) -> ! {
+    let config = load_config().unwrap_or_else(|e| {
+        warn!("[CONFIG] {}; usando as opções padrão", e);
+        AppConfig::default()
+    });
+    let (target, outcome) = match run_single_check(target, &config) {
+        Ok(result) => result,
+        Err(e) => {
+            println!("{}{}", if nagios { "UNKNOWN - " } else { "" }, e);
+            process::exit(3);
+        }
+    };
+    let (code, line) = nagios_output(&target, &outcome);
+    if nagios {
+        println!("{}", line);
+    } else {
+        println!("{} {}: {}", outcome_icon(&outcome), target.address.trim(), outcome.message);
+    }
+    process::exit(code);
+}
+
+fn run_support_bundle(path: Option<PathBuf>) {
+    let path = path.unwrap_or_else(default_support_bundle_path);
+    match write_support_bundle(&path) {
+        Ok(()) => println!("{}", trf("bundle.saved", &[("path", &path.display())])),
+        Err(e) => {
+            eprintln!("{}", trf("bundle.failed", &[("error", &e)]));
+            process::exit(1);
+        }
+    }
+}
+
+// --- TRAY (BANDEJA) ---
+#[cfg(target_os = "linux")]
+use sni_tray::run_tray;
+#[cfg(any(windows, target_os = "macos"))]
+use native_tray::run_tray;
+
+/// Sem bandeja nas demais plataformas: cai no modo texto.
+#[cfg(not(any(target_os = "linux", windows, target_os = "macos")))]
+fn run_tray() {
+    warn!("[TRAY] Bandeja indisponível nesta plataforma; usando o modo --text");
+    run_text_output(TextOutput::default());
+}
+
+// --- BARRAS DE STATUS ---
+/// Modo para barras de status (Waybar, i3status-rs): uma linha JSON por
+/// atualização em stdout (os logs já vão para stderr).
+fn run_statusbar() {
+    info!("--- Iniciando Modo Statusbar ---");
+
+    let (state_tx, state_rx) = state_channel();
+    let (command_tx, command_rx) = mpsc::channel::<MonitorCommand>();
+    let print = move || print_line(&statusbar_line(&current_state(&state_rx)));
+    print();
+
+    run_monitor(state_tx, command_tx, command_rx, &print);
+}
+
+/// Modo texto para polybar/i3blocks: uma linha por atualização, montada a partir
+/// do modelo em `--format`.
+fn run_text_output(output: TextOutput) {
+    info!("--- Iniciando Modo Texto ---");
+
+    let (state_tx, state_rx) = state_channel();
+    let (command_tx, command_rx) = mpsc::channel::<MonitorCommand>();
+    let print = move || print_line(&render_text_line(&current_state(&state_rx), &output));
+    print();
+
+    run_monitor(state_tx, command_tx, command_rx, &print);
+}
+
+fn print_line(line: &str) {
+    use std::io::Write;
+
+    let mut out = std::io::stdout().lock();
+    let _ = writeln!(out, "{}", line);
+    let _ = out.flush();
+}
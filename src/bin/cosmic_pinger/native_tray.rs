@@ -0,0 +1,243 @@
+//! Bandeja do Windows (área de notificação) e item da barra de menus do
+//! macOS (NSStatusItem) via `tray-icon`. O menu, a dica e o ícone vêm do
+//! mesmo modelo da bandeja do Linux (`tray_menu`, `tray_tooltip`, `tray_pixmap`).
+
+use super::*;
+use std::sync::Mutex;
+use tray_icon::menu::{IsMenuItem, Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem, Submenu};
+use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+use winit::event::{Event, StartCause};
+use winit::event_loop::{ControlFlow, EventLoopBuilder};
+
+/// O que acorda o laço de eventos da thread principal.
+enum TrayEvent {
+    /// O monitor publicou um estado novo.
+    StateChanged,
+    Menu(MenuId),
+    /// Clique esquerdo no ícone (só no Windows; no macOS o clique abre o menu).
+    #[cfg(windows)]
+    Activate,
+    /// Clique do meio no ícone.
+    #[cfg(windows)]
+    CheckNow,
+    /// O monitor terminou (item "Sair" ou sinal).
+    Stopped,
+}
+
+/// Limite do Windows para a dica do ícone (`szTip`, 128 caracteres com o nulo).
+const TOOLTIP_MAX_CHARS: usize = 127;
+
+pub(crate) fn run_tray() {
+    info!("--- Iniciando Modo Tray ---");
+    if needs_onboarding() {
+        run_onboarding();
+    }
+
+    let mut builder = EventLoopBuilder::<TrayEvent>::with_user_event();
+    // Só o item na barra de menus, sem ícone no Dock nem menu de aplicativo
+    #[cfg(target_os = "macos")]
+    {
+        use winit::platform::macos::{ActivationPolicy, EventLoopBuilderExtMacOS};
+        builder.with_activation_policy(ActivationPolicy::Accessory).with_default_menu(false);
+    }
+    let event_loop = match builder.build() {
+        Ok(event_loop) => event_loop,
+        Err(e) => {
+            warn!("[TRAY] Laço de eventos indisponível ({}); usando o modo --text", e);
+            return run_text_output(TextOutput::default());
+        }
+    };
+
+    let (state_tx, state_rx) = state_channel();
+    let (command_tx, command_rx) = mpsc::channel::<MonitorCommand>();
+
+    // Os handlers do tray-icon exigem Sync; o proxy do winit é só Send
+    let proxy = Mutex::new(event_loop.create_proxy());
+    MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+        if let Ok(proxy) = proxy.lock() {
+            let _ = proxy.send_event(TrayEvent::Menu(event.id));
+        }
+    }));
+    // No macOS qualquer clique abre o menu, como nos demais itens da barra
+    #[cfg(windows)]
+    {
+        use tray_icon::{MouseButton, MouseButtonState, TrayIconEvent};
+
+        let proxy = Mutex::new(event_loop.create_proxy());
+        TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+            let TrayIconEvent::Click { button, button_state: MouseButtonState::Up, .. } = event else {
+                return;
+            };
+            let event = match button {
+                MouseButton::Left => TrayEvent::Activate,
+                MouseButton::Middle => TrayEvent::CheckNow,
+                MouseButton::Right => return,
+            };
+            if let Ok(proxy) = proxy.lock() {
+                let _ = proxy.send_event(event);
+            }
+        }));
+    }
+
+    let proxy = event_loop.create_proxy();
+    let monitor_commands = command_tx.clone();
+    let monitor = thread::spawn(move || {
+        run_monitor(state_tx, monitor_commands, command_rx, &|| {
+            let _ = proxy.send_event(TrayEvent::StateChanged);
+        });
+        let _ = proxy.send_event(TrayEvent::Stopped);
+    });
+
+    let mut tray: Option<NativeTray> = None;
+    let result = event_loop.run(move |event, target| {
+        target.set_control_flow(ControlFlow::Wait);
+        match event {
+            // O ícone precisa do laço de eventos já rodando (o NSStatusItem, do NSApplication)
+            Event::NewEvents(StartCause::Init) => match NativeTray::new(&current_state(&state_rx)) {
+                Ok(created) => {
+                    info!("[TRAY] Ícone da bandeja criado");
+                    tray = Some(created);
+                }
+                Err(e) => {
+                    error!("[TRAY] Falha ao criar o ícone da bandeja: {}", e);
+                    let _ = request_shutdown(&command_tx);
+                }
+            },
+            Event::UserEvent(TrayEvent::StateChanged) => {
+                if let Some(tray) = tray.as_mut() {
+                    tray.refresh(&current_state(&state_rx));
+                }
+            }
+            Event::UserEvent(TrayEvent::Menu(id)) => {
+                if let Some(action) = tray.as_ref().and_then(|tray| tray.actions.get(&id)).cloned() {
+                    perform_tray_action(action, &command_tx);
+                }
+            }
+            #[cfg(windows)]
+            Event::UserEvent(TrayEvent::Activate) => open_dashboard(),
+            #[cfg(windows)]
+            Event::UserEvent(TrayEvent::CheckNow) => {
+                let _ = command_tx.send(MonitorCommand::CheckNow);
+            }
+            Event::UserEvent(TrayEvent::Stopped) => {
+                // Remove o ícone antes de sair, senão ele fica órfão até o mouse passar por cima
+                tray = None;
+                target.exit();
+            }
+            _ => {}
+        }
+    });
+    if let Err(e) = result {
+        error!("[TRAY] Laço de eventos parou: {}", e);
+    }
+    if monitor.join().is_err() {
+        warn!("[TRAY] Thread do monitor terminou em pânico");
+    }
+    info!("[TRAY] Bandeja encerrada");
+}
+
+/// Ícone criado e o que ele mostra agora, para só refazer o que mudou.
+struct NativeTray {
+    icon: TrayIcon,
+    /// Ação de cada item do menu atual.
+    actions: HashMap<MenuId, TrayAction>,
+    menu: Vec<TrayMenuItem>,
+    tooltip: String,
+    pixmap: Vec<u8>,
+}
+
+impl NativeTray {
+    fn new(s: &PingerState) -> Result<Self, String> {
+        let mut builder = TrayIconBuilder::new().with_id("cosmic-pinger");
+        // Como no Linux: clique esquerdo abre o dashboard e o menu fica no direito
+        #[cfg(windows)]
+        {
+            builder = builder.with_menu_on_left_click(false);
+        }
+        if let Some(icon) = rgba_icon(&tray_pixmap(s)) {
+            builder = builder.with_icon(icon);
+        }
+        let icon = builder.build().map_err(|e| e.to_string())?;
+        let mut tray = NativeTray { icon, actions: HashMap::new(), menu: Vec::new(), tooltip: String::new(), pixmap: Vec::new() };
+        tray.refresh(s);
+        Ok(tray)
+    }
+
+    fn refresh(&mut self, s: &PingerState) {
+        #[cfg(windows)]
+        let menu = tray_menu(s);
+        // Sem clique que abra o dashboard na barra de menus: ele vira o primeiro item
+        #[cfg(target_os = "macos")]
+        let menu = {
+            let mut menu = vec![
+                TrayMenuItem::Action(tr("tray.open_dashboard").to_string(), TrayAction::OpenDashboard),
+                TrayMenuItem::Separator,
+            ];
+            menu.extend(tray_menu(s));
+            menu
+        };
+        if menu != self.menu {
+            let mut actions = HashMap::new();
+            let native = Menu::new();
+            for item in native_items(menu.clone(), &mut actions) {
+                if let Err(e) = native.append(item.as_ref()) {
+                    warn!("[TRAY] Falha ao montar o menu: {}", e);
+                }
+            }
+            self.icon.set_menu(Some(Box::new(native)));
+            self.actions = actions;
+            self.menu = menu;
+        }
+
+        let tooltip: String = format!("{} v{}\n{}", APP_NAME, APP_VERSION, tray_tooltip(s)).chars().take(TOOLTIP_MAX_CHARS).collect();
+        if tooltip != self.tooltip {
+            if let Err(e) = self.icon.set_tooltip(Some(&tooltip)) {
+                warn!("[TRAY] Falha ao atualizar a dica: {}", e);
+            }
+            self.tooltip = tooltip;
+        }
+
+        let pixmap = tray_pixmap(s);
+        if pixmap != self.pixmap {
+            if let Err(e) = self.icon.set_icon(rgba_icon(&pixmap)) {
+                warn!("[TRAY] Falha ao atualizar o ícone: {}", e);
+            }
+            self.pixmap = pixmap;
+        }
+    }
+}
+
+/// `tray_pixmap` vem em ARGB (formato do StatusNotifier); o tray-icon quer RGBA.
+fn rgba_icon(argb: &[u8]) -> Option<Icon> {
+    let rgba = argb.chunks_exact(4).flat_map(|px| [px[1], px[2], px[3], px[0]]).collect();
+    Icon::from_rgba(rgba, TRAY_ICON_SIZE as u32, TRAY_ICON_SIZE as u32)
+        .map_err(|e| warn!("[TRAY] Ícone inválido: {}", e))
+        .ok()
+}
+
+/// Traduz o menu da biblioteca para itens do `muda`, guardando a ação de cada um.
+fn native_items(items: Vec<TrayMenuItem>, actions: &mut HashMap<MenuId, TrayAction>) -> Vec<Box<dyn IsMenuItem>> {
+    items
+        .into_iter()
+        .map(|item| -> Box<dyn IsMenuItem> {
+            match item {
+                TrayMenuItem::Label(label) => Box::new(MenuItem::new(label, false, None)),
+                TrayMenuItem::Action(label, action) => {
+                    let entry = MenuItem::new(label, true, None);
+                    actions.insert(entry.id().clone(), action);
+                    Box::new(entry)
+                }
+                TrayMenuItem::Submenu(label, children) => {
+                    let submenu = Submenu::new(label, true);
+                    for child in native_items(children, actions) {
+                        if let Err(e) = submenu.append(child.as_ref()) {
+                            warn!("[TRAY] Falha ao montar o submenu: {}", e);
+                        }
+                    }
+                    Box::new(submenu)
+                }
+                TrayMenuItem::Separator => Box::new(PredefinedMenuItem::separator()),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,134 @@
+//! Assistente de primeira execução (iced).
+
+use super::*;
+use crate::config_window::system_prefers_dark;
+use iced::widget::{button, checkbox, column, container, row, scrollable, text, text_input};
+
+/// Abre o assistente (`--onboarding`) e volta quando a janela fecha.
+pub(crate) fn run() {
+    let mut settings = Settings::with_flags(());
+    settings.window = iced::window::Settings { size: iced::Size::new(560.0, 560.0), ..Default::default() };
+    OnboardingWindow::run(settings).unwrap();
+}
+
+/// Primeira execução: propõe gateway, DNS e um destino público e explica a bandeja.
+struct OnboardingWindow {
+    /// Sugestões e se estão marcadas.
+    suggestions: Vec<(Suggestion, bool)>,
+    extra: String,
+    error: Option<String>,
+    system_dark: bool,
+}
+
+#[derive(Debug, Clone)]
+enum OnboardingMessage {
+    Toggled(usize, bool),
+    ExtraChanged(String),
+    Start,
+    Skip,
+}
+
+impl OnboardingWindow {
+    fn finish(&mut self) -> Result<AppConfig, String> {
+        let mut targets: Vec<TargetConfig> = self
+            .suggestions
+            .iter()
+            .filter(|(_, selected)| *selected)
+            .map(|(suggestion, _)| {
+                let mut target = TargetConfig::new(&suggestion.address);
+                target.tags = vec![suggestion.tag.to_string()];
+                target
+            })
+            .collect();
+        if !self.extra.trim().is_empty() {
+            let address = normalize_target(&self.extra)
+                .ok_or_else(|| trf("onboarding.invalid", &[("address", &self.extra.trim())]))?;
+            if !targets.iter().any(|t| t.address == address) {
+                targets.push(TargetConfig::new(&address));
+            }
+        }
+        if targets.is_empty() {
+            return Err(tr("onboarding.none").to_string());
+        }
+        Ok(AppConfig { targets, ..AppConfig::default() })
+    }
+}
+
+impl Application for OnboardingWindow {
+    type Executor = iced::executor::Default;
+    type Message = OnboardingMessage;
+    type Theme = Theme;
+    type Flags = ();
+
+    fn new(_flags: ()) -> (Self, Command<OnboardingMessage>) {
+        let suggestions = onboarding_suggestions().into_iter().map(|s| (s, true)).collect();
+        (Self { suggestions, extra: String::new(), error: None, system_dark: system_prefers_dark() }, Command::none())
+    }
+
+    fn title(&self) -> String {
+        tr("onboarding.title").to_string()
+    }
+
+    fn theme(&self) -> Theme {
+        if self.system_dark { Theme::Dark } else { Theme::Light }
+    }
+
+    fn update(&mut self, message: OnboardingMessage) -> Command<OnboardingMessage> {
+        match message {
+            OnboardingMessage::Toggled(i, on) => {
+                if let Some(item) = self.suggestions.get_mut(i) {
+                    item.1 = on;
+                }
+                self.error = None;
+            }
+            OnboardingMessage::ExtraChanged(value) => {
+                self.extra = value;
+                self.error = None;
+            }
+            OnboardingMessage::Start => match self.finish() {
+                Ok(config) => {
+                    info!("[ONBOARDING] Monitorando {} destino(s)", config.targets.len());
+                    save_config(&config);
+                    return window::close(window::Id::MAIN);
+                }
+                Err(e) => self.error = Some(e),
+            },
+            OnboardingMessage::Skip => {
+                save_config(&AppConfig::default());
+                return window::close(window::Id::MAIN);
+            }
+        }
+        Command::none()
+    }
+
+    fn view(&self) -> Element<'_, OnboardingMessage> {
+        let mut col = column![text(tr("onboarding.title")).size(24), text(tr("onboarding.intro")).size(14)].spacing(14).padding(20);
+        for (i, (suggestion, selected)) in self.suggestions.iter().enumerate() {
+            let key = match suggestion.tag {
+                "gateway" => "onboarding.gateway",
+                "dns" => "onboarding.dns",
+                _ => "onboarding.internet",
+            };
+            let label = trf(key, &[("address", &suggestion.address)]);
+            col = col.push(checkbox(label, *selected).on_toggle(move |on| OnboardingMessage::Toggled(i, on)));
+        }
+        col = col.push(
+            text_input(tr("onboarding.extra"), &self.extra)
+                .on_input(OnboardingMessage::ExtraChanged)
+                .on_submit(OnboardingMessage::Start)
+                .padding(8),
+        );
+        if let Some(err) = &self.error {
+            col = col.push(text(format!("⚠️ {}", err)).size(13));
+        }
+        col = col.push(container(text(tr("onboarding.tray")).size(13)).padding(10).style(iced::theme::Container::Box));
+        col = col.push(
+            row![
+                button(tr("onboarding.start")).on_press(OnboardingMessage::Start).padding(10),
+                button(tr("onboarding.skip")).on_press(OnboardingMessage::Skip).style(iced::theme::Button::Secondary).padding(10),
+            ]
+            .spacing(10),
+        );
+        scrollable(col).height(Length::Fill).into()
+    }
+}
@@ -0,0 +1,223 @@
+//! Bandeja do Linux via StatusNotifierItem (ksni).
+
+use super::*;
+use ksni::menu::{StandardItem, SubMenu};
+use ksni::{MenuItem, ToolTip, Tray};
+
+pub(crate) fn run_tray() {
+    info!("--- Iniciando Modo Tray ---");
+    if needs_onboarding() {
+        run_onboarding();
+    }
+
+    let (state_tx, state_rx) = state_channel();
+    let (command_tx, command_rx) = mpsc::channel::<MonitorCommand>();
+
+    // Cria o serviço de tray uma única vez
+    let icon_theme = install_tray_icons();
+    let service = ksni::TrayService::new(PingerTray {
+        state: state_rx.clone(),
+        icon_theme: icon_theme.clone(),
+        commands: command_tx.clone(),
+    });
+    let handle = service.handle();
+    // Thread própria em vez de `spawn()`, para esperar o item sair do painel no fim
+    let service_thread = thread::spawn(move || {
+        if let Err(e) = service.run() {
+            error!("[TRAY] Serviço de tray parou: {}", e);
+        }
+    });
+    info!("[TRAY] Serviço de tray iniciado");
+
+    // Cópia local da bandeja, só para comparar o que ela mostraria
+    let shown = PingerTray { state: state_rx, icon_theme, commands: command_tx.clone() };
+    let last_signature = std::cell::RefCell::new(String::new());
+    run_monitor(state_tx, command_tx, command_rx, &|| {
+        let signature = tray_signature(&shown);
+        if *last_signature.borrow() != signature {
+            refresh_tray(&handle);
+            *last_signature.borrow_mut() = signature;
+        }
+    });
+
+    // Tira o item do StatusNotifier antes de sair, senão ele fica órfão no painel
+    handle.shutdown();
+    if service_thread.join().is_err() {
+        warn!("[TRAY] Thread do serviço de tray terminou em pânico");
+    }
+    info!("[TRAY] Serviço de tray encerrado");
+}
+
+/// Pede ao ksni que releia o estado da bandeja. O serviço compara com o que já
+/// foi publicado e só emite sinais D-Bus das propriedades e itens de menu que
+/// mudaram, então o ícone não pisca nem perde a posição no painel.
+fn refresh_tray(tray: &ksni::Handle<PingerTray>) {
+    tray.update(|_tray| {});
+}
+
+/// Tudo o que a bandeja mostra (título, ícone, status, dica e menu) numa
+/// string. Publicações que não mudam essa assinatura não acordam o ksni.
+fn tray_signature(tray: &PingerTray) -> String {
+    use std::hash::{Hash, Hasher};
+
+    let s = current_state(&tray.state);
+    let mut pixmap = std::collections::hash_map::DefaultHasher::new();
+    tray_pixmap(&s).hash(&mut pixmap);
+    format!(
+        "{}\n{}\n{:?}\n{}\n{:x}\n{:?}",
+        tray.title(),
+        tray.icon_name(),
+        tray.status(),
+        tray_tooltip(&s),
+        pixmap.finish(),
+        tray_menu(&s)
+    )
+}
+
+/// Ícones simbólicos por estado, recoloridos pelo painel conforme o tema claro/escuro.
+const TRAY_ICONS: [(&str, &str); 6] = [
+    ("cosmic-pinger-starting-symbolic", include_str!("../../../assets/icons/cosmic-pinger-starting-symbolic.svg")),
+    ("cosmic-pinger-ok-symbolic", include_str!("../../../assets/icons/cosmic-pinger-ok-symbolic.svg")),
+    ("cosmic-pinger-down-symbolic", include_str!("../../../assets/icons/cosmic-pinger-down-symbolic.svg")),
+    ("cosmic-pinger-paused-symbolic", include_str!("../../../assets/icons/cosmic-pinger-paused-symbolic.svg")),
+    ("cosmic-pinger-degraded-symbolic", include_str!("../../../assets/icons/cosmic-pinger-degraded-symbolic.svg")),
+    ("cosmic-pinger-empty-symbolic", include_str!("../../../assets/icons/cosmic-pinger-empty-symbolic.svg")),
+];
+
+/// Grava os ícones no diretório de dados e retorna o caminho a anunciar como
+/// `IconThemePath`. Os arquivos ficam tanto na raiz (hosts que procuram o nome
+/// direto no caminho) quanto em `hicolor/` (hosts que o tratam como tema).
+/// Retorna `None` se a gravação falhar; nesse caso a bandeja usa só o pixmap.
+fn install_tray_icons() -> Option<PathBuf> {
+    let root = get_data_dir().join("icons");
+    let themed = root.join("hicolor").join("scalable").join("status");
+    let install = || -> std::io::Result<()> {
+        fs::create_dir_all(&themed)?;
+        fs::write(
+            root.join("hicolor").join("index.theme"),
+            "[Icon Theme]\nName=Hicolor\nDirectories=scalable/status\n\n[scalable/status]\nSize=16\nMinSize=8\nMaxSize=512\nType=Scalable\nContext=Status\n",
+        )?;
+        for (name, svg) in TRAY_ICONS {
+            let file = format!("{}.svg", name);
+            fs::write(root.join(&file), svg)?;
+            fs::write(themed.join(&file), svg)?;
+        }
+        Ok(())
+    };
+
+    match install() {
+        Ok(()) => Some(root),
+        Err(e) => {
+            warn!("[TRAY] Falha ao instalar ícones simbólicos, usando pixmap: {}", e);
+            None
+        }
+    }
+}
+
+struct PingerTray {
+    /// Última cópia publicada pelo monitor; o menu nunca espera o ciclo.
+    state: StateReceiver,
+    /// Diretório com os ícones simbólicos; `None` força o fallback em pixmap.
+    icon_theme: Option<PathBuf>,
+    commands: mpsc::Sender<MonitorCommand>,
+}
+
+/// Traduz o menu da biblioteca para os itens do ksni.
+fn sni_items(items: Vec<TrayMenuItem>) -> Vec<MenuItem<PingerTray>> {
+    items
+        .into_iter()
+        .map(|item| match item {
+            TrayMenuItem::Label(label) => MenuItem::Standard(StandardItem { label, enabled: false, ..Default::default() }),
+            TrayMenuItem::Action(label, action) => MenuItem::Standard(StandardItem {
+                label,
+                activate: Box::new(move |tray: &mut PingerTray| perform_tray_action(action.clone(), &tray.commands)),
+                ..Default::default()
+            }),
+            TrayMenuItem::Submenu(label, submenu) => {
+                MenuItem::SubMenu(SubMenu { label, submenu: sni_items(submenu), ..Default::default() })
+            }
+            TrayMenuItem::Separator => MenuItem::Separator,
+        })
+        .collect()
+}
+
+impl Tray for PingerTray {
+    // Clique esquerdo: abre o dashboard se a API estiver ativa, senão a configuração
+    fn activate(&mut self, _x: i32, _y: i32) {
+        open_dashboard();
+    }
+
+    // Clique do meio: checagem imediata
+    fn secondary_activate(&mut self, _x: i32, _y: i32) {
+        let _ = self.commands.send(MonitorCommand::CheckNow);
+    }
+
+    fn id(&self) -> String {
+        "cosmic-pinger".to_string()
+    }
+
+    fn title(&self) -> String {
+        tray_title(&current_state(&self.state))
+    }
+
+    fn icon_theme_path(&self) -> String {
+        self.icon_theme
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    fn icon_name(&self) -> String {
+        if self.icon_theme.is_none() {
+            return String::new();
+        }
+        tray_icon_name(&current_state(&self.state)).to_string()
+    }
+
+    fn status(&self) -> ksni::Status {
+        if tray_needs_attention(&current_state(&self.state)) {
+            ksni::Status::NeedsAttention
+        } else {
+            ksni::Status::Active
+        }
+    }
+
+    fn attention_icon_name(&self) -> String {
+        if self.icon_theme.is_some() {
+            "cosmic-pinger-down-symbolic".to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    fn attention_icon_pixmap(&self) -> Vec<ksni::Icon> {
+        self.icon_pixmap()
+    }
+
+    // Usado pelo host apenas quando o ícone do tema não pode ser resolvido.
+    fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+        let data = tray_pixmap(&current_state(&self.state));
+        vec![ksni::Icon { width: TRAY_ICON_SIZE as i32, height: TRAY_ICON_SIZE as i32, data }]
+    }
+
+    // Contador sobreposto ao ícone simbólico, para hosts que suportam overlay.
+    fn overlay_icon_pixmap(&self) -> Vec<ksni::Icon> {
+        let data = tray_badge_pixmap(&current_state(&self.state));
+        if data.is_empty() {
+            return Vec::new();
+        }
+        vec![ksni::Icon { width: TRAY_ICON_SIZE as i32, height: TRAY_ICON_SIZE as i32, data }]
+    }
+
+    fn tool_tip(&self) -> ToolTip {
+        ToolTip {
+            title: format!("{} v{}", APP_NAME, APP_VERSION),
+            description: tray_tooltip(&current_state(&self.state)),
+            ..Default::default()
+        }
+    }
+
+    fn menu(&self) -> Vec<MenuItem<Self>> {
+        sni_items(tray_menu(&current_state(&self.state)))
+    }
+}
@@ -0,0 +1,370 @@
+//! Cadeia de certificados e revogação.
+
+use super::*;
+
+/// Dados de um certificado X.509 lidos do DER (só o que a janela mostra).
+#[derive(Debug, Clone)]
+pub struct CertSummary {
+    pub subject: String,
+    pub issuer: String,
+    pub not_before: i64,
+    pub not_after: i64,
+    /// Nomes DNS e IPs de `subjectAltName`.
+    pub sans: Vec<String>,
+}
+
+/// Cadeia apresentada pelo servidor, da folha para a raiz.
+#[derive(Debug, Clone)]
+pub struct CertReport {
+    pub host: String,
+    pub port: u16,
+    pub chain: Vec<CertSummary>,
+    /// Motivo da rejeição pela validação webpki; `None` se a cadeia é confiável.
+    pub verify_error: Option<String>,
+}
+
+/// Lê um elemento DER: (tag, conteúdo, restante).
+pub(crate) fn der_next(data: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = data.split_first()?;
+    let (&first, rest) = rest.split_first()?;
+    let (len, rest) = if first & 0x80 == 0 {
+        (first as usize, rest)
+    } else {
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > 4 || rest.len() < count {
+            return None;
+        }
+        let len = rest[..count].iter().fold(0usize, |acc, &b| (acc << 8) | b as usize);
+        (len, &rest[count..])
+    };
+    (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+}
+
+/// Elemento DER como (tag, conteúdo).
+pub(crate) type DerItem<'a> = (u8, &'a [u8]);
+
+/// Elementos DER consecutivos de `data`.
+pub(crate) fn der_items(mut data: &[u8]) -> Vec<DerItem<'_>> {
+    let mut items = Vec::new();
+    while let Some((tag, content, rest)) = der_next(data) {
+        items.push((tag, content));
+        data = rest;
+    }
+    items
+}
+
+/// `Name` X.509 como `CN=..., O=...`, com os atributos mais comuns.
+pub(crate) fn der_name(name: &[u8]) -> String {
+    let mut parts = Vec::new();
+    for (_, set) in der_items(name) {
+        for (_, attr) in der_items(set) {
+            let fields = der_items(attr);
+            let [(0x06, oid), (_, value), ..] = fields.as_slice() else {
+                continue;
+            };
+            let label = match oid {
+                [0x55, 0x04, 0x03] => "CN",
+                [0x55, 0x04, 0x0a] => "O",
+                [0x55, 0x04, 0x0b] => "OU",
+                [0x55, 0x04, 0x06] => "C",
+                _ => continue,
+            };
+            parts.push(format!("{}={}", label, String::from_utf8_lossy(value)));
+        }
+    }
+    parts.join(", ")
+}
+
+/// `UTCTime` (AAMMDDhhmmssZ) ou `GeneralizedTime` (AAAAMMDDhhmmssZ) em Unix.
+pub(crate) fn der_time(tag: u8, raw: &[u8]) -> Option<i64> {
+    let text = std::str::from_utf8(raw).ok()?.trim_end_matches('Z');
+    let (year, rest) = match tag {
+        0x17 => {
+            let yy: i32 = text.get(..2)?.parse().ok()?;
+            (if yy >= 50 { 1900 + yy } else { 2000 + yy }, text.get(2..)?)
+        }
+        0x18 => (text.get(..4)?.parse().ok()?, text.get(4..)?),
+        _ => return None,
+    };
+    let field = |i: usize| -> Option<u32> { rest.get(i..i + 2)?.parse().ok() };
+    let date = chrono::NaiveDate::from_ymd_opt(year, field(0)?, field(2)?)?;
+    Some(date.and_hms_opt(field(4)?, field(6)?, field(8).unwrap_or(0))?.and_utc().timestamp())
+}
+
+/// Campos do `TBSCertificate` e o deslocamento causado pelo `version` ([0]),
+/// que é opcional; depois vêm serial, algoritmo, emissor, validade, titular,
+/// chave pública e as extensões ([3]).
+pub(crate) fn tbs_fields(der: &[u8]) -> Option<(Vec<DerItem<'_>>, usize)> {
+    let (_, cert, _) = der_next(der)?;
+    let (_, tbs, _) = der_next(cert)?;
+    let items = der_items(tbs);
+    let offset = usize::from(items.first()?.0 == 0xa0);
+    Some((items, offset))
+}
+
+/// Valor (já sem o OCTET STRING) da extensão `oid` do certificado.
+pub(crate) fn cert_extension<'a>(der: &'a [u8], oid: &[u8]) -> Option<&'a [u8]> {
+    let (items, offset) = tbs_fields(der)?;
+    let (_, extensions) = items.iter().skip(offset + 6).find(|(tag, _)| *tag == 0xa3)?;
+    let (_, list, _) = der_next(extensions)?;
+    der_items(list).into_iter().find_map(|(_, extension)| {
+        let fields = der_items(extension);
+        if fields.first() != Some(&(0x06, oid)) {
+            return None;
+        }
+        let (_, octets) = fields.last()?;
+        Some(*octets)
+    })
+}
+
+/// URLs HTTP da extensão `cRLDistributionPoints`.
+pub(crate) fn crl_urls(der: &[u8]) -> Vec<String> {
+    let mut urls = Vec::new();
+    let Some((_, points, _)) = cert_extension(der, &[0x55, 0x1d, 0x1f]).and_then(der_next) else {
+        return urls;
+    };
+    // DistributionPoint → distributionPoint [0] → fullName [0] → URI [6]
+    for (_, point) in der_items(points) {
+        for (_, name) in der_items(point).into_iter().filter(|(tag, _)| *tag == 0xa0) {
+            for (_, full) in der_items(name).into_iter().filter(|(tag, _)| *tag == 0xa0) {
+                for (tag, uri) in der_items(full) {
+                    let uri = String::from_utf8_lossy(uri);
+                    if tag == 0x86 && (uri.starts_with("http://") || uri.starts_with("https://")) {
+                        urls.push(uri.into_owned());
+                    }
+                }
+            }
+        }
+    }
+    urls
+}
+
+pub(crate) fn parse_certificate(der: &[u8]) -> Option<CertSummary> {
+    let (items, offset) = tbs_fields(der)?;
+    let issuer = der_name(items.get(offset + 2)?.1);
+    let validity = der_items(items.get(offset + 3)?.1);
+    let subject = der_name(items.get(offset + 4)?.1);
+    let [(from_tag, from), (to_tag, to)] = validity.as_slice() else {
+        return None;
+    };
+
+    let mut sans = Vec::new();
+    if let Some((_, names, _)) = cert_extension(der, &[0x55, 0x1d, 0x11]).and_then(der_next) {
+        for (tag, value) in der_items(names) {
+            match tag {
+                0x82 => sans.push(String::from_utf8_lossy(value).into_owned()),
+                0x87 if value.len() == 4 => {
+                    sans.push(std::net::Ipv4Addr::new(value[0], value[1], value[2], value[3]).to_string())
+                }
+                0x87 if value.len() == 16 => {
+                    let octets: [u8; 16] = value.try_into().ok()?;
+                    sans.push(std::net::Ipv6Addr::from(octets).to_string())
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Some(CertSummary {
+        subject,
+        issuer,
+        not_before: der_time(*from_tag, from)?,
+        not_after: der_time(*to_tag, to)?,
+        sans,
+    })
+}
+
+/// Aceita qualquer certificado para ler a cadeia mesmo quando ela é
+/// inválida, mas guarda o veredito da validação webpki normal.
+#[derive(Debug)]
+pub(crate) struct RecordingVerifier {
+    pub inner: Arc<rustls::client::WebPkiServerVerifier>,
+    pub verdict: Mutex<Option<String>>,
+}
+
+impl rustls::client::danger::ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &rustls::pki_types::CertificateDer<'_>,
+        intermediates: &[rustls::pki_types::CertificateDer<'_>],
+        server_name: &rustls::pki_types::ServerName<'_>,
+        ocsp_response: &[u8],
+        now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        if let Err(e) = self.inner.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now) {
+            if let Ok(mut verdict) = self.verdict.lock() {
+                *verdict = Some(e.to_string());
+            }
+        }
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &rustls::pki_types::CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Certificados crus de um handshake, na ordem enviada pelo servidor.
+pub(crate) struct PeerChain {
+    pub host: String,
+    pub port: u16,
+    pub server_name: rustls::pki_types::ServerName<'static>,
+    pub certs: Vec<rustls::pki_types::CertificateDer<'static>>,
+    pub verify_error: Option<String>,
+}
+
+/// Raízes do Mozilla embutidas (`webpki-roots`), as mesmas do reqwest.
+pub(crate) fn trust_roots() -> Arc<rustls::RootCertStore> {
+    Arc::new(rustls::RootCertStore::from_iter(webpki_roots::TLS_SERVER_ROOTS.iter().cloned()))
+}
+
+/// Faz um handshake TLS com o host da URL e guarda a cadeia apresentada,
+/// mesmo que ela não seja confiável.
+pub(crate) fn tls_peer_chain(address: &str, timeout: Duration) -> Result<PeerChain, String> {
+    use std::io::Write;
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let (host, port) = split_host_port(address).ok_or_else(|| format!("endereço inválido: {}", address))?;
+    let port = port.unwrap_or(443);
+    let bare = host.trim_start_matches('[').trim_end_matches(']').to_string();
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let inner = rustls::client::WebPkiServerVerifier::builder_with_provider(trust_roots(), provider.clone())
+        .build()
+        .map_err(|e| e.to_string())?;
+    let verifier = Arc::new(RecordingVerifier { inner, verdict: Mutex::new(None) });
+    let config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+    let server_name = rustls::pki_types::ServerName::try_from(bare.clone()).map_err(|e| e.to_string())?;
+    let mut conn = rustls::ClientConnection::new(Arc::new(config), server_name.clone()).map_err(|e| e.to_string())?;
+
+    let addr = (bare.as_str(), port)
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("{}: sem endereço", host))?;
+    let mut tcp = TcpStream::connect_timeout(&addr, timeout).map_err(|e| e.to_string())?;
+    tcp.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    tcp.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    while conn.is_handshaking() {
+        conn.complete_io(&mut tcp).map_err(|e| e.to_string())?;
+    }
+    conn.send_close_notify();
+    let _ = conn.write_tls(&mut tcp);
+    let _ = tcp.flush();
+
+    let certs: Vec<_> = conn.peer_certificates().unwrap_or_default().iter().map(|c| c.clone().into_owned()).collect();
+    if certs.is_empty() {
+        return Err(tr("cert.empty").to_string());
+    }
+    let verify_error = verifier.verdict.lock().ok().and_then(|v| v.clone());
+    Ok(PeerChain { host, port, server_name, certs, verify_error })
+}
+
+/// Cadeia legível da URL. A checagem HTTP passa pelo reqwest, que só expõe
+/// a folha; por isso a janela abre a própria conexão.
+pub fn fetch_certificate_chain(address: &str, timeout: Duration) -> Result<CertReport, String> {
+    let peer = tls_peer_chain(address, timeout)?;
+    let chain: Vec<CertSummary> = peer.certs.iter().filter_map(|der| parse_certificate(der)).collect();
+    if chain.is_empty() {
+        return Err(tr("cert.empty").to_string());
+    }
+    Ok(CertReport { host: peer.host, port: peer.port, chain, verify_error: peer.verify_error })
+}
+
+/// Situação de revogação do certificado do servidor.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Revocation {
+    Good,
+    Revoked,
+    /// Sem CRL publicada, download falhou ou a CRL não pôde ser validada.
+    Unknown(String),
+}
+
+/// CRLs baixadas por URL, reaproveitadas por `CRL_CACHE_SECS`; algumas ACs
+/// publicam listas de vários MB.
+pub(crate) static CRL_CACHE: OnceLock<Mutex<HashMap<String, CachedCrl>>> = OnceLock::new();
+
+/// Momento do download e o DER da CRL.
+pub(crate) type CachedCrl = (Instant, Arc<Vec<u8>>);
+
+pub(crate) fn fetch_crl(client: &Client, url: &str, timeout: Duration) -> Result<Arc<Vec<u8>>, String> {
+    let cache = CRL_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some((fetched, crl)) = cache.lock().ok().and_then(|c| c.get(url).cloned()) {
+        if fetched.elapsed() < Duration::from_secs(CRL_CACHE_SECS) {
+            return Ok(crl);
+        }
+    }
+    let body = client
+        .get(url)
+        .timeout(timeout)
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.bytes())
+        .map_err(|e| format!("{}: {}", url, e))?;
+    let crl = Arc::new(body.to_vec());
+    if let Ok(mut cache) = cache.lock() {
+        cache.insert(url.to_string(), (Instant::now(), crl.clone()));
+    }
+    Ok(crl)
+}
+
+/// Confere o certificado final da URL contra a CRL indicada nele. OCSP não
+/// é consultado: as ACs públicas estão migrando para CRLs e o rustls não
+/// valida respostas OCSP. `timeout` vale para o handshake e para cada CRL baixada.
+pub(crate) fn check_revocation(address: &str, client: &Client, timeout: Duration) -> Revocation {
+    let peer = match tls_peer_chain(address, timeout) {
+        Ok(peer) => peer,
+        Err(e) => return Revocation::Unknown(e),
+    };
+    let Some((leaf, intermediates)) = peer.certs.split_first() else {
+        return Revocation::Unknown(tr("cert.empty").to_string());
+    };
+    let urls = crl_urls(leaf);
+    if urls.is_empty() {
+        return Revocation::Unknown(tr("revocation.no_crl").to_string());
+    }
+    // Qualquer um dos pontos de distribuição serve; fica com o primeiro que responder
+    let mut last_error = String::new();
+    let Some(crl) = urls.iter().find_map(|url| fetch_crl(client, url, timeout).map_err(|e| last_error = e).ok()) else {
+        return Revocation::Unknown(last_error);
+    };
+
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = match rustls::client::WebPkiServerVerifier::builder_with_provider(trust_roots(), provider)
+        .with_crls([rustls::pki_types::CertificateRevocationListDer::from(crl.as_slice().to_vec())])
+        .only_check_end_entity_revocation()
+        .build()
+    {
+        Ok(verifier) => verifier,
+        Err(e) => return Revocation::Unknown(e.to_string()),
+    };
+    use rustls::client::danger::ServerCertVerifier;
+    match verifier.verify_server_cert(leaf, intermediates, &peer.server_name, &[], rustls::pki_types::UnixTime::now()) {
+        Ok(_) => Revocation::Good,
+        Err(rustls::Error::InvalidCertificate(rustls::CertificateError::Revoked)) => Revocation::Revoked,
+        Err(e) => Revocation::Unknown(e.to_string()),
+    }
+}
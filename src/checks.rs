@@ -0,0 +1,1122 @@
+//! Tipos de checagem e execução das checagens do ciclo.
+
+use super::*;
+
+/// Resultado bruto de uma checagem, antes da histerese.
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub success: bool,
+    pub message: String,
+    pub latency_ms: Option<f64>,
+    /// Respondeu, mas mal: perda parcial, HTTP 5xx ou latência acima de `degraded_ms`.
+    pub degraded: bool,
+    /// Hash do conteúdo acompanhado (ver `apply_content_fingerprint`).
+    pub fingerprint: Option<String>,
+    /// Perda (%) medida quando a checagem envia vários pings.
+    pub loss_pct: Option<f64>,
+    /// Tempo (ms) para resolver o nome do destino, medido à parte da checagem.
+    pub dns_ms: Option<f64>,
+}
+
+impl CheckOutcome {
+    pub fn up(message: String, latency_ms: Option<f64>) -> Self {
+        Self { success: true, message, latency_ms, degraded: false, fingerprint: None, loss_pct: None, dns_ms: None }
+    }
+
+    pub fn degraded(message: String, latency_ms: Option<f64>) -> Self {
+        Self { success: true, message, latency_ms, degraded: true, fingerprint: None, loss_pct: None, dns_ms: None }
+    }
+
+    pub fn down(message: &str) -> Self {
+        Self { success: false, message: message.to_string(), latency_ms: None, degraded: false, fingerprint: None, loss_pct: None, dns_ms: None }
+    }
+}
+
+/// Tempo (ms) que o resolvedor do sistema leva para achar o host do endereço.
+/// `None` para IPs, destinos `dns://` (a própria checagem já mede isso) e
+/// nomes que não resolveram.
+pub(crate) fn dns_resolution_ms(address: &str) -> Option<f64> {
+    use std::net::ToSocketAddrs;
+
+    if target_ip_literal(address).is_some() || check_name(address) == "dns" {
+        return None;
+    }
+    let (host, port) = split_host_port(address.trim())?;
+    let started = Instant::now();
+    let mut addrs = (host.as_str(), port.unwrap_or(0)).to_socket_addrs().ok()?;
+    addrs.next()?;
+    Some(started.elapsed().as_secs_f64() * 1000.0)
+}
+
+/// Uma checagem pendente no ciclo atual.
+#[derive(Clone)]
+pub(crate) struct CheckJob {
+    pub host: String,
+    /// Endereço passado à checagem; difere de `host` quando o tipo é forçado.
+    /// `Err` leva a mensagem de configuração inválida para o menu.
+    pub probe: Result<String, String>,
+    pub attempts: u8,
+    pub probes: u8,
+    pub degraded_ms: Option<f64>,
+    /// Conferir a revogação do certificado (só endereços `https://`).
+    pub revocation: bool,
+    /// Cabeçalhos exigidos na resposta (só endereços HTTP), já sem apelidos.
+    pub headers: Vec<String>,
+    /// Seletor do conteúdo acompanhado; vazio para o corpo inteiro.
+    pub content: Option<String>,
+    /// Idade máxima do item mais recente do feed servido pelo endereço.
+    pub feed_max_age: Option<Duration>,
+    /// Checagens extras combinadas com `host` (ver `combine_outcomes`).
+    pub extra_checks: Vec<String>,
+    pub policy: CheckPolicy,
+    /// Script de pós-processamento do destino.
+    pub script: Option<String>,
+    /// Timeout próprio do destino (`timeout_ms`).
+    pub timeout: Option<Duration>,
+}
+
+impl CheckJob {
+    /// Checagem de `host` (o endereço já normalizado) com as opções do destino.
+    pub fn from_target(host: String, target: &TargetConfig) -> Self {
+        Self {
+            host,
+            probe: target.probe_address(),
+            attempts: target.attempts(),
+            probes: target.probes(),
+            degraded_ms: target.degraded_ms,
+            revocation: target.revocation,
+            headers: target.headers.iter().map(|h| header_alias(h)).filter(|h| !h.is_empty()).collect(),
+            content: target.content_watch(),
+            feed_max_age: target.feed_max_age_hours.filter(|h| *h > 0.0).map(|h| Duration::from_secs_f64(h * 3600.0)),
+            extra_checks: target.checks.iter().filter_map(|c| normalize_target(c)).collect(),
+            policy: target.policy,
+            script: target.script.clone().filter(|s| !s.trim().is_empty()),
+            timeout: target.timeout(),
+        }
+    }
+}
+
+/// Distribuição dos inícios das checagens, para não disparar tudo no mesmo instante.
+pub(crate) struct CheckPacing {
+    /// O job `i` de `n` começa em `i * spread / n` a partir do início do ciclo.
+    pub spread: Duration,
+    /// Atraso aleatório entre zero e este valor, somado ao deslocamento.
+    pub jitter: Duration,
+}
+
+impl CheckPacing {
+    pub fn start_offset(&self, index: usize, total: usize) -> Duration {
+        let staggered = self.spread.mul_f64(index as f64 / total.max(1) as f64);
+        let jitter = Duration::from_millis(fastrand::u64(0..=self.jitter.as_millis() as u64));
+        staggered + jitter
+    }
+}
+
+/// Tetos de tempo das checagens do ciclo.
+#[derive(Clone, Copy, Debug)]
+pub struct CheckLimits {
+    /// Tempo total de uma checagem (`check_budget_secs`), somando tentativas,
+    /// extras e script. Um `timeout_ms` maior do destino estende o orçamento.
+    pub budget: Duration,
+    /// Timeout do cliente HTTP (`http_timeout_secs`).
+    pub http: Duration,
+}
+
+impl AppConfig {
+    pub fn check_limits(&self) -> CheckLimits {
+        CheckLimits { budget: Duration::from_secs(self.check_budget_secs.max(1)), http: self.http_timeout() }
+    }
+}
+
+/// Executa as checagens com no máximo `parallelism` ao mesmo tempo, devolvendo
+/// os resultados na mesma ordem dos jobs. Cada uma roda na própria thread de
+/// trabalho; o orçamento vale pelos timeouts de cada tentativa (ver `run_check_job`).
+/// Com o encerramento pedido, as que ainda não começaram ficam sem resultado.
+pub(crate) fn run_checks(
+    jobs: &[CheckJob],
+    client: Option<&Client>,
+    parallelism: usize,
+    limits: CheckLimits,
+    pacing: &CheckPacing,
+) -> Vec<CheckOutcome> {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let started = Instant::now();
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<CheckOutcome>>> = Mutex::new(jobs.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..parallelism.min(jobs.len()) {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(job) = jobs.get(index) else { break };
+                let start_at = started + pacing.start_offset(index, jobs.len());
+                sleep_unless_shutdown(start_at.saturating_duration_since(Instant::now()));
+                if shutdown_requested() {
+                    break;
+                }
+
+                let outcome = run_check_job(job, client, limits);
+                if let Ok(mut results) = results.lock() {
+                    results[index] = Some(outcome);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .into_iter()
+        .map(|outcome| outcome.unwrap_or_else(|| CheckOutcome::down(tr("check.error"))))
+        .collect()
+}
+
+/// Uma checagem completa do ciclo: principal, auditorias, extras e script.
+/// Cada tentativa recebe no máximo a sua fração do orçamento, e as etapas
+/// seguintes são puladas quando ele acaba, com a checagem contando como falha.
+pub(crate) fn run_check_job(job: &CheckJob, client: Option<&Client>, limits: CheckLimits) -> CheckOutcome {
+    let started = Instant::now();
+    let tries = u32::from(job.attempts.max(job.probes).max(1));
+    // Um timeout próprio maior que o orçamento global estende o orçamento
+    let budget = job.timeout.map_or(limits.budget, |t| limits.budget.max(t * tries));
+    let limit = budget / tries;
+    let ctx = CheckContext {
+        attempts: job.attempts,
+        probes: job.probes,
+        http_client: client,
+        timeout: job.timeout,
+        limit: Some(limit),
+        http_timeout: Some(job.timeout.unwrap_or(limits.http).min(limit)),
+    };
+    let expired = || {
+        let over = started.elapsed() >= budget;
+        if over {
+            warn!("[CHECK] {} excedeu {:?}", job.host, budget);
+        }
+        over
+    };
+    let timed_out = || CheckOutcome::down(tr("check.timeout"));
+
+    // Antes da checagem, para o tempo de DNS não ficar escondido na latência
+    let dns_ms = job.probe.as_deref().ok().and_then(dns_resolution_ms);
+    let mut outcome = match &job.probe {
+        Ok(address) => run_check(address, &ctx),
+        Err(e) => CheckOutcome::down(e),
+    };
+    if expired() {
+        return timed_out();
+    }
+    if let (true, Ok(address), Some(client)) = (job.revocation, &job.probe, client) {
+        if outcome.success && address.starts_with("https://") {
+            // O que sobrou do orçamento, sem passar do timeout HTTP do destino
+            let timeout = budget.saturating_sub(started.elapsed()).min(ctx.http_timeout.unwrap_or(limits.http));
+            outcome = apply_revocation(outcome, address, client, timeout);
+        }
+    }
+    if let (Ok(address), Some(client)) = (&job.probe, client) {
+        if outcome.success && !job.headers.is_empty() && check_name(address) == "http" {
+            outcome = apply_header_audit(outcome, address, client, &job.headers);
+        }
+    }
+    if let (Some(selector), Ok(address), Some(client)) = (&job.content, &job.probe, client) {
+        if outcome.success && check_name(address) == "http" {
+            outcome = apply_content_fingerprint(outcome, address, client, selector);
+        }
+    }
+    if let (Some(max_age), Ok(address), Some(client)) = (job.feed_max_age, &job.probe, client) {
+        if outcome.success && check_name(address) == "http" {
+            outcome = apply_feed_freshness(outcome, address, client, max_age);
+        }
+    }
+    if !job.extra_checks.is_empty() {
+        let probe_name = job.probe.as_deref().map_or("?", check_name);
+        let mut parts = vec![(probe_name, outcome)];
+        for extra in &job.extra_checks {
+            if expired() {
+                return timed_out();
+            }
+            parts.push((check_name(extra), run_check(extra, &ctx)));
+        }
+        outcome = combine_outcomes(job.policy, parts);
+    }
+    if expired() {
+        return timed_out();
+    }
+    let mut outcome = match &job.script {
+        Some(script) => run_script(script, &job.host, outcome, client),
+        None => outcome,
+    };
+    if let (Some(limit), Some(latency)) = (job.degraded_ms, outcome.latency_ms) {
+        outcome.degraded |= outcome.success && latency > limit;
+    }
+    outcome.dns_ms = dns_ms;
+    outcome
+}
+
+/// Marca como degradado um destino online cujo certificado foi revogado ou
+/// cuja revogação não pôde ser conferida.
+pub(crate) fn apply_revocation(mut outcome: CheckOutcome, address: &str, client: &Client, timeout: Duration) -> CheckOutcome {
+    let note = match check_revocation(address, client, timeout) {
+        Revocation::Good => return outcome,
+        Revocation::Revoked => tr("revocation.revoked").to_string(),
+        Revocation::Unknown(e) => {
+            warn!("[CHECK] Revogação de {} indeterminada: {}", address, e);
+            trf("revocation.unknown", &[("error", &e)])
+        }
+    };
+    outcome.message = format!("{} · {}", outcome.message, note);
+    outcome.degraded = true;
+    outcome
+}
+
+/// Nome completo dos cabeçalhos de segurança mais comuns; outros nomes passam
+/// como estão, em minúsculas.
+pub(crate) fn header_alias(name: &str) -> String {
+    let name = name.trim().to_ascii_lowercase();
+    match name.as_str() {
+        "hsts" => "strict-transport-security".into(),
+        "csp" => "content-security-policy".into(),
+        "xfo" => "x-frame-options".into(),
+        "xcto" => "x-content-type-options".into(),
+        _ => name,
+    }
+}
+
+/// Cabeçalhos de `required` ausentes na resposta da URL. Usa HEAD e recorre
+/// ao GET quando o servidor não aceita HEAD, como a checagem HTTP.
+pub(crate) fn missing_headers(client: &Client, url: &str, required: &[String]) -> Result<Vec<String>, String> {
+    let resp = match client.head(url).send() {
+        Ok(resp) if resp.status() != StatusCode::METHOD_NOT_ALLOWED => resp,
+        _ => client.get(url).send().map_err(|e| e.to_string())?,
+    };
+    Ok(required.iter().filter(|name| !resp.headers().contains_key(name.as_str())).cloned().collect())
+}
+
+/// Marca como degradado um destino online que deixou de enviar algum dos
+/// cabeçalhos exigidos; a volta do cabeçalho gera o alerta de normalização.
+pub(crate) fn apply_header_audit(mut outcome: CheckOutcome, address: &str, client: &Client, required: &[String]) -> CheckOutcome {
+    let note = match missing_headers(client, address, required) {
+        Ok(missing) if missing.is_empty() => return outcome,
+        Ok(missing) => trf("headers.missing", &[("headers", &missing.join(", "))]),
+        Err(e) => {
+            warn!("[CHECK] Auditoria de cabeçalhos de {} falhou: {}", address, e);
+            trf("headers.failed", &[("error", &e)])
+        }
+    };
+    outcome.message = format!("{} · {}", outcome.message, note);
+    outcome.degraded = true;
+    outcome
+}
+
+pub(crate) fn content_state_path() -> PathBuf {
+    get_data_dir().join("content_state.json")
+}
+
+/// Última impressão digital de cada destino com `content`; gravada em disco
+/// para que um reinício não conte como mudança.
+pub(crate) fn load_content_hashes() -> HashMap<String, String> {
+    fs::read_to_string(content_state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_content_hashes(hashes: &HashMap<String, String>) {
+    let json = serde_json::to_string(hashes).unwrap_or_default();
+    if let Err(e) = fs::write(content_state_path(), json) {
+        error!("Erro ao gravar estado de conteúdo: {}", e);
+    }
+}
+
+/// SHA-256 do corpo da URL ou, com `selector`, do texto dos elementos
+/// selecionados, com os espaços normalizados para ignorar reindentação.
+pub(crate) fn content_fingerprint(client: &Client, url: &str, selector: &str) -> Result<String, String> {
+    use sha2::{Digest, Sha256};
+
+    let body = client
+        .get(url)
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .map_err(|e| e.to_string())?;
+    if selector.is_empty() {
+        return Ok(format!("{:x}", Sha256::digest(body.as_bytes())));
+    }
+    let parsed = scraper::Selector::parse(selector).map_err(|e| trf("content.bad_selector", &[("error", &e)]))?;
+    let document = scraper::Html::parse_document(&body);
+    let fragments: Vec<String> = document
+        .select(&parsed)
+        .map(|element| element.text().flat_map(str::split_whitespace).collect::<Vec<_>>().join(" "))
+        .collect();
+    if fragments.is_empty() {
+        return Err(trf("content.no_match", &[("selector", &selector)]));
+    }
+    Ok(format!("{:x}", Sha256::digest(fragments.join("\n").as_bytes())))
+}
+
+/// Anexa a impressão digital do conteúdo ao resultado; a comparação com a
+/// anterior fica no monitor. Sem impressão (erro ou seletor sem resultado),
+/// o destino fica degradado em vez de acusar uma mudança.
+pub(crate) fn apply_content_fingerprint(mut outcome: CheckOutcome, address: &str, client: &Client, selector: &str) -> CheckOutcome {
+    match content_fingerprint(client, address, selector) {
+        Ok(hash) => outcome.fingerprint = Some(hash),
+        Err(e) => {
+            warn!("[CHECK] Conteúdo de {} indisponível: {}", address, e);
+            outcome.message = format!("{} · {}", outcome.message, trf("content.failed", &[("error", &e)]));
+            outcome.degraded = true;
+        }
+    }
+    outcome
+}
+
+/// Data (Unix) do item mais recente de um feed RSS, Atom ou JSON Feed;
+/// `None` se nenhum item tem data legível.
+pub(crate) fn newest_feed_item(body: &str) -> Result<Option<i64>, String> {
+    let parse_date = |raw: &str| {
+        let raw = raw.trim();
+        chrono::DateTime::parse_from_rfc2822(raw)
+            .or_else(|_| chrono::DateTime::parse_from_rfc3339(raw))
+            .ok()
+            .map(|date| date.timestamp())
+    };
+
+    if body.trim_start().starts_with('{') {
+        let feed: serde_json::Value = serde_json::from_str(body).map_err(|e| e.to_string())?;
+        let items = feed.get("items").and_then(|i| i.as_array()).cloned().unwrap_or_default();
+        return Ok(items
+            .iter()
+            .filter_map(|item| {
+                let date = item.get("date_modified").or_else(|| item.get("date_published"))?;
+                parse_date(date.as_str()?)
+            })
+            .max());
+    }
+
+    // RSS usa `item` com `pubDate` (ou `dc:date`); Atom usa `entry` com `updated`/`published`
+    let document = roxmltree::Document::parse(body).map_err(|e| e.to_string())?;
+    Ok(document
+        .descendants()
+        .filter(|node| matches!(node.tag_name().name(), "item" | "entry"))
+        .flat_map(|item| item.children())
+        .filter(|field| matches!(field.tag_name().name(), "pubDate" | "date" | "updated" | "published"))
+        .filter_map(|field| parse_date(field.text()?))
+        .max())
+}
+
+/// Derruba um destino cujo feed parou de receber itens há mais de `max_age`,
+/// mesmo com o servidor respondendo.
+pub(crate) fn apply_feed_freshness(mut outcome: CheckOutcome, address: &str, client: &Client, max_age: Duration) -> CheckOutcome {
+    let body = client
+        .get(address)
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.text())
+        .map_err(|e| e.to_string());
+    let newest = match body.and_then(|body| newest_feed_item(&body)) {
+        Ok(Some(newest)) => newest,
+        Ok(None) => return CheckOutcome::down(tr("feed.empty")),
+        Err(e) => return CheckOutcome::down(&trf("feed.error", &[("error", &e)])),
+    };
+    let age = Local::now().timestamp() - newest;
+    let age_label = format_duration(age);
+    if age > max_age.as_secs() as i64 {
+        let limit = format_duration(max_age.as_secs() as i64);
+        return CheckOutcome::down(&trf("feed.stale", &[("age", &age_label), ("limit", &limit)]));
+    }
+    outcome.message = format!("{} · {}", outcome.message, trf("feed.fresh", &[("age", &age_label)]));
+    outcome
+}
+
+/// `attempts` vale só para ping; o HTTP já tem o timeout do cliente.
+pub(crate) fn build_http_client(timeout: Duration) -> Option<Client> {
+    Client::builder()
+        .timeout(timeout)
+        .user_agent(format!("CosmicPinger/{}", APP_VERSION))
+        .build()
+        .map_err(|err| {
+            error!("Falha ao criar cliente HTTP: {}", err);
+            err
+        })
+        .ok()
+}
+
+/// Checagem avulsa de um destino (botão "Testar" da janela); `timeout` é o
+/// do cliente HTTP e `target_timeout`, o `timeout_ms` de um destino configurado.
+pub fn test_target(address: &str, attempts: u8, probes: u8, timeout: Duration, target_timeout: Option<Duration>) -> CheckOutcome {
+    match normalize_target(address) {
+        Some(cleaned) => check_target(&cleaned, attempts, probes, build_http_client(timeout).as_ref(), target_timeout),
+        None => CheckOutcome::down(tr("check.no_valid_targets")),
+    }
+}
+
+/// O que uma checagem recebe além do endereço.
+pub struct CheckContext<'a> {
+    pub attempts: u8,
+    /// Pings por checagem; acima de 1 usa `do_ping_probes`.
+    pub probes: u8,
+    pub http_client: Option<&'a Client>,
+    /// `timeout_ms` do destino; `None` mantém os timeouts globais.
+    pub timeout: Option<Duration>,
+    /// Teto de cada tentativa, tirado do orçamento da checagem; `None` fora do
+    /// ciclo (botão "Testar", `/probe`).
+    pub limit: Option<Duration>,
+    /// Timeout de cada requisição HTTP; `None` deixa valer o do cliente.
+    pub http_timeout: Option<Duration>,
+}
+
+impl CheckContext<'_> {
+    fn capped(&self, default: Duration) -> Duration {
+        let wait = self.timeout.unwrap_or(default);
+        self.limit.map_or(wait, |limit| wait.min(limit))
+    }
+
+    pub fn ping_wait(&self) -> Duration {
+        self.capped(Duration::from_millis(PING_WAIT_MS))
+    }
+
+    pub fn tcp_timeout(&self) -> Duration {
+        self.capped(Duration::from_millis(TCP_CONNECT_TIMEOUT_MS))
+    }
+
+    pub fn dns_timeout(&self) -> Duration {
+        self.capped(Duration::from_millis(DNS_TIMEOUT_MS))
+    }
+}
+
+/// Tipo de checagem, escolhido pelo esquema do endereço (`https://...`).
+/// Endereços sem esquema vão para o ping.
+pub trait Check: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Esquemas atendidos, em minúsculas e sem `://`.
+    fn schemes(&self) -> &'static [&'static str];
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome;
+}
+
+/// ICMP pelo backend de `pinger()`; também atende `ping://host`.
+pub(crate) struct PingCheck;
+
+impl Check for PingCheck {
+    fn name(&self) -> &'static str {
+        "ping"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["ping"]
+    }
+
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome {
+        let host = target.strip_prefix("ping://").unwrap_or(target).trim_end_matches('/');
+        let outcome = if ctx.probes > 1 {
+            do_ping_probes(host, ctx.probes, ctx.ping_wait())
+        } else {
+            do_ping(host, ctx.attempts, ctx.ping_wait())
+        };
+        if outcome.success {
+            note_icmp_reply(host);
+            outcome
+        } else if pinger().warning().is_some() {
+            // O TcpPinger já tentou as mesmas portas do fallback
+            outcome
+        } else {
+            icmp_fallback(host, outcome, ctx.tcp_timeout())
+        }
+    }
+}
+
+/// `tcp://host:porta`: online se a porta aceitar conexão.
+pub(crate) struct TcpCheck;
+
+impl Check for TcpCheck {
+    fn name(&self) -> &'static str {
+        "tcp"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["tcp"]
+    }
+
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome {
+        let rest = target.split_once("://").map_or(target, |(_, rest)| rest);
+        let Some((host, Some(port))) = split_host_port(rest) else {
+            return CheckOutcome::down(&trf("check.tcp_no_port", &[("target", &target)]));
+        };
+        match tcp_connect(&host, port, ctx.tcp_timeout()) {
+            Ok(latency) => CheckOutcome::up(trf("check.tcp_open", &[("port", &port), ("latency", &format!("{:.1}", latency))]), Some(latency)),
+            Err(e) => {
+                debug!("[TCP] {}:{} falhou: {}", host, port, e);
+                CheckOutcome::down(&trf("check.tcp_closed", &[("port", &port)]))
+            }
+        }
+    }
+}
+
+/// `ports://host:22,80,443`: online só se todas as portas aceitarem conexão;
+/// a mensagem lista as fechadas, para pegar firewalls mal configurados.
+pub(crate) struct PortsCheck;
+
+impl Check for PortsCheck {
+    fn name(&self) -> &'static str {
+        "ports"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["ports"]
+    }
+
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome {
+        let rest = target.split_once("://").map_or(target, |(_, rest)| rest).trim_end_matches('/');
+        let parsed = rest.rsplit_once(':').and_then(|(host, list)| {
+            let ports: Result<Vec<u16>, _> = list.split(',').map(|p| p.trim().parse::<u16>()).collect();
+            Some((host, ports.ok()?)).filter(|(host, ports)| !host.is_empty() && !ports.is_empty())
+        });
+        let Some((host, ports)) = parsed else {
+            return CheckOutcome::down(&trf("check.tcp_no_port", &[("target", &target)]));
+        };
+
+        // Em paralelo: com várias portas filtradas, em série somaria os timeouts
+        let timeout = ctx.tcp_timeout();
+        let results: Vec<(u16, Option<f64>)> = thread::scope(|scope| {
+            let handles: Vec<_> = ports
+                .iter()
+                .map(|&port| scope.spawn(move || (port, tcp_connect(host, port, timeout).ok())))
+                .collect();
+            handles.into_iter().filter_map(|h| h.join().ok()).collect()
+        });
+        let list = |open: bool| -> String {
+            results
+                .iter()
+                .filter(|(_, latency)| latency.is_some() == open)
+                .map(|(port, _)| port.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let latencies: Vec<f64> = results.iter().filter_map(|(_, latency)| *latency).collect();
+        if latencies.len() == results.len() {
+            let avg = latencies.iter().sum::<f64>() / latencies.len() as f64;
+            CheckOutcome::up(trf("check.ports_open", &[("ports", &list(true))]), Some(avg))
+        } else if latencies.is_empty() {
+            CheckOutcome::down(&trf("check.ports_all_closed", &[("ports", &list(false))]))
+        } else {
+            CheckOutcome::down(&trf("check.ports_closed", &[("closed", &list(false)), ("open", &list(true))]))
+        }
+    }
+}
+
+/// Tipos de registro aceitos em `dns://nome/TIPO`.
+pub(crate) const DNS_RECORD_TYPES: [(&str, u16); 5] = [("A", 1), ("AAAA", 28), ("MX", 15), ("TXT", 16), ("PTR", 12)];
+
+/// Consulta `dns://nome[/TIPO][?server=IP&expect=v1,v2]` já decomposta.
+pub(crate) struct DnsQuery {
+    pub name: String,
+    pub kind: &'static str,
+    pub qtype: u16,
+    pub server: Option<String>,
+    /// Valores esperados, já em forma canônica (`normalize_dns_value`).
+    pub expect: Vec<String>,
+}
+
+impl DnsQuery {
+    pub fn parse(target: &str) -> Option<Self> {
+        let rest = target.split_once("://").map_or(target, |(_, rest)| rest);
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (name, kind) = path.trim_end_matches('/').split_once('/').unwrap_or((path.trim_end_matches('/'), "A"));
+        let (kind, qtype) = DNS_RECORD_TYPES.iter().copied().find(|(k, _)| k.eq_ignore_ascii_case(kind))?;
+        let name = name.trim().trim_end_matches('.');
+        if name.is_empty() || name.split('.').any(|label| label.is_empty() || label.len() > 63) {
+            return None;
+        }
+        let mut server = None;
+        let mut expect = Vec::new();
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "server" => server = Some(value.trim().to_string()),
+                "expect" => expect.extend(value.split(',').map(|v| normalize_dns_value(v, qtype)).filter(|v| !v.is_empty())),
+                _ => {}
+            }
+        }
+        Some(Self { name: name.to_string(), kind, qtype, server, expect })
+    }
+}
+
+/// Forma canônica de um valor para comparação: IPs reescritos, nomes sem
+/// ponto final e em minúsculas, TXT sem aspas e com a caixa original.
+pub(crate) fn normalize_dns_value(raw: &str, qtype: u16) -> String {
+    let value = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    match qtype {
+        16 => value.trim_matches('"').to_string(),
+        1 | 28 => value.parse::<std::net::IpAddr>().map_or(value, |ip| ip.to_string()),
+        _ => value.trim_end_matches('.').to_ascii_lowercase(),
+    }
+}
+
+/// Primeiro `nameserver` de /etc/resolv.conf.
+pub(crate) fn system_nameserver() -> Option<String> {
+    fs::read_to_string("/etc/resolv.conf").ok()?.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        (fields.next() == Some("nameserver")).then(|| fields.next().map(str::to_string)).flatten()
+    })
+}
+
+/// Nome (possivelmente comprimido) em `msg[pos..]`: (nome, posição seguinte).
+pub(crate) fn dns_name(msg: &[u8], mut pos: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut next = None;
+    // Limita os saltos para não girar em ponteiros circulares
+    for _ in 0..64 {
+        let len = *msg.get(pos)? as usize;
+        if len & 0xc0 == 0xc0 {
+            let target = ((len & 0x3f) << 8) | *msg.get(pos + 1)? as usize;
+            next.get_or_insert(pos + 2);
+            pos = target;
+        } else if len == 0 {
+            return Some((labels.join("."), next.unwrap_or(pos + 1)));
+        } else {
+            labels.push(String::from_utf8_lossy(msg.get(pos + 1..pos + 1 + len)?).into_owned());
+            pos += 1 + len;
+        }
+    }
+    None
+}
+
+/// Valores dos registros de resposta do tipo `qtype`, já normalizados.
+/// `Err` traz o rcode do servidor.
+pub(crate) fn parse_dns_response(msg: &[u8], id: u16, qtype: u16) -> Option<Result<Vec<String>, u8>> {
+    let word = |at: usize| -> Option<u16> { Some(u16::from_be_bytes([*msg.get(at)?, *msg.get(at + 1)?])) };
+    // Cabeçalho incompleto: resposta truncada, forjada ou de um resolvedor quebrado
+    if msg.len() < 12 || word(0)? != id || msg[2] & 0x80 == 0 {
+        return None;
+    }
+    let rcode = msg[3] & 0x0f;
+    if rcode != 0 {
+        return Some(Err(rcode));
+    }
+    let (questions, answers) = (word(4)?, word(6)?);
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = dns_name(msg, pos)?.1 + 4;
+    }
+    let mut values = Vec::new();
+    for _ in 0..answers {
+        pos = dns_name(msg, pos)?.1;
+        let (rtype, rdlen) = (word(pos)?, word(pos + 8)? as usize);
+        let start = pos + 10;
+        let rdata = msg.get(start..start + rdlen)?;
+        pos = start + rdlen;
+        if rtype != qtype {
+            continue;
+        }
+        let value = match rtype {
+            1 if rdlen == 4 => std::net::Ipv4Addr::new(rdata[0], rdata[1], rdata[2], rdata[3]).to_string(),
+            28 if rdlen == 16 => std::net::Ipv6Addr::from(<[u8; 16]>::try_from(rdata).ok()?).to_string(),
+            12 => dns_name(msg, start)?.0,
+            15 => format!("{} {}", word(start)?, dns_name(msg, start + 2)?.0),
+            16 => {
+                // Várias strings de um mesmo TXT são concatenadas
+                let mut text = Vec::new();
+                let mut at = 0;
+                while let Some(&len) = rdata.get(at) {
+                    text.extend_from_slice(rdata.get(at + 1..at + 1 + len as usize)?);
+                    at += 1 + len as usize;
+                }
+                String::from_utf8_lossy(&text).into_owned()
+            }
+            _ => continue,
+        };
+        values.push(normalize_dns_value(&value, qtype));
+    }
+    Some(Ok(values))
+}
+
+/// Envia a consulta por UDP e devolve (valores, latência em ms).
+pub(crate) fn dns_lookup(query: &DnsQuery, attempts: u8, timeout: Duration) -> Result<(Result<Vec<String>, u8>, f64), String> {
+    use std::net::{IpAddr, SocketAddr, UdpSocket};
+
+    let server = query.server.clone().or_else(system_nameserver).ok_or_else(|| tr("check.dns_no_server").to_string())?;
+    let server: SocketAddr = server
+        .parse()
+        .or_else(|_| server.trim_matches(['[', ']']).parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 53)))
+        .map_err(|_| trf("check.dns_bad_server", &[("server", &server)]))?;
+
+    let id = fastrand::u16(..);
+    let mut packet = Vec::with_capacity(64);
+    packet.extend_from_slice(&id.to_be_bytes());
+    // RD ligado; uma pergunta e um registro OPT (EDNS0) para respostas TXT grandes
+    packet.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 1]);
+    for label in query.name.split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&query.qtype.to_be_bytes());
+    packet.extend_from_slice(&[0, 1]);
+    packet.extend_from_slice(&[0, 0, 41, 0x04, 0xd0, 0, 0, 0, 0, 0, 0]);
+
+    let bind: SocketAddr = if server.is_ipv4() { ([0, 0, 0, 0], 0).into() } else { (std::net::Ipv6Addr::UNSPECIFIED, 0).into() };
+    let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+    socket.connect(server).map_err(|e| e.to_string())?;
+    socket.set_read_timeout(Some(timeout.max(Duration::from_millis(1)))).map_err(|e| e.to_string())?;
+    let mut last_error = String::new();
+    let mut buf = [0u8; 4096];
+    for _ in 0..attempts.max(1) {
+        let started = Instant::now();
+        socket.send(&packet).map_err(|e| e.to_string())?;
+        match socket.recv(&mut buf) {
+            Ok(len) => {
+                let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+                if buf[..len].get(2).is_some_and(|flags| flags & 0x02 != 0) {
+                    return Err(tr("check.dns_truncated").to_string());
+                }
+                match parse_dns_response(&buf[..len], id, query.qtype) {
+                    Some(answer) => return Ok((answer, elapsed)),
+                    None => last_error = tr("check.dns_bad_response").to_string(),
+                }
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+/// Nome da consulta reversa: `4.3.2.1.in-addr.arpa` ou os nibbles em `ip6.arpa`.
+pub(crate) fn reverse_dns_name(ip: std::net::IpAddr) -> String {
+    match ip {
+        std::net::IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        std::net::IpAddr::V6(v6) => {
+            let nibbles: Vec<String> =
+                v6.octets().iter().rev().flat_map(|byte| [byte & 0x0f, byte >> 4]).map(|n| format!("{:x}", n)).collect();
+            format!("{}.ip6.arpa", nibbles.join("."))
+        }
+    }
+}
+
+/// Registro PTR do IP; `Ok(None)` quando não existe.
+pub(crate) fn reverse_lookup(ip: std::net::IpAddr) -> Result<Option<String>, String> {
+    let query = DnsQuery { name: reverse_dns_name(ip), kind: "PTR", qtype: 12, server: None, expect: Vec::new() };
+    match dns_lookup(&query, 2, Duration::from_millis(DNS_TIMEOUT_MS))?.0 {
+        Ok(names) => Ok(names.into_iter().next()),
+        // NXDOMAIN: o IP simplesmente não tem nome reverso
+        Err(3) => Ok(None),
+        Err(rcode) => Err(format!("rcode {}", rcode)),
+    }
+}
+
+/// Consulta DNS com asserção opcional dos valores: `expect` precisa bater
+/// exatamente com o conjunto respondido (ordem não importa).
+pub(crate) struct DnsCheck;
+
+impl Check for DnsCheck {
+    fn name(&self) -> &'static str {
+        "dns"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["dns"]
+    }
+
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome {
+        let Some(query) = DnsQuery::parse(target) else {
+            return CheckOutcome::down(&trf("check.dns_bad_target", &[("target", &target)]));
+        };
+        let (answer, latency) = match dns_lookup(&query, ctx.attempts, ctx.dns_timeout()) {
+            Ok(result) => result,
+            Err(e) => return CheckOutcome::down(&e),
+        };
+        let mut records = match answer {
+            Ok(records) => records,
+            Err(3) => return CheckOutcome::down(&trf("check.dns_nxdomain", &[("name", &query.name)])),
+            Err(rcode) => return CheckOutcome::down(&trf("check.dns_rcode", &[("rcode", &rcode)])),
+        };
+        if records.is_empty() {
+            return CheckOutcome::down(&trf("check.dns_empty", &[("kind", &query.kind), ("name", &query.name)]));
+        }
+        records.sort();
+        records.dedup();
+        let listed = records.join(", ");
+        if !query.expect.is_empty() {
+            let mut expected = query.expect.clone();
+            expected.sort();
+            expected.dedup();
+            if expected != records {
+                return CheckOutcome::down(&trf(
+                    "check.dns_mismatch",
+                    &[("kind", &query.kind), ("records", &listed), ("expected", &expected.join(", "))],
+                ));
+            }
+        }
+        CheckOutcome::up(trf("check.dns_records", &[("kind", &query.kind), ("records", &listed)]), Some(latency))
+    }
+}
+
+/// HEAD com fallback para GET; sucesso em 2xx/3xx.
+pub(crate) struct HttpCheck;
+
+impl Check for HttpCheck {
+    fn name(&self) -> &'static str {
+        "http"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["http", "https"]
+    }
+
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome {
+        match ctx.http_client {
+            Some(client) => do_http_check(client, target, ctx.http_timeout),
+            None => CheckOutcome::down(tr("check.http_unavailable")),
+        }
+    }
+}
+
+/// Checagens disponíveis indexadas pelo esquema. Tipos extras (inclusive de
+/// crates atrás de feature flags) entram com `register` num registro partindo
+/// de `builtin()`, instalado com `install_checks` antes de iniciar o monitor.
+pub struct CheckRegistry {
+    pub checks: Vec<Box<dyn Check>>,
+    pub by_scheme: HashMap<&'static str, usize>,
+    /// Usada quando o endereço não tem esquema.
+    pub fallback: usize,
+}
+
+impl CheckRegistry {
+    pub fn builtin() -> Self {
+        let mut registry = Self { checks: Vec::new(), by_scheme: HashMap::new(), fallback: 0 };
+        registry.register(Box::new(PingCheck));
+        registry.register(Box::new(HttpCheck));
+        registry.register(Box::new(TcpCheck));
+        registry.register(Box::new(PortsCheck));
+        registry.register(Box::new(DnsCheck));
+        #[cfg(feature = "scripting")]
+        registry.register(Box::new(ScriptCheck));
+        registry
+    }
+
+    /// Um esquema já registrado passa a apontar para a nova checagem.
+    pub fn register(&mut self, check: Box<dyn Check>) {
+        let index = self.checks.len();
+        for scheme in check.schemes() {
+            if let Some(previous) = self.by_scheme.insert(scheme, index) {
+                info!("[CHECK] Esquema {}:// passou de {} para {}", scheme, self.checks[previous].name(), check.name());
+            }
+        }
+        self.checks.push(check);
+    }
+
+    /// `None` quando o endereço tem um esquema sem checagem registrada.
+    pub fn resolve(&self, target: &str) -> Option<&dyn Check> {
+        let index = match target.split_once("://") {
+            Some((scheme, _)) => *self.by_scheme.get(scheme.to_ascii_lowercase().as_str())?,
+            None => self.fallback,
+        };
+        self.checks.get(index).map(Box::as_ref)
+    }
+}
+
+static CHECKS: OnceLock<CheckRegistry> = OnceLock::new();
+
+/// Troca o registro usado por todas as checagens. Só vale antes da primeira
+/// checagem; depois disso o registro em uso fica e o recebido volta no `Err`.
+pub fn install_checks(registry: CheckRegistry) -> Result<(), CheckRegistry> {
+    CHECKS.set(registry)
+}
+
+pub fn checks() -> &'static CheckRegistry {
+    CHECKS.get_or_init(CheckRegistry::builtin)
+}
+
+pub fn check_target(
+    target: &str,
+    attempts: u8,
+    probes: u8,
+    http_client: Option<&Client>,
+    timeout: Option<Duration>,
+) -> CheckOutcome {
+    run_check(target, &CheckContext { attempts, probes, http_client, timeout, limit: None, http_timeout: timeout })
+}
+
+/// Roda a checagem registrada para o esquema de `target`.
+pub fn run_check(target: &str, ctx: &CheckContext) -> CheckOutcome {
+    let Some(check) = checks().resolve(target) else {
+        let scheme = target.split_once("://").map_or("", |(scheme, _)| scheme);
+        return CheckOutcome::down(&trf("check.unsupported_scheme", &[("scheme", &scheme)]));
+    };
+    check.run(target, ctx)
+}
+
+/// Nome curto da checagem que atende o endereço, para rotular resultados combinados.
+pub(crate) fn check_name(target: &str) -> &'static str {
+    checks().resolve(target).map_or("?", |check| check.name())
+}
+
+/// Junta as checagens de um destino composto em um único resultado.
+/// A latência é a da primeira parte que respondeu (a principal, se possível)
+/// e a mensagem lista cada parte: `ping: 12.3 ms · http: HTTP 502`.
+pub(crate) fn combine_outcomes(policy: CheckPolicy, parts: Vec<(&'static str, CheckOutcome)>) -> CheckOutcome {
+    let success = match policy {
+        CheckPolicy::All => parts.iter().all(|(_, o)| o.success),
+        CheckPolicy::Any => parts.iter().any(|(_, o)| o.success),
+    };
+    let degraded = success && parts.iter().any(|(_, o)| !o.success || o.degraded);
+    let latency_ms = parts.iter().find(|(_, o)| o.success).and_then(|(_, o)| o.latency_ms);
+    let message = parts
+        .iter()
+        .map(|(name, o)| format!("{}: {}", name, o.message))
+        .collect::<Vec<_>>()
+        .join(" · ");
+    CheckOutcome { success, message, latency_ms: latency_ms.filter(|_| success), degraded, fingerprint: None, loss_pct: None, dns_ms: None }
+}
+
+/// `timeout` substitui o do cliente só nesta requisição.
+pub(crate) fn do_http_check(client: &Client, url: &str, timeout: Option<Duration>) -> CheckOutcome {
+    let started = Instant::now();
+    let mut request = client.head(url);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    match request.send() {
+        Ok(resp) => {
+            let status = resp.status();
+            if status == StatusCode::METHOD_NOT_ALLOWED {
+                return fetch_via_get(client, url, timeout);
+            }
+            summarize_http_status(status, started)
+        }
+        Err(err) => {
+            if err.is_timeout() {
+                return CheckOutcome::down("HTTP timeout");
+            }
+            warn!("HEAD falhou para {}: {}", url, err);
+            fetch_via_get(client, url, timeout)
+        }
+    }
+}
+
+pub(crate) fn fetch_via_get(client: &Client, url: &str, timeout: Option<Duration>) -> CheckOutcome {
+    let started = Instant::now();
+    let mut request = client.get(url);
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+    match request.send() {
+        Ok(resp) => summarize_http_status(resp.status(), started),
+        Err(err) => {
+            if err.is_timeout() {
+                CheckOutcome::down("HTTP timeout")
+            } else {
+                warn!("GET falhou para {}: {}", url, err);
+                CheckOutcome::down(tr("check.http_error"))
+            }
+        }
+    }
+}
+
+pub(crate) fn summarize_http_status(status: StatusCode, started: Instant) -> CheckOutcome {
+    let label = format!("HTTP {}", status.as_u16());
+    let ok = status.is_success() || status.is_redirection();
+    let latency = Some(started.elapsed().as_secs_f64() * 1000.0);
+    if ok {
+        CheckOutcome::up(label, latency)
+    } else if status.is_server_error() {
+        // O servidor respondeu: degradado, não offline
+        CheckOutcome::degraded(label, latency)
+    } else {
+        CheckOutcome { success: false, message: label, latency_ms: None, degraded: false, fingerprint: None, loss_pct: None, dns_ms: None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combine_all_and_any() {
+        let parts = || vec![("ping", CheckOutcome::up("ok".into(), Some(12.0))), ("http", CheckOutcome::down("HTTP 502"))];
+        let all = combine_outcomes(CheckPolicy::All, parts());
+        assert!(!all.success);
+        assert_eq!(all.latency_ms, None);
+        assert_eq!(all.message, "ping: ok · http: HTTP 502");
+
+        let any = combine_outcomes(CheckPolicy::Any, parts());
+        assert!(any.success && any.degraded);
+        assert_eq!(any.latency_ms, Some(12.0));
+    }
+
+    #[test]
+    fn header_aliases() {
+        assert_eq!(header_alias(" HSTS "), "strict-transport-security");
+        assert_eq!(header_alias("csp"), "content-security-policy");
+        assert_eq!(header_alias("X-Custom"), "x-custom");
+    }
+
+    #[test]
+    fn reverse_dns_names() {
+        assert_eq!(reverse_dns_name("192.168.0.1".parse().unwrap()), "1.0.168.192.in-addr.arpa");
+        assert!(reverse_dns_name("2001:db8::1".parse().unwrap()).ends_with(".8.b.d.0.1.0.0.2.ip6.arpa"));
+    }
+
+    #[test]
+    fn context_caps_waits_at_the_attempt_limit() {
+        let ctx = |timeout, limit| CheckContext { attempts: 1, probes: 1, http_client: None, timeout, limit, http_timeout: None };
+        assert_eq!(ctx(None, None).ping_wait(), Duration::from_millis(PING_WAIT_MS));
+        assert_eq!(ctx(None, Some(Duration::from_millis(300))).tcp_timeout(), Duration::from_millis(300));
+        assert_eq!(ctx(Some(Duration::from_secs(3)), Some(Duration::from_secs(2))).ping_wait(), Duration::from_secs(2));
+        assert_eq!(ctx(None, None).dns_timeout(), Duration::from_millis(DNS_TIMEOUT_MS));
+        assert_eq!(ctx(Some(Duration::from_millis(500)), None).dns_timeout(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn dns_lookup_honours_the_target_timeout() {
+        // Servidor que nunca responde: cada tentativa espera só o timeout dado
+        let silent = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        let server = silent.local_addr().unwrap().to_string();
+        let query = DnsQuery { name: "example.com".to_string(), kind: "A", qtype: 1, server: Some(server), expect: Vec::new() };
+        let started = Instant::now();
+        assert!(dns_lookup(&query, 2, Duration::from_millis(100)).is_err());
+        assert!(started.elapsed() < Duration::from_millis(DNS_TIMEOUT_MS));
+    }
+
+    #[test]
+    fn pacing_staggers_starts() {
+        let pacing = CheckPacing { spread: Duration::from_secs(10), jitter: Duration::ZERO };
+        assert_eq!(pacing.start_offset(0, 4), Duration::ZERO);
+        assert_eq!(pacing.start_offset(2, 4), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn dns_response_parses_a_records() {
+        let mut msg = vec![0x12, 0x34, 0x81, 0x80, 0, 1, 0, 1, 0, 0, 0, 0];
+        msg.extend_from_slice(&[1, b'a', 3, b'c', b'o', b'm', 0, 0, 1, 0, 1]);
+        msg.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 1, 2, 3, 4]);
+        assert_eq!(parse_dns_response(&msg, 0x1234, 1), Some(Ok(vec!["1.2.3.4".to_string()])));
+        assert_eq!(parse_dns_response(&msg, 0x4321, 1), None);
+        msg[3] = 0x83;
+        assert_eq!(parse_dns_response(&msg, 0x1234, 1), Some(Err(3)));
+    }
+
+    #[test]
+    fn dns_response_rejects_short_or_garbage_buffers() {
+        for len in 0..12 {
+            assert_eq!(parse_dns_response(&[0x12, 0x34, 0x80, 0, 0, 1, 0, 1, 0, 0, 0, 0][..len], 0x1234, 1), None);
+        }
+        // Contagens que apontam além do fim e ponteiros de nome em laço
+        assert_eq!(parse_dns_response(&[0x12, 0x34, 0x80, 0, 0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0], 0x1234, 1), None);
+        assert_eq!(parse_dns_response(&[0x12, 0x34, 0x80, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0xc0, 12], 0x1234, 1), None);
+        for seed in 0..200u64 {
+            let mut rng = fastrand::Rng::with_seed(seed);
+            let mut msg: Vec<u8> = (0..rng.usize(..64)).map(|_| rng.u8(..)).collect();
+            if msg.len() >= 3 {
+                msg[..3].copy_from_slice(&[0x12, 0x34, 0x80]);
+            }
+            let _ = parse_dns_response(&msg, 0x1234, [1, 12, 15, 16, 28][rng.usize(..5)]);
+        }
+    }
+
+    #[test]
+    fn unknown_scheme_is_down() {
+        let outcome = check_target("gopher://h", 1, 1, None, None);
+        assert!(!outcome.success);
+    }
+}
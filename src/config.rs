@@ -0,0 +1,1222 @@
+//! Configuração (`sites.json`), migrações do esquema e parâmetros de execução.
+
+use super::*;
+
+// Valores resolvidos na inicialização: flags de linha de comando têm prioridade
+// sobre variáveis de ambiente, que têm prioridade sobre `sites.json` e as
+// constantes de `lib.rs`. `None` significa "sem override".
+pub struct RuntimeSettings {
+    pub monitor_interval: Option<Duration>,
+    pub http_timeout: Option<Duration>,
+    pub config_path: Option<PathBuf>,
+    pub log_level: tracing::Level,
+    /// Também grava os logs em `cosmic_pinger.log` no diretório de dados.
+    pub log_file: bool,
+}
+
+pub static RUNTIME: OnceLock<RuntimeSettings> = OnceLock::new();
+
+/// Parâmetros em vigor. Os binários preenchem `RUNTIME` a partir da linha de
+/// comando; outros frontends caem nas variáveis de ambiente.
+pub fn runtime() -> &'static RuntimeSettings {
+    RUNTIME.get_or_init(|| {
+        RuntimeSettings::from_env().unwrap_or_else(|err| {
+            eprintln!("Aviso: {}; usando os valores padrão", err);
+            RuntimeSettings::default()
+        })
+    })
+}
+
+impl Default for RuntimeSettings {
+    fn default() -> Self {
+        Self {
+            monitor_interval: None,
+            http_timeout: None,
+            config_path: None,
+            log_level: tracing::Level::INFO,
+            log_file: false,
+        }
+    }
+}
+
+impl RuntimeSettings {
+    pub fn from_env() -> Result<Self, String> {
+        let mut settings = Self::default();
+        if let Ok(val) = std::env::var(ENV_INTERVAL) {
+            settings.monitor_interval = Some(parse_secs(ENV_INTERVAL, &val)?);
+        }
+        if let Ok(val) = std::env::var(ENV_TIMEOUT) {
+            settings.http_timeout = Some(parse_secs(ENV_TIMEOUT, &val)?);
+        }
+        if let Ok(val) = std::env::var(ENV_CONFIG_PATH) {
+            if !val.trim().is_empty() {
+                settings.config_path = Some(PathBuf::from(val));
+            }
+        }
+        if let Ok(val) = std::env::var(ENV_LOG_LEVEL) {
+            settings.log_level = parse_log_level(ENV_LOG_LEVEL, &val)?;
+        }
+        Ok(settings)
+    }
+}
+
+pub fn parse_secs(name: &str, raw: &str) -> Result<Duration, String> {
+    match raw.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+        _ => Err(format!("Valor inválido para {}: '{}' (esperado número de segundos > 0)", name, raw)),
+    }
+}
+
+pub fn parse_log_level(name: &str, raw: &str) -> Result<tracing::Level, String> {
+    raw.trim()
+        .parse()
+        .map_err(|_| format!("Valor inválido para {}: '{}' (use error, warn, info, debug ou trace)", name, raw))
+}
+
+// --- CONFIGURAÇÃO ---
+/// Versão atual do esquema de `sites.json`. Incrementar sempre que o formato
+/// mudar e registrar a migração correspondente em `CONFIG_MIGRATIONS`.
+pub const CONFIG_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AppConfig {
+    #[serde(default)]
+    pub version: u32,
+    pub targets: Vec<TargetConfig>,
+    /// Intervalo (s) entre os ciclos completos de checagem.
+    #[serde(default = "default_interval_secs")]
+    pub interval_secs: u64,
+    /// Timeout (s) das checagens HTTP; lido na inicialização.
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64,
+    /// Checagens seguidas com falha antes de um destino ficar offline; os
+    /// destinos podem sobrescrever com `fail_threshold`.
+    #[serde(default = "default_fail_threshold")]
+    pub fail_threshold: u8,
+    /// Múltiplo da latência de referência (EWMA) que dispara o alerta de anomalia; 0 desativa.
+    #[serde(default = "default_latency_alert_factor")]
+    pub latency_alert_factor: f64,
+    #[serde(default)]
+    pub report: ReportConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub influx: Option<InfluxConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics: Option<MetricsConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zabbix: Option<ZabbixConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geoip: Option<GeoIpConfig>,
+    /// Aviso de nova versão no menu; desligado sem esta seção.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_check: Option<UpdateCheckConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_page: Option<StatusPageConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api: Option<ApiConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matrix: Option<MatrixConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ntfy: Option<NtfyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gotify: Option<GotifyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pushover: Option<PushoverConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pagerduty: Option<PagerDutyConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opsgenie: Option<OpsgenieConfig>,
+    #[serde(default)]
+    pub desktop: DesktopConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub webhooks: Vec<WebhookConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<EmailConfig>,
+    /// Junta mudanças de vários destinos no mesmo ciclo em uma única notificação.
+    #[serde(default = "default_true")]
+    pub group_notifications: bool,
+    /// Máximo de alertas por destino em uma hora; 0 desativa o limite.
+    #[serde(default = "default_max_notifications_per_hour")]
+    pub max_notifications_per_hour: u32,
+    /// Intervalo (s) para rechecar destinos em queda entre os ciclos; 0 desativa.
+    #[serde(default = "default_outage_check_interval")]
+    pub outage_check_interval: u64,
+    /// Checagens executadas ao mesmo tempo em cada ciclo.
+    #[serde(default = "default_max_parallel_checks")]
+    pub max_parallel_checks: usize,
+    /// Tempo máximo (s) de uma checagem, incluindo tentativas; depois disso conta como falha.
+    #[serde(default = "default_check_budget_secs")]
+    pub check_budget_secs: u64,
+    /// Janela (s) em que os inícios das checagens são escalonados; 0 inicia todas
+    /// juntas. Sem valor, usa um décimo do intervalo (ver `check_spread`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_spread_secs: Option<u64>,
+    /// Atraso aleatório extra (ms) antes de cada checagem.
+    #[serde(default = "default_check_jitter_ms")]
+    pub check_jitter_ms: u64,
+    /// Amostras recentes mantidas em memória por destino, para o minigráfico e
+    /// a detecção de flapping; o histórico completo fica em `history.jsonl`.
+    #[serde(default = "default_recent_samples")]
+    pub recent_samples: usize,
+    /// Canais desligados para todos os destinos (mesmos nomes de `disabled_notifiers`
+    /// dos destinos). A configuração do canal é mantida para religá-lo depois.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_notifiers: Vec<String>,
+    #[serde(default)]
+    pub theme: ThemeChoice,
+    /// Idioma da interface (`pt`, `en` ou `auto`); ausente segue o locale do sistema.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Regras de agregação: destinos com a tag do grupo só alertam em conjunto.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupRule>,
+    /// Comando executado em toda queda/recuperação (ver `run_hook`); destinos
+    /// com `hook` próprio usam o deles.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook: Option<String>,
+}
+
+pub(crate) fn default_interval_secs() -> u64 {
+    MONITOR_INTERVAL_SECS
+}
+
+pub(crate) fn default_http_timeout_secs() -> u64 {
+    HTTP_TIMEOUT_SECS
+}
+
+pub(crate) fn default_fail_threshold() -> u8 {
+    FAIL_STREAK_THRESHOLD
+}
+
+pub(crate) fn default_max_notifications_per_hour() -> u32 {
+    DEFAULT_MAX_NOTIFICATIONS_PER_HOUR
+}
+
+pub(crate) fn default_outage_check_interval() -> u64 {
+    DEFAULT_OUTAGE_CHECK_SECS
+}
+
+pub(crate) fn default_max_parallel_checks() -> usize {
+    DEFAULT_MAX_PARALLEL_CHECKS
+}
+
+pub(crate) fn default_check_budget_secs() -> u64 {
+    DEFAULT_CHECK_BUDGET_SECS
+}
+
+pub(crate) fn default_check_jitter_ms() -> u64 {
+    DEFAULT_CHECK_JITTER_MS
+}
+
+pub(crate) fn default_recent_samples() -> usize {
+    DEFAULT_RECENT_SAMPLES
+}
+
+/// Gravidade de um alerta; também usada como limite mínimo nos filtros.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, PartialOrd, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Low,
+    Normal,
+    Critical,
+}
+
+impl Severity {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "low" => Some(Severity::Low),
+            "normal" => Some(Severity::Normal),
+            "critical" => Some(Severity::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Low => "low",
+            Severity::Normal => "normal",
+            Severity::Critical => "critical",
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn urgency(self) -> Urgency {
+        match self {
+            Severity::Low => Urgency::Low,
+            Severity::Normal => Urgency::Normal,
+            Severity::Critical => Urgency::Critical,
+        }
+    }
+}
+
+/// Filtros comuns a todos os canais de notificação.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct NotifierFilter {
+    /// Ignora alertas abaixo desta gravidade.
+    #[serde(default)]
+    pub min_severity: Severity,
+    /// Se preenchido, só recebe alertas de destinos com alguma destas tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl NotifierFilter {
+    pub fn accepts(&self, severity: Severity, target_tags: &[String]) -> bool {
+        severity >= self.min_severity
+            && (self.tags.is_empty() || self.tags.iter().any(|t| target_tags.contains(t)))
+    }
+}
+
+/// Notificações de desktop (ativadas por padrão).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DesktopConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+impl Default for DesktopConfig {
+    fn default() -> Self {
+        Self { enabled: true, filter: NotifierFilter::default() }
+    }
+}
+
+/// POST de um JSON com o alerta para uma URL arbitrária.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Valores aceitam os mesmos marcadores de `template`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// Corpo próprio com marcadores (`{target}`, `{message}`, ...). Um JSON é
+    /// enviado como JSON; uma string vai como corpo cru.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+/// E-mail enviado pelo `sendmail` do sistema (msmtp, postfix, ...).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct EmailConfig {
+    pub to: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(default = "default_sendmail")]
+    pub sendmail: String,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+pub(crate) fn default_sendmail() -> String {
+    "/usr/sbin/sendmail".to_string()
+}
+
+/// Envio de alertas para uma sala Matrix.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MatrixConfig {
+    /// URL base do homeserver, ex.: `https://matrix.org`.
+    pub homeserver: String,
+    pub access_token: String,
+    /// ID interno da sala (`!abc123:matrix.org`).
+    pub room_id: String,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+/// Publicação em um tópico ntfy (ntfy.sh ou instância própria).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NtfyConfig {
+    #[serde(default = "default_ntfy_server")]
+    pub server: String,
+    pub topic: String,
+    /// Token de acesso para tópicos protegidos.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+pub(crate) fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GotifyConfig {
+    /// URL base do servidor Gotify.
+    pub server: String,
+    /// Token de aplicação criado no Gotify.
+    pub app_token: String,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PushoverConfig {
+    pub app_token: String,
+    pub user_key: String,
+    /// Envia só para um dispositivo específico.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+/// Incidentes no PagerDuty (Events API v2): queda abre, recuperação resolve.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PagerDutyConfig {
+    /// Integration key do serviço (Events API v2).
+    pub routing_key: String,
+    /// Campo `source` do evento; padrão é o próprio destino.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+/// Alertas no Opsgenie: queda cria, recuperação fecha (alias por destino).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OpsgenieConfig {
+    pub api_key: String,
+    /// `https://api.eu.opsgenie.com` para contas na região europeia.
+    #[serde(default = "default_opsgenie_url")]
+    pub api_url: String,
+    /// Tags fixas somadas às tags do destino (`tags` é o filtro do canal).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alert_tags: Vec<String>,
+    #[serde(flatten)]
+    pub filter: NotifierFilter,
+}
+
+fn default_opsgenie_url() -> String {
+    "https://api.opsgenie.com".into()
+}
+
+/// API HTTP local opcional (lida apenas na inicialização do modo tray).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ApiConfig {
+    #[serde(default = "default_api_bind")]
+    pub bind: String,
+    /// Exigido em `Authorization: Bearer <token>` ou `?token=`.
+    pub token: String,
+}
+
+pub(crate) fn default_api_bind() -> String {
+    "127.0.0.1:8787".to_string()
+}
+
+/// Saída em line protocol do InfluxDB: via HTTP (`url`), arquivo local (`file`) ou ambos.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct InfluxConfig {
+    /// Endpoint de escrita completo, ex.: `http://host:8086/api/v2/write?org=o&bucket=b`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Token enviado como `Authorization: Token ...` (InfluxDB 2.x).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+    #[serde(default = "default_influx_measurement")]
+    pub measurement: String,
+}
+
+pub(crate) fn default_influx_measurement() -> String {
+    "cosmic_pinger".to_string()
+}
+
+/// Exportação de métricas para statsd (UDP) e/ou coletor OpenTelemetry (OTLP/HTTP JSON).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct MetricsConfig {
+    /// Endereço `host:porta` do servidor statsd.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statsd: Option<String>,
+    /// Endpoint OTLP de métricas, ex.: `http://localhost:4318/v1/metrics`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub otlp_url: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub otlp_headers: HashMap<String, String>,
+    /// Prefixo dos nomes das métricas, independente do `measurement` do InfluxDB.
+    #[serde(default = "default_metrics_prefix")]
+    pub prefix: String,
+}
+
+pub(crate) fn default_metrics_prefix() -> String {
+    "cosmic_pinger".to_string()
+}
+
+/// Envio dos resultados a um Zabbix server ou proxy pelo protocolo do
+/// `zabbix_sender`, para itens do tipo trapper.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ZabbixConfig {
+    /// `host:porta` do server ou proxy; sem porta, usa 10051.
+    pub server: String,
+    /// Host do Zabbix que recebe os itens dos destinos fora de `hosts`.
+    pub host: String,
+    /// Host do Zabbix por destino (endereço → host).
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub hosts: HashMap<String, String>,
+    /// Chaves dos itens; `{target}` vira o endereço entre aspas.
+    #[serde(default = "default_zabbix_up_key")]
+    pub up_key: String,
+    #[serde(default = "default_zabbix_latency_key")]
+    pub latency_key: String,
+}
+
+pub(crate) fn default_zabbix_up_key() -> String {
+    "cosmic_pinger.up[{target}]".to_string()
+}
+
+pub(crate) fn default_zabbix_latency_key() -> String {
+    "cosmic_pinger.latency[{target}]".to_string()
+}
+
+/// Dono (ASN) e país do IP de cada destino. Com bases MaxMind locais
+/// (GeoLite2 ASN/Country) nada sai da máquina; sem elas, consulta o RDAP.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct GeoIpConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asn_db: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country_db: Option<String>,
+}
+
+impl GeoIpConfig {
+    pub fn uses_mmdb(&self) -> bool {
+        self.asn_db.is_some() || self.country_db.is_some()
+    }
+}
+
+/// Consulta periódica das releases no GitHub.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct UpdateCheckConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_update_interval_hours")]
+    pub interval_hours: u64,
+    /// Lista de releases no formato da API do GitHub.
+    #[serde(default = "default_releases_url")]
+    pub url: String,
+    /// Também avisa de versões marcadas como pré-lançamento.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub prereleases: bool,
+}
+
+pub(crate) fn default_update_interval_hours() -> u64 {
+    24
+}
+
+pub(crate) fn default_releases_url() -> String {
+    "https://api.github.com/repos/ttavares369/cosmic_pinger/releases".to_string()
+}
+
+/// Frequência do resumo periódico de quedas e latências.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportPeriod {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl ReportPeriod {
+    pub fn secs(self) -> Option<i64> {
+        match self {
+            ReportPeriod::Off => None,
+            ReportPeriod::Daily => Some(DAY_SECS),
+            ReportPeriod::Weekly => Some(7 * DAY_SECS),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ReportConfig {
+    #[serde(default)]
+    pub period: ReportPeriod,
+    /// Envia o resumo como notificação.
+    #[serde(default = "default_true")]
+    pub notify: bool,
+    /// Grava o resumo como HTML em `<dados>/reports/`.
+    #[serde(default = "default_true")]
+    pub html: bool,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            period: ReportPeriod::Off,
+            notify: true,
+            html: true,
+        }
+    }
+}
+
+/// "Alertar só se ≥ `min_down` destinos com a tag `tag` estiverem offline".
+/// Os membros deixam de alertar individualmente; o grupo alerta ao cruzar o limite.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GroupRule {
+    pub name: String,
+    pub tag: String,
+    #[serde(default = "default_min_down")]
+    pub min_down: usize,
+}
+
+pub(crate) fn default_min_down() -> usize {
+    1
+}
+
+/// Página de status estática regravada a cada ciclo, para servir por um
+/// servidor web qualquer.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct StatusPageConfig {
+    pub path: PathBuf,
+    /// Título da página; padrão `APP_NAME`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Inclui a mensagem da última checagem (erros podem expor detalhes internos).
+    #[serde(default)]
+    pub show_details: bool,
+}
+
+/// Tema da janela de configuração; `system` segue a preferência do desktop.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeChoice {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 3] = [ThemeChoice::System, ThemeChoice::Light, ThemeChoice::Dark];
+}
+
+impl std::fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(tr(match self {
+            ThemeChoice::System => "config.theme_system",
+            ThemeChoice::Light => "config.theme_light",
+            ThemeChoice::Dark => "config.theme_dark",
+        }))
+    }
+}
+
+/// Como as checagens extras de um destino se combinam com a principal.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckPolicy {
+    /// Online só se todas responderem.
+    #[default]
+    All,
+    /// Online se qualquer uma responder; as demais falhas deixam o destino degradado.
+    Any,
+}
+
+impl CheckPolicy {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_lowercase().as_str() {
+            "all" => Some(CheckPolicy::All),
+            "any" => Some(CheckPolicy::Any),
+            _ => None,
+        }
+    }
+
+    pub fn is_all(&self) -> bool {
+        *self == CheckPolicy::All
+    }
+}
+
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+pub(crate) fn is_true(value: &bool) -> bool {
+    *value
+}
+
+pub(crate) fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+pub(crate) fn default_latency_alert_factor() -> f64 {
+    DEFAULT_LATENCY_ALERT_FACTOR
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            version: CONFIG_VERSION,
+            targets: vec![TargetConfig::new("google.com"), TargetConfig::new("1.1.1.1")],
+            interval_secs: MONITOR_INTERVAL_SECS,
+            http_timeout_secs: HTTP_TIMEOUT_SECS,
+            fail_threshold: FAIL_STREAK_THRESHOLD,
+            latency_alert_factor: DEFAULT_LATENCY_ALERT_FACTOR,
+            report: ReportConfig::default(),
+            influx: None,
+            metrics: None,
+            zabbix: None,
+            geoip: None,
+            update_check: None,
+            status_page: None,
+            api: None,
+            matrix: None,
+            ntfy: None,
+            gotify: None,
+            pushover: None,
+            pagerduty: None,
+            opsgenie: None,
+            desktop: DesktopConfig::default(),
+            webhooks: Vec::new(),
+            email: None,
+            group_notifications: true,
+            max_notifications_per_hour: DEFAULT_MAX_NOTIFICATIONS_PER_HOUR,
+            outage_check_interval: DEFAULT_OUTAGE_CHECK_SECS,
+            max_parallel_checks: DEFAULT_MAX_PARALLEL_CHECKS,
+            check_budget_secs: DEFAULT_CHECK_BUDGET_SECS,
+            check_spread_secs: None,
+            check_jitter_ms: DEFAULT_CHECK_JITTER_MS,
+            recent_samples: DEFAULT_RECENT_SAMPLES,
+            disabled_notifiers: Vec::new(),
+            theme: ThemeChoice::System,
+            language: None,
+            groups: Vec::new(),
+            hook: None,
+        }
+    }
+}
+
+impl AppConfig {
+    pub fn monitor_interval(&self) -> Duration {
+        runtime().monitor_interval.unwrap_or_else(|| Duration::from_secs(self.interval_secs.max(1)))
+    }
+
+    pub fn http_timeout(&self) -> Duration {
+        runtime().http_timeout.unwrap_or_else(|| Duration::from_secs(self.http_timeout_secs.max(1)))
+    }
+
+    /// Capacidade dos buffers de amostras recentes: `recent_samples`, mas nunca
+    /// menos que o necessário para cobrir `FLAP_WINDOW_SECS` no ritmo mais rápido
+    /// de checagem (rechecagem de quedas ou ciclo completo). Menos que isso
+    /// desligaria a detecção de flapping sem aviso.
+    pub fn recent_sample_capacity(&self) -> usize {
+        let mut step = self.monitor_interval().as_secs().max(1);
+        if self.outage_check_interval > 0 {
+            step = step.min(self.outage_check_interval);
+        }
+        let flap_window = FLAP_WINDOW_SECS.div_ceil(step) as usize + 1;
+        self.recent_samples.max(flap_window)
+    }
+
+    /// Janela de escalonamento dos inícios, nunca maior que o intervalo do ciclo.
+    pub fn check_spread(&self) -> Duration {
+        let interval = self.monitor_interval();
+        let spread = match self.check_spread_secs {
+            Some(secs) => Duration::from_secs(secs),
+            None => (interval / DEFAULT_CHECK_SPREAD_DIVISOR).min(Duration::from_secs(DEFAULT_CHECK_SPREAD_MAX_SECS)),
+        };
+        spread.min(interval)
+    }
+}
+
+/// Um destino monitorado e suas opções individuais.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TargetConfig {
+    pub address: String,
+    /// Uptime mínimo esperado (%) em 30 dias; abaixo disso a entrada é destacada.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sla: Option<f64>,
+    /// URL de um monitor "Push" do Uptime Kuma que recebe o status a cada ciclo.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kuma_push_url: Option<String>,
+    /// Canais remotos que não devem receber alertas deste destino (ex.: `["pushover"]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub disabled_notifiers: Vec<String>,
+    /// Se preenchido, só estes canais recebem alertas deste destino.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifiers: Vec<String>,
+    /// `false` silencia todos os alertas do destino; o estado continua no menu.
+    #[serde(default = "default_true", skip_serializing_if = "is_true")]
+    pub notify: bool,
+    /// Substitui a gravidade dos alertas deste destino (ex.: `"low"` para VMs de teste).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub urgency: Option<Severity>,
+    /// Rótulos livres usados pelos filtros dos canais (ex.: `["prod"]`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Sobrescreve `max_notifications_per_hour` para este destino.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_notifications_per_hour: Option<u32>,
+    /// Tentativas de ping por checagem (padrão `PING_ATTEMPTS`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u8>,
+    /// Pings enviados em toda checagem para medir mín/méd/máx e perda; com
+    /// mais de um, substitui as tentativas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub probes: Option<u8>,
+    /// Força o tipo de checagem (`ping`, `http`, `https` ou `tcp`) em vez de
+    /// deduzi-lo do endereço; ex.: `"ping"` em `https://example.com`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check: Option<String>,
+    /// Checagens extras do mesmo serviço (ex.: `["https://host/"]` para um
+    /// destino de ping), combinadas com o endereço conforme `policy`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checks: Vec<String>,
+    #[serde(default, skip_serializing_if = "CheckPolicy::is_all")]
+    pub policy: CheckPolicy,
+    /// Latência (ms) acima da qual o destino, ainda online, fica degradado.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degraded_ms: Option<f64>,
+    /// Consulta a CRL do certificado de destinos `https://`; revogado ou
+    /// indeterminado deixa o destino degradado.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub revocation: bool,
+    /// Avisa quando o corpo da resposta muda (páginas de status, notas de versão).
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub content: bool,
+    /// Seletor CSS do trecho acompanhado em vez do corpo inteiro; implica `content`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_selector: Option<String>,
+    /// Destinos HTTP que servem um feed RSS, Atom ou JSON Feed ficam offline
+    /// quando o item mais recente passa desta idade, em horas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feed_max_age_hours: Option<f64>,
+    /// Avisa quando o registro do domínio do destino vence em até tantos dias
+    /// (consulta RDAP diária, fora do ciclo de checagens).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_expiry_days: Option<u32>,
+    /// Domínio registrado, quando o deduzido do endereço não serve.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    /// Cabeçalhos de resposta exigidos de destinos HTTP (ex.: `["hsts", "csp"]`);
+    /// a falta de algum deixa o destino degradado.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<String>,
+    /// Checagens seguidas com falha antes de considerar o destino offline
+    /// (padrão `fail_threshold` global).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fail_threshold: Option<u8>,
+    /// Expressão cron que define quando o destino é checado (ex.: `"* 8-18 * * mon-fri"`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Script Rhai que pós-processa o resultado da checagem (ver `run_script`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script: Option<String>,
+    /// Comando executado nas quedas e recuperações deste destino.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hook: Option<String>,
+    /// Timeout da checagem em ms: espera de cada ping, conexão TCP e requisição
+    /// HTTP. Substitui o `http_timeout_secs` global e a espera de 1 s do ping.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+impl TargetConfig {
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            sla: None,
+            kuma_push_url: None,
+            disabled_notifiers: Vec::new(),
+            notifiers: Vec::new(),
+            notify: true,
+            urgency: None,
+            tags: Vec::new(),
+            max_notifications_per_hour: None,
+            attempts: None,
+            probes: None,
+            check: None,
+            checks: Vec::new(),
+            policy: CheckPolicy::All,
+            degraded_ms: None,
+            revocation: false,
+            headers: Vec::new(),
+            content: false,
+            content_selector: None,
+            feed_max_age_hours: None,
+            domain_expiry_days: None,
+            domain: None,
+            fail_threshold: None,
+            schedule: None,
+            script: None,
+            hook: None,
+            timeout_ms: None,
+        }
+    }
+
+    pub fn attempts(&self) -> u8 {
+        self.attempts.unwrap_or(PING_ATTEMPTS).max(1)
+    }
+
+    pub fn probes(&self) -> u8 {
+        self.probes.unwrap_or(1).max(1)
+    }
+
+    /// `None` usa os timeouts globais.
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout_ms.filter(|ms| *ms > 0).map(Duration::from_millis)
+    }
+
+    /// Trecho acompanhado: `Some("")` para o corpo inteiro, `None` sem acompanhamento.
+    pub fn content_watch(&self) -> Option<String> {
+        match self.content_selector.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            Some(selector) => Some(selector.to_string()),
+            None => self.content.then(String::new),
+        }
+    }
+
+    /// Domínio cujo registro é acompanhado: `domain` ou o registrável do endereço.
+    pub fn registered_domain(&self) -> Option<String> {
+        match self.domain.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+            Some(domain) => Some(domain.trim_end_matches('.').to_ascii_lowercase()),
+            None => registrable_domain(&self.address),
+        }
+    }
+
+    pub fn fail_threshold(&self, default: u8) -> u8 {
+        self.fail_threshold.unwrap_or(default).max(1)
+    }
+
+    /// Endereço efetivamente checado: o próprio, ou reescrito para o tipo em `check`.
+    pub fn probe_address(&self) -> Result<String, String> {
+        let address = self.address.trim();
+        match self.check.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+            Some(kind) => forced_check_address(address, kind),
+            None => Ok(address.to_string()),
+        }
+    }
+}
+
+/// Separa host e porta de um endereço com ou sem esquema
+/// (`https://h:8443/x`, `h:22`, `[::1]:80`, `h`). A porta de URLs sem porta
+/// explícita é a padrão do esquema.
+pub(crate) fn split_host_port(address: &str) -> Option<(String, Option<u16>)> {
+    if address.contains("://") {
+        let url = reqwest::Url::parse(address).ok()?;
+        let host = url.host_str()?.to_string();
+        return Some((host, url.port_or_known_default()));
+    }
+    let address = address.trim_end_matches('/');
+    if let Some(rest) = address.strip_prefix('[') {
+        let (host, tail) = rest.split_once(']')?;
+        let port = tail.strip_prefix(':').and_then(|p| p.parse().ok());
+        return Some((format!("[{}]", host), port));
+    }
+    match address.rsplit_once(':') {
+        // Mais de um ':' sem colchetes é um IPv6 sem porta
+        Some((host, port)) if !host.contains(':') => Some((host.to_string(), Some(port.parse().ok()?))),
+        _ => Some((address.to_string(), None)),
+    }
+}
+
+/// Reescreve o endereço para o esquema do tipo de checagem forçado.
+pub(crate) fn forced_check_address(address: &str, kind: &str) -> Result<String, String> {
+    let (host, port) = split_host_port(address).ok_or_else(|| format!("endereço inválido: {}", address))?;
+    let has_scheme = |scheme: &str| address.to_ascii_lowercase().starts_with(&format!("{}://", scheme));
+    match kind.to_ascii_lowercase().as_str() {
+        "ping" => Ok(format!("ping://{}", host.trim_start_matches('[').trim_end_matches(']'))),
+        "http" | "https" if has_scheme("http") || has_scheme("https") => Ok(address.to_string()),
+        scheme @ ("http" | "https") => Ok(match port {
+            Some(port) => format!("{}://{}:{}/", scheme, host, port),
+            None => format!("{}://{}/", scheme, host),
+        }),
+        "tcp" => match port {
+            Some(port) => Ok(format!("tcp://{}:{}", host, port)),
+            None => Err(format!("checagem tcp sem porta em '{}' (use host:porta)", address)),
+        },
+        other => Err(format!("tipo de checagem desconhecido: '{}' (use ping, http, https ou tcp)", other)),
+    }
+}
+
+pub(crate) type ConfigMigration = fn(&mut serde_json::Value) -> Result<(), String>;
+
+/// Migrações em ordem: o item `n` converte um documento da versão `n` para `n + 1`.
+pub(crate) const CONFIG_MIGRATIONS: &[ConfigMigration] = &[migrate_v0_to_v1, migrate_v1_to_v2, migrate_v2_to_v3];
+
+/// v0: arquivos anteriores ao campo `version` (apenas `targets` como lista de strings).
+pub(crate) fn migrate_v0_to_v1(doc: &mut serde_json::Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("configuração não é um objeto JSON")?;
+    if !obj.get("targets").map(|t| t.is_array()).unwrap_or(false) {
+        return Err("campo 'targets' ausente ou inválido".to_string());
+    }
+    obj.insert("version".to_string(), serde_json::Value::from(1));
+    Ok(())
+}
+
+/// v2: cada destino passa de string para objeto `{ "address": ... }`.
+pub(crate) fn migrate_v1_to_v2(doc: &mut serde_json::Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("configuração não é um objeto JSON")?;
+    let targets = obj
+        .get_mut("targets")
+        .and_then(|t| t.as_array_mut())
+        .ok_or("campo 'targets' ausente ou inválido")?;
+    for target in targets.iter_mut() {
+        if let Some(address) = target.as_str() {
+            *target = serde_json::json!({ "address": address });
+        }
+    }
+    obj.insert("version".to_string(), serde_json::Value::from(2));
+    Ok(())
+}
+
+/// v3: `check_spread_secs: 0` era só o padrão gravado em todo arquivo; sem ele
+/// o ciclo passa a usar o escalonamento padrão (`AppConfig::check_spread`).
+pub(crate) fn migrate_v2_to_v3(doc: &mut serde_json::Value) -> Result<(), String> {
+    let obj = doc.as_object_mut().ok_or("configuração não é um objeto JSON")?;
+    if obj.get("check_spread_secs").and_then(|v| v.as_u64()) == Some(0) {
+        obj.remove("check_spread_secs");
+    }
+    obj.insert("version".to_string(), serde_json::Value::from(3));
+    Ok(())
+}
+
+/// Interpreta o conteúdo de `sites.json`, aplicando as migrações necessárias.
+/// Retorna a configuração e se ela foi migrada (e portanto deve ser regravada).
+pub(crate) fn parse_config(content: &str) -> Result<(AppConfig, bool), String> {
+    let mut doc: serde_json::Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+    let found = doc.get("version").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+
+    if found > CONFIG_VERSION {
+        warn!(
+            "Aviso: configuração na versão {} é mais nova que a suportada ({}); ela não será regravada por esta versão",
+            found, CONFIG_VERSION
+        );
+    }
+
+    for (version, migrate) in CONFIG_MIGRATIONS.iter().enumerate().skip(found as usize) {
+        migrate(&mut doc).map_err(|e| format!("migração v{} -> v{} falhou: {}", version, version + 1, e))?;
+    }
+
+    let cfg: AppConfig = serde_json::from_value(doc).map_err(|e| e.to_string())?;
+    Ok((cfg, found < CONFIG_VERSION))
+}
+
+/// Versão do esquema gravada no `sites.json` atual, quando ela é mais nova que
+/// `CONFIG_VERSION`. Regravar esse arquivo perderia os campos desconhecidos.
+pub fn config_newer_than_supported() -> Option<u32> {
+    let content = fs::read_to_string(get_config_path()).ok()?;
+    let doc: serde_json::Value = serde_json::from_str(&content).ok()?;
+    let found = doc.get("version").and_then(|v| v.as_u64())? as u32;
+    (found > CONFIG_VERSION).then_some(found)
+}
+
+pub(crate) fn get_config_path() -> PathBuf {
+    if let Some(path) = &runtime().config_path {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            if let Err(e) = fs::create_dir_all(parent) {
+                error!("Erro ao criar diretório de configuração: {}", e);
+            }
+        }
+        return path.clone();
+    }
+    let dirs = directories::ProjectDirs::from("com", "cosmicpinger", "cosmic_pinger")
+        .expect("Não foi possível determinar o diretório de configuração");
+    let path = dirs.config_dir();
+    if let Err(e) = fs::create_dir_all(path) {
+        error!("Erro ao criar diretório de configuração: {}", e);
+    }
+    path.join("sites.json")
+}
+
+pub(crate) fn backup_path(path: &std::path::Path) -> PathBuf {
+    path.with_extension("json.bak")
+}
+
+/// Lê `sites.json`. Um arquivo ausente resulta na configuração padrão; um arquivo
+/// corrompido é reportado como erro para que o chamador possa oferecer o backup.
+pub fn load_config() -> Result<AppConfig, String> {
+    let path = get_config_path();
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(AppConfig::default()),
+        Err(e) => return Err(format!("não foi possível ler {:?}: {}", path, e)),
+    };
+    let (cfg, migrated) = parse_config(&content).map_err(|e| format!("{:?} inválido: {}", path, e))?;
+    if migrated {
+        info!("Configuração migrada para o esquema v{}", CONFIG_VERSION);
+        log_event(&format!("Configuração migrada para o esquema v{}", CONFIG_VERSION));
+        save_config(&cfg);
+    }
+    Ok(cfg)
+}
+
+/// Lê o `.bak` com a última configuração válida, se existir.
+pub fn load_config_backup() -> Option<AppConfig> {
+    let content = fs::read_to_string(backup_path(&get_config_path())).ok()?;
+    parse_config(&content).ok().map(|(cfg, _)| cfg)
+}
+
+/// Substitui o arquivo corrompido pelo backup. O arquivo inválido é preservado
+/// como `sites.json.corrupt` para inspeção manual.
+pub fn restore_config_backup() -> Result<AppConfig, String> {
+    let path = get_config_path();
+    let cfg = load_config_backup().ok_or("nenhum backup válido encontrado")?;
+    if path.exists() {
+        let corrupt = path.with_extension("json.corrupt");
+        if let Err(e) = fs::rename(&path, &corrupt) {
+            warn!("Não foi possível preservar o arquivo corrompido: {}", e);
+        }
+    }
+    write_config_atomic(&path, &cfg)?;
+    info!("Configuração restaurada a partir de {:?}", backup_path(&path));
+    log_event("Configuração restaurada a partir do backup");
+    Ok(cfg)
+}
+
+/// Grava via arquivo temporário + rename para nunca deixar um JSON pela metade.
+pub(crate) fn write_config_atomic(path: &std::path::Path, cfg: &AppConfig) -> Result<(), String> {
+    use std::io::Write;
+
+    let json = serde_json::to_string_pretty(cfg).map_err(|e| format!("erro ao serializar: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    let mut file = fs::File::create(&tmp).map_err(|e| format!("erro ao criar {:?}: {}", tmp, e))?;
+    file.write_all(json.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("erro ao gravar {:?}: {}", tmp, e))?;
+    fs::rename(&tmp, path).map_err(|e| format!("erro ao substituir {:?}: {}", path, e))
+}
+
+pub fn save_config(cfg: &AppConfig) {
+    let path = get_config_path();
+    if let Some(found) = config_newer_than_supported() {
+        error!(
+            "Configuração na versão {} é mais nova que a suportada ({}); não será sobrescrita",
+            found, CONFIG_VERSION
+        );
+        log_event(&format!("Configuração v{} não salva: esquema mais novo que o suportado", found));
+        return;
+    }
+    let mut cfg = cfg.clone();
+    cfg.version = CONFIG_VERSION;
+
+    // Só guarda como backup um arquivo que ainda é válido
+    if let Ok(current) = fs::read_to_string(&path) {
+        if parse_config(&current).is_ok() {
+            if let Err(e) = fs::write(backup_path(&path), current) {
+                error!("Erro ao atualizar backup da configuração: {}", e);
+            }
+        }
+    }
+
+    match write_config_atomic(&path, &cfg) {
+        Ok(()) => info!("Configuração salva em: {:?}", path),
+        Err(e) => error!("Erro ao salvar configuração: {}", e),
+    }
+}
+
+pub fn normalize_target(raw: &str) -> Option<String> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+/// Resultado da importação de uma lista colada na janela de configuração.
+pub struct BulkImport {
+    pub added: Vec<String>,
+    pub duplicates: usize,
+    pub invalid: Vec<String>,
+}
+
+/// Lê uma lista colada: um destino por linha, `#` no início da linha (ou
+/// depois de um espaço) inicia um comentário e linhas vazias são ignoradas.
+/// Endereços já presentes em `existing` ou repetidos na lista contam como duplicados.
+pub fn parse_target_list(raw: &str, existing: &[TargetConfig]) -> BulkImport {
+    let mut seen: HashSet<String> = existing.iter().map(|t| t.address.clone()).collect();
+    let mut import = BulkImport { added: Vec::new(), duplicates: 0, invalid: Vec::new() };
+    for line in raw.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        // Um '#' colado ao endereço é fragmento de URL, não comentário
+        let entry = line.find(" #").or_else(|| line.find("\t#")).map_or(line, |pos| &line[..pos]);
+        let Some(cleaned) = normalize_target(entry) else {
+            continue;
+        };
+        let is_url = cleaned.starts_with("http://") || cleaned.starts_with("https://");
+        if cleaned.chars().any(char::is_whitespace) || (is_url && reqwest::Url::parse(&cleaned).is_err()) {
+            import.invalid.push(cleaned);
+        } else if seen.insert(cleaned.clone()) {
+            import.added.push(cleaned);
+        } else {
+            import.duplicates += 1;
+        }
+    }
+    import
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_host_port_forms() {
+        assert_eq!(split_host_port("https://h.com/x"), Some(("h.com".into(), Some(443))));
+        assert_eq!(split_host_port("http://h.com:8080"), Some(("h.com".into(), Some(8080))));
+        assert_eq!(split_host_port("h:22"), Some(("h".into(), Some(22))));
+        assert_eq!(split_host_port("[::1]:80"), Some(("[::1]".into(), Some(80))));
+        assert_eq!(split_host_port("::1"), Some(("::1".into(), None)));
+        assert_eq!(split_host_port("h"), Some(("h".into(), None)));
+        assert_eq!(split_host_port("h:porta"), None);
+    }
+
+    #[test]
+    fn forced_check_rewrites_the_address() {
+        assert_eq!(forced_check_address("https://h.com/x", "ping").as_deref(), Ok("ping://h.com"));
+        assert_eq!(forced_check_address("h:8443", "https").as_deref(), Ok("https://h:8443/"));
+        assert_eq!(forced_check_address("http://h/x", "https").as_deref(), Ok("http://h/x"));
+        assert_eq!(forced_check_address("h:22", "tcp").as_deref(), Ok("tcp://h:22"));
+        assert!(forced_check_address("h", "tcp").is_err());
+        assert!(forced_check_address("h", "smtp").is_err());
+    }
+
+    #[test]
+    fn parse_config_migrates_old_files() {
+        let (cfg, migrated) = parse_config(r#"{ "targets": ["1.1.1.1"], "check_spread_secs": 0 }"#).unwrap();
+        assert!(migrated);
+        assert_eq!(cfg.version, CONFIG_VERSION);
+        assert_eq!(cfg.targets[0].address, "1.1.1.1");
+        // O 0 gravado como padrão vira o escalonamento padrão
+        assert_eq!(cfg.check_spread_secs, None);
+
+        let current = format!(r#"{{ "version": {}, "targets": [], "check_spread_secs": 0 }}"#, CONFIG_VERSION);
+        let (cfg, migrated) = parse_config(&current).unwrap();
+        assert!(!migrated);
+        assert_eq!(cfg.check_spread_secs, Some(0));
+
+        assert!(parse_config(r#"{ "targets": 1 }"#).is_err());
+    }
+
+    #[test]
+    fn default_spread_is_a_fraction_of_the_interval() {
+        let mut cfg = AppConfig { interval_secs: 60, ..AppConfig::default() };
+        assert_eq!(cfg.check_spread(), Duration::from_secs(6));
+        cfg.interval_secs = 3600;
+        assert_eq!(cfg.check_spread(), Duration::from_secs(DEFAULT_CHECK_SPREAD_MAX_SECS));
+        cfg.check_spread_secs = Some(7200);
+        assert_eq!(cfg.check_spread(), Duration::from_secs(3600));
+    }
+
+    #[test]
+    fn sample_capacity_covers_the_flap_window() {
+        let cfg = AppConfig { interval_secs: 60, outage_check_interval: 0, recent_samples: 10, ..AppConfig::default() };
+        assert_eq!(cfg.recent_sample_capacity(), 31);
+        let cfg = AppConfig { interval_secs: 60, outage_check_interval: 20, recent_samples: 10, ..AppConfig::default() };
+        assert_eq!(cfg.recent_sample_capacity(), 91);
+        let cfg = AppConfig { recent_samples: 1000, ..AppConfig::default() };
+        assert_eq!(cfg.recent_sample_capacity(), 1000);
+    }
+
+    #[test]
+    fn target_list_skips_comments_and_duplicates() {
+        let existing = vec![TargetConfig::new("1.1.1.1")];
+        let import = parse_target_list("# lista\n1.1.1.1\n8.8.8.8 # google\n\n8.8.8.8\nhttps://h/#frag\nnão vale\n", &existing);
+        assert_eq!(import.added, ["8.8.8.8", "https://h/#frag"]);
+        assert_eq!(import.duplicates, 2);
+        assert_eq!(import.invalid, ["não vale"]);
+    }
+}
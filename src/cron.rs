@@ -0,0 +1,225 @@
+//! Expressões cron dos destinos com `schedule`.
+
+use chrono::Local;
+
+/// Expressão cron de 5 campos (`minuto hora dia mês dia-da-semana`), com `*`,
+/// listas, intervalos, passos (`*/15`, `8-18/2`), nomes (`mon`, `jan`) e os
+/// atalhos `@hourly`, `@daily`, `@weekly` e `@monthly`.
+#[derive(Clone)]
+pub struct CronSchedule {
+    pub minutes: u64,
+    pub hours: u64,
+    pub days: u64,
+    pub months: u64,
+    pub weekdays: u64,
+    /// Dia do mês e da semana restritos ao mesmo tempo combinam com OU, como no cron.
+    pub days_restricted: bool,
+    pub weekdays_restricted: bool,
+}
+
+pub(crate) const CRON_MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+pub(crate) const CRON_WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+pub(crate) fn parse_cron_value(raw: &str, min: u32, names: &[&str]) -> Result<u32, String> {
+    let lower = raw.to_ascii_lowercase();
+    if let Some(pos) = names.iter().position(|name| *name == lower) {
+        return Ok(pos as u32 + min);
+    }
+    raw.parse().map_err(|_| format!("valor inválido '{}'", raw))
+}
+
+/// Converte um campo em máscara de bits; retorna também se ele restringe algo.
+pub(crate) fn parse_cron_field(spec: &str, min: u32, max: u32, names: &[&str]) -> Result<(u64, bool), String> {
+    let mut mask = 0u64;
+    for part in spec.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| format!("passo inválido '{}'", step))?;
+                if step == 0 {
+                    return Err("passo deve ser maior que zero".to_string());
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let start = parse_cron_value(a, min, names)?;
+            let end = parse_cron_value(b, min, names)?;
+            // "mon-sun": domingo como fim de intervalo é o 7
+            (start, if max == 7 && end == 0 { 7 } else { end })
+        } else {
+            let start = parse_cron_value(range, min, names)?;
+            // "5/10" vai de 5 até o fim do intervalo
+            (start, if part.contains('/') { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return Err(format!("'{}' fora de {}-{}", part, min, max));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok((mask, spec != "*"))
+}
+
+impl CronSchedule {
+    pub fn parse(expr: &str) -> Result<Self, String> {
+        let expr = match expr.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(format!("'{}' deve ter 5 campos", expr));
+        };
+        let (minutes, _) = parse_cron_field(minute, 0, 59, &[])?;
+        let (hours, _) = parse_cron_field(hour, 0, 23, &[])?;
+        let (days, days_restricted) = parse_cron_field(day, 1, 31, &[])?;
+        let (months, _) = parse_cron_field(month, 1, 12, &CRON_MONTHS)?;
+        let (mut weekdays, weekdays_restricted) = parse_cron_field(weekday, 0, 7, &CRON_WEEKDAYS)?;
+        // 7 também é domingo
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+        Ok(Self { minutes, hours, days, months, weekdays, days_restricted, weekdays_restricted })
+    }
+
+    pub fn day_matches(&self, t: &chrono::NaiveDateTime) -> bool {
+        use chrono::Datelike;
+        let day = self.days & (1 << t.day()) != 0;
+        let weekday = self.weekdays & (1 << t.weekday().num_days_from_sunday()) != 0;
+        if self.days_restricted && self.weekdays_restricted {
+            day || weekday
+        } else {
+            day && weekday
+        }
+    }
+
+    pub fn matches(&self, t: &chrono::NaiveDateTime) -> bool {
+        use chrono::{Datelike, Timelike};
+        self.months & (1 << t.month()) != 0
+            && self.day_matches(t)
+            && self.hours & (1 << t.hour()) != 0
+            && self.minutes & (1 << t.minute()) != 0
+    }
+
+    /// Próximo minuto (estritamente depois de `after`) em que a expressão casa,
+    /// procurando até um ano à frente.
+    pub fn next_after(&self, after: chrono::DateTime<Local>) -> Option<chrono::DateTime<Local>> {
+        use chrono::{Datelike, TimeZone, Timelike};
+        let naive = after.naive_local();
+        let mut t = naive.with_second(0)?.with_nanosecond(0)? + chrono::Duration::minutes(1);
+        let limit = naive + chrono::Duration::days(366);
+        while t <= limit {
+            if self.months & (1 << t.month()) == 0 || !self.day_matches(&t) {
+                t = t.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if self.hours & (1 << t.hour()) == 0 {
+                t = t.with_minute(0)? + chrono::Duration::hours(1);
+            } else if self.minutes & (1 << t.minute()) == 0 {
+                t += chrono::Duration::minutes(1);
+            } else if let Some(local) = Local.from_local_datetime(&t).earliest() {
+                return Some(local);
+            } else {
+                // Minuto inexistente na troca de horário de verão
+                t += chrono::Duration::minutes(1);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, NaiveDateTime, TimeZone};
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).and_then(|date| date.and_hms_opt(h, min, 0)).unwrap()
+    }
+
+    fn bits(mask: u64) -> Vec<u32> {
+        (0..64).filter(|b| mask & (1 << b) != 0).collect()
+    }
+
+    fn next(expr: &str, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let after = Local.from_local_datetime(&after).earliest().unwrap();
+        CronSchedule::parse(expr).unwrap().next_after(after).map(|t| t.naive_local())
+    }
+
+    #[test]
+    fn field_ranges_steps_and_lists() {
+        assert_eq!(bits(parse_cron_field("*/15", 0, 59, &[]).unwrap().0), [0, 15, 30, 45]);
+        assert_eq!(bits(parse_cron_field("8-18/4", 0, 23, &[]).unwrap().0), [8, 12, 16]);
+        assert_eq!(bits(parse_cron_field("1,15,30", 1, 31, &[]).unwrap().0), [1, 15, 30]);
+        assert_eq!(bits(parse_cron_field("50/5", 0, 59, &[]).unwrap().0), [50, 55]);
+        assert_eq!(bits(parse_cron_field("1-3,10", 1, 31, &[]).unwrap().0), [1, 2, 3, 10]);
+    }
+
+    #[test]
+    fn field_names_and_sunday() {
+        assert_eq!(bits(parse_cron_field("jan,DEC", 1, 12, &CRON_MONTHS).unwrap().0), [1, 12]);
+        assert_eq!(bits(parse_cron_field("mon-fri", 0, 7, &CRON_WEEKDAYS).unwrap().0), [1, 2, 3, 4, 5]);
+        // "mon-sun" termina no 7, que `parse` também trata como domingo
+        assert_eq!(CronSchedule::parse("0 0 * * mon-sun").unwrap().weekdays & 0xff, 0xff);
+    }
+
+    #[test]
+    fn only_star_leaves_a_field_unrestricted() {
+        assert!(!parse_cron_field("*", 1, 31, &[]).unwrap().1);
+        assert!(parse_cron_field("*/2", 1, 31, &[]).unwrap().1);
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        for expr in ["0 * * *", "0 * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "*/0 * * * *", "5-1 * * * *", "* * * foo *"] {
+            assert!(CronSchedule::parse(expr).is_err(), "{}", expr);
+        }
+    }
+
+    #[test]
+    fn shortcuts_match_their_expansion() {
+        let t = at(2026, 11, 1, 0, 0);
+        assert!(CronSchedule::parse("@daily").unwrap().matches(&t));
+        assert!(CronSchedule::parse("@monthly").unwrap().matches(&t));
+        assert!(!CronSchedule::parse("@daily").unwrap().matches(&at(2026, 11, 1, 0, 1)));
+        assert_eq!(next("@hourly", at(2026, 11, 1, 10, 0)), Some(at(2026, 11, 1, 11, 0)));
+    }
+
+    #[test]
+    fn day_of_month_or_day_of_week_when_both_restricted() {
+        let both = CronSchedule::parse("0 12 13 * fri").unwrap();
+        assert!(both.matches(&at(2026, 11, 13, 12, 0)));
+        assert!(both.matches(&at(2026, 11, 6, 12, 0)), "sexta que não é dia 13");
+        assert!(both.matches(&at(2026, 10, 13, 12, 0)), "dia 13 que não é sexta");
+        assert!(!both.matches(&at(2026, 11, 12, 12, 0)));
+
+        let day_only = CronSchedule::parse("0 12 13 * *").unwrap();
+        assert!(!day_only.matches(&at(2026, 11, 6, 12, 0)));
+        let weekday_only = CronSchedule::parse("0 12 * * fri").unwrap();
+        assert!(!weekday_only.matches(&at(2026, 10, 13, 12, 0)));
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        assert_eq!(next("*/15 * * * *", at(2026, 11, 1, 10, 15)), Some(at(2026, 11, 1, 10, 30)));
+        assert_eq!(next("30 9 * * *", at(2026, 11, 1, 9, 30)), Some(at(2026, 11, 2, 9, 30)));
+    }
+
+    #[test]
+    fn next_after_rolls_over_month_and_year() {
+        assert_eq!(next("0 12 1 * *", at(2026, 1, 31, 12, 0)), Some(at(2026, 2, 1, 12, 0)));
+        assert_eq!(next("0 12 31 * *", at(2026, 1, 31, 13, 0)), Some(at(2026, 3, 31, 12, 0)));
+        assert_eq!(next("0 12 1 jan *", at(2026, 12, 31, 23, 59)), Some(at(2027, 1, 1, 12, 0)));
+    }
+
+    #[test]
+    fn next_after_searches_one_year_ahead() {
+        assert_eq!(next("30 9 29 feb *", at(2027, 3, 1, 12, 0)), Some(at(2028, 2, 29, 9, 30)));
+        assert_eq!(next("30 9 29 feb *", at(2026, 3, 1, 12, 0)), None);
+    }
+}
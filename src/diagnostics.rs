@@ -0,0 +1,433 @@
+//! Relatório de diagnóstico e pacote de suporte.
+
+use super::*;
+
+pub(crate) const DIAG_PING_COUNT: usize = 5;
+pub(crate) const TRACEROUTE_MAX_HOPS: u32 = 20;
+
+/// Bloco do relatório de diagnóstico ("Ping", "DNS", ...).
+#[derive(Debug, Clone)]
+pub struct DiagnosticSection {
+    pub title: String,
+    pub lines: Vec<String>,
+}
+
+impl DiagnosticSection {
+    fn new(title_key: &'static str) -> Self {
+        Self { title: tr(title_key).to_string(), lines: Vec::new() }
+    }
+}
+
+/// Rota até o host pelo `traceroute` do sistema, ou `tracepath`/`tracert`
+/// quando ele não existe.
+pub(crate) fn traceroute_lines(host: &str) -> Vec<String> {
+    let hops = TRACEROUTE_MAX_HOPS.to_string();
+    #[cfg(windows)]
+    let candidates: Vec<(&str, Vec<&str>)> = vec![("tracert", vec!["-d", "-h", &hops, "-w", "1000"])];
+    #[cfg(not(windows))]
+    let candidates: Vec<(&str, Vec<&str>)> =
+        vec![("traceroute", vec!["-n", "-q", "1", "-w", "1", "-m", &hops]), ("tracepath", vec!["-n", "-m", &hops])];
+    for (program, args) in candidates {
+        let Ok(out) = SysCommand::new(program).args(&args).arg(host).output() else {
+            continue;
+        };
+        let mut lines: Vec<String> = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .chain(String::from_utf8_lossy(&out.stderr).lines())
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        lines.insert(0, format!("$ {} {} {}", program, args.join(" "), host));
+        return lines;
+    }
+    vec![tr("diag.no_traceroute").to_string()]
+}
+
+/// Roda o pacote de diagnóstico de um destino: a checagem configurada, ping,
+/// DNS, rota e, para URLs, os cabeçalhos HTTP. Bloqueia por alguns segundos.
+pub fn diagnose_target(address: &str, config: &AppConfig) -> Vec<DiagnosticSection> {
+    use std::net::ToSocketAddrs;
+
+    let address = address.trim();
+    let mut sections = Vec::new();
+
+    let mut check = DiagnosticSection::new("diag.check");
+    match run_single_check(address, config) {
+        Ok((_, outcome)) => check.lines.push(format!("{} {}", if outcome.success { "🟢" } else { "🔴" }, outcome.message)),
+        Err(e) => check.lines.push(format!("🔴 {}", e)),
+    }
+    sections.push(check);
+
+    let Some(host) = split_host_port(address).map(|(host, _)| host.trim_start_matches('[').trim_end_matches(']').to_string())
+    else {
+        return sections;
+    };
+
+    let mut ping = DiagnosticSection::new("diag.ping");
+    let mut times = Vec::new();
+    let mut received = 0;
+    for seq in 1..=DIAG_PING_COUNT {
+        match pinger().echo(&host, Duration::from_millis(PING_WAIT_MS)) {
+            Ok(latency) => {
+                received += 1;
+                times.extend(latency);
+                ping.lines.push(format!("#{} {}", seq, latency.map_or_else(|| "OK".to_string(), |ms| format!("{:.1} ms", ms))));
+            }
+            Err(PingFailure::Offline) => ping.lines.push(format!("#{} OFFLINE", seq)),
+            Err(PingFailure::Error) => ping.lines.push(format!("#{} {}", seq, tr("check.error"))),
+        }
+    }
+    ping.lines.push(trf(
+        "diag.ping_summary",
+        &[
+            ("backend", &pinger().name()),
+            ("sent", &DIAG_PING_COUNT),
+            ("received", &received),
+            ("loss", &format!("{:.0}", (DIAG_PING_COUNT - received) as f64 * 100.0 / DIAG_PING_COUNT as f64)),
+        ],
+    ));
+    if let (Some(min), Some(max)) = (times.iter().copied().reduce(f64::min), times.iter().copied().reduce(f64::max)) {
+        let avg = times.iter().sum::<f64>() / times.len() as f64;
+        ping.lines.push(format!("min/avg/max = {:.1}/{:.1}/{:.1} ms", min, avg, max));
+    }
+    sections.push(ping);
+
+    let mut dns = DiagnosticSection::new("diag.dns");
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => dns.lines.push(match reverse_lookup(ip) {
+            Ok(Some(name)) => format!("PTR {} → {}", ip, name),
+            Ok(None) => format!("PTR {} → -", ip),
+            Err(e) => format!("PTR {}: {}", ip, e),
+        }),
+        Err(_) => {
+            let started = Instant::now();
+            match (host.as_str(), 0).to_socket_addrs() {
+                Ok(addrs) => {
+                    let mut ips: Vec<String> = addrs.map(|a| a.ip().to_string()).collect();
+                    ips.dedup();
+                    dns.lines.push(trf(
+                        "diag.dns_system",
+                        &[("ms", &format!("{:.1}", started.elapsed().as_secs_f64() * 1000.0)), ("ips", &ips.join(", "))],
+                    ));
+                }
+                Err(e) => dns.lines.push(format!("{}: {}", tr("diag.dns_system_failed"), e)),
+            }
+            for (kind, qtype) in [("A", 1), ("AAAA", 28)] {
+                let query = DnsQuery { name: host.clone(), kind, qtype, server: None, expect: Vec::new() };
+                dns.lines.push(match dns_lookup(&query, 1, Duration::from_millis(DNS_TIMEOUT_MS)) {
+                    Ok((Ok(values), ms)) if values.is_empty() => format!("{} ({:.1} ms): -", kind, ms),
+                    Ok((Ok(values), ms)) => format!("{} ({:.1} ms): {}", kind, ms, values.join(", ")),
+                    Ok((Err(rcode), ms)) => format!("{} ({:.1} ms): rcode {}", kind, ms, rcode),
+                    Err(e) => format!("{}: {}", kind, e),
+                });
+            }
+        }
+    }
+    sections.push(dns);
+
+    let mut route = DiagnosticSection::new("diag.traceroute");
+    route.lines = traceroute_lines(&host);
+    sections.push(route);
+
+    if address.starts_with("http://") || address.starts_with("https://") {
+        let mut http = DiagnosticSection::new("diag.http");
+        match build_http_client(config.http_timeout()) {
+            Some(client) => {
+                let started = Instant::now();
+                match client.get(address).send() {
+                    Ok(resp) => {
+                        http.lines.push(format!(
+                            "{:?} {} — {:.0} ms — {}",
+                            resp.version(),
+                            resp.status(),
+                            started.elapsed().as_secs_f64() * 1000.0,
+                            resp.url()
+                        ));
+                        for (name, value) in resp.headers() {
+                            http.lines.push(format!("{}: {}", name, String::from_utf8_lossy(value.as_bytes())));
+                        }
+                    }
+                    Err(e) => http.lines.push(format!("🔴 {}", e)),
+                }
+            }
+            None => http.lines.push(tr("check.error").to_string()),
+        }
+        sections.push(http);
+    }
+    sections
+}
+
+/// Texto do relatório para a janela e a área de transferência.
+pub fn render_diagnostics(address: &str, sections: &[DiagnosticSection]) -> String {
+    let mut out = trf(
+        "diag.report_heading",
+        &[("address", &address), ("time", &Local::now().format("%d/%m/%Y %H:%M:%S")), ("version", &APP_VERSION)],
+    );
+    out.push('\n');
+    for section in sections {
+        out.push_str(&format!("\n== {} ==\n", section.title));
+        for line in &section.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Campos de `sites.json` trocados por `"***"` no pacote de suporte.
+pub(crate) const SECRET_CONFIG_KEYS: [&str; 10] = [
+    "token",
+    "password",
+    "access_token",
+    "app_token",
+    "user_key",
+    "routing_key",
+    "api_key",
+    "kuma_push_url",
+    "headers",
+    "push_url",
+];
+/// Seções de notificadores, onde também `url` e `topic` são segredos: URLs de
+/// webhooks (Slack, Discord) e tópicos do ntfy dão acesso a quem os conhece.
+pub(crate) const NOTIFIER_CONFIG_SECTIONS: [&str; 8] =
+    ["webhooks", "matrix", "ntfy", "gotify", "pushover", "pagerduty", "opsgenie", "email"];
+pub(crate) const SECRET_NOTIFIER_KEYS: [&str; 2] = ["url", "topic"];
+/// Quanto do fim de cada log entra no pacote de suporte.
+pub(crate) const SUPPORT_LOG_TAIL_BYTES: usize = 256 * 1024;
+pub(crate) const SUPPORT_HISTORY_LINES: usize = 2000;
+
+/// Apaga credenciais de um JSON de configuração, em qualquer nível.
+pub(crate) fn redact_secrets(value: &mut serde_json::Value) {
+    redact_secrets_in(value, false);
+}
+
+fn redact_secrets_in(value: &mut serde_json::Value, notifier: bool) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                let secret = SECRET_CONFIG_KEYS.contains(&key.as_str())
+                    || (notifier && SECRET_NOTIFIER_KEYS.contains(&key.as_str()));
+                if secret && !field.is_null() {
+                    *field = serde_json::Value::String("***".into());
+                } else {
+                    redact_secrets_in(field, notifier || NOTIFIER_CONFIG_SECTIONS.contains(&key.as_str()));
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|item| redact_secrets_in(item, notifier)),
+        _ => {}
+    }
+}
+
+/// Tira de cada URL do texto o usuário e a senha (`user:pass@`) e a query
+/// string, que costumam levar credenciais: `https://a:b@host/x?k=1` vira
+/// `https://***@host/x?***`.
+pub(crate) fn redact_urls(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("://") {
+        let (before, after) = rest.split_at(pos + 3);
+        out.push_str(before);
+        let end = after
+            .find(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`' | '\\'))
+            .unwrap_or(after.len());
+        let (url, tail) = after.split_at(end);
+        let authority_end = url.find(['/', '?', '#']).unwrap_or(url.len());
+        let (authority, path) = url.split_at(authority_end);
+        match authority.rsplit_once('@') {
+            Some((_, host)) => {
+                out.push_str("***@");
+                out.push_str(host);
+            }
+            None => out.push_str(authority),
+        }
+        match path.split_once('?') {
+            Some((path, _)) => {
+                out.push_str(path);
+                out.push_str("?***");
+            }
+            None => out.push_str(path),
+        }
+        rest = tail;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `sites.json` sem segredos. Um arquivo inválido não é copiado (pode ter
+/// segredos soltos), só o erro de leitura.
+pub(crate) fn redacted_config() -> String {
+    let path = get_config_path();
+    let raw = match fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) => return format!("{:?}: {}\n", path, e),
+    };
+    match serde_json::from_str::<serde_json::Value>(&raw) {
+        Ok(mut value) => {
+            redact_secrets(&mut value);
+            serde_json::to_string_pretty(&value).unwrap_or_default()
+        }
+        Err(e) => format!("{:?} inválido: {}\n", path, e),
+    }
+}
+
+/// Versão, sistema, sessão gráfica, backend de ping e caminhos em uso.
+pub(crate) fn environment_report() -> String {
+    let mut lines = vec![
+        format!("{} {}", APP_NAME, APP_VERSION),
+        format!("Gerado em: {}", Local::now().to_rfc3339()),
+        format!("Plataforma: {} {}", std::env::consts::OS, std::env::consts::ARCH),
+    ];
+    if let Some(name) = fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|c| c.lines().find_map(|l| l.strip_prefix("PRETTY_NAME=").map(|v| v.trim_matches('"').to_string())))
+    {
+        lines.push(format!("Distribuição: {}", name));
+    }
+    if let Ok(kernel) = fs::read_to_string("/proc/sys/kernel/osrelease") {
+        lines.push(format!("Kernel: {}", kernel.trim()));
+    }
+    for var in ["XDG_CURRENT_DESKTOP", "XDG_SESSION_TYPE", "LANG", "LANGUAGE", "LC_ALL"] {
+        if let Ok(value) = std::env::var(var) {
+            lines.push(format!("{}={}", var, value));
+        }
+    }
+    lines.push(format!("Idioma: {:?}", lang()));
+    lines.push(format!("Scripts (rhai): {}", cfg!(feature = "scripting")));
+    lines.push(format!("Ping: {}", pinger().name()));
+    #[cfg(target_os = "linux")]
+    {
+        let caps = IcmpCapabilities::detect();
+        lines.push(format!(
+            "ICMP: ping_group_range={:?} grupos={:?} CAP_NET_RAW={}",
+            caps.group_range, caps.groups, caps.cap_net_raw
+        ));
+    }
+    lines.push(format!("Configuração: {:?}", get_config_path()));
+    lines.push(format!("Dados: {:?}", get_data_dir()));
+    lines.push(match system_nameserver() {
+        Some(server) => format!("DNS: {}", server),
+        None => "DNS: sem nameserver em /etc/resolv.conf".to_string(),
+    });
+    lines.join("\n") + "\n"
+}
+
+/// Estado atual da instância em execução: o arquivo de status ou, sem ele,
+/// o `/status` da API local.
+pub(crate) fn running_status(config: &AppConfig) -> String {
+    if let Some(status) = status_file_path().and_then(|path| fs::read_to_string(path).ok()) {
+        return status;
+    }
+    let Some(api) = &config.api else {
+        return "Sem arquivo de status nem API local; estado ao vivo indisponível\n".to_string();
+    };
+    let result = build_http_client(Duration::from_secs(LIVE_STATUS_TIMEOUT_SECS))
+        .ok_or_else(|| "cliente HTTP indisponível".to_string())
+        .and_then(|client| {
+            client
+                .get(format!("{}/status", api_base_url(api)))
+                .bearer_auth(&api.token)
+                .send()
+                .and_then(|resp| resp.error_for_status())
+                .and_then(|resp| resp.text())
+                .map_err(|e| e.to_string())
+        });
+    result.unwrap_or_else(|e| format!("Instância em execução não respondeu: {}\n", e))
+}
+
+/// Fim de um arquivo, começando numa linha inteira.
+pub(crate) fn file_tail(path: &Path, max_bytes: usize) -> Option<Vec<u8>> {
+    let data = fs::read(path).ok()?;
+    if data.len() <= max_bytes {
+        return Some(data);
+    }
+    let tail = &data[data.len() - max_bytes..];
+    let start = tail.iter().position(|&b| b == b'\n').map_or(0, |i| i + 1);
+    Some(tail[start..].to_vec())
+}
+
+/// Nome padrão do pacote: na pasta de downloads, com data e hora.
+pub fn default_support_bundle_path() -> PathBuf {
+    let dir = directories::UserDirs::new()
+        .and_then(|dirs| dirs.download_dir().map(Path::to_path_buf))
+        .unwrap_or_else(get_data_dir);
+    dir.join(format!("cosmic_pinger-suporte-{}.tar.gz", Local::now().format("%Y%m%d-%H%M%S")))
+}
+
+/// Junta configuração sem segredos, logs recentes, ambiente e estado atual
+/// num `.tar.gz` para anexar a relatos de bug.
+pub fn write_support_bundle(path: &Path) -> Result<(), String> {
+    let config = load_config().unwrap_or_default();
+    let data_dir = get_data_dir();
+    let mut files: Vec<(String, Vec<u8>)> = vec![
+        ("ambiente.txt".into(), environment_report().into_bytes()),
+        ("sites.json".into(), redacted_config().into_bytes()),
+        ("status.json".into(), running_status(&config).into_bytes()),
+    ];
+    for name in ["events.log", "events.log.1", "cosmic_pinger.log", "cosmic_pinger.log.1"] {
+        if let Some(data) = file_tail(&data_dir.join(name), SUPPORT_LOG_TAIL_BYTES) {
+            files.push((format!("logs/{}", name), data));
+        }
+    }
+    for name in ["report_state.json", "domain_state.json", "network_state.json", "reverse_dns.json", "content_state.json"] {
+        if let Ok(data) = fs::read(data_dir.join(name)) {
+            files.push((format!("estado/{}", name), data));
+        }
+    }
+    if let Ok(history) = fs::read_to_string(history_path()) {
+        let lines: Vec<&str> = history.lines().collect();
+        let recent = lines[lines.len().saturating_sub(SUPPORT_HISTORY_LINES)..].join("\n");
+        files.push(("estado/history.jsonl".into(), recent.into_bytes()));
+    }
+
+    let file = fs::File::create(path).map_err(|e| format!("{:?}: {}", path, e))?;
+    let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(file, flate2::Compression::default()));
+    let mtime = Local::now().timestamp().max(0) as u64;
+    for (name, data) in files {
+        // Endereços aparecem em todo lugar: status, eventos, histórico, logs
+        let data = redact_urls(&String::from_utf8_lossy(&data)).into_bytes();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        archive
+            .append_data(&mut header, format!("cosmic_pinger-suporte/{}", name), data.as_slice())
+            .map_err(|e| e.to_string())?;
+    }
+    archive.into_inner().and_then(|gz| gz.finish()).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_lose_credentials_and_queries() {
+        assert_eq!(
+            redact_urls(r#"{"host":"https://u:p@h.com/a?t=1","x":"tcp://h:22"}"#),
+            r#"{"host":"https://***@h.com/a?***","x":"tcp://h:22"}"#
+        );
+        assert_eq!(redact_urls("GET http://h?k=v ok"), "GET http://h?*** ok");
+        assert_eq!(redact_urls("sem url"), "sem url");
+    }
+
+    #[test]
+    fn secrets_redacted_at_any_level() {
+        let mut value = serde_json::json!({
+            "api": { "token": "t" },
+            "webhooks": [{ "url": "https://hooks/x", "format": "slack" }],
+            "ntfy": { "topic": "segredo", "server": "https://ntfy.sh" },
+            "targets": [{ "address": "https://h", "headers": ["hsts"] }],
+        });
+        redact_secrets(&mut value);
+        assert_eq!(value["api"]["token"], "***");
+        assert_eq!(value["webhooks"][0]["url"], "***");
+        assert_eq!(value["webhooks"][0]["format"], "slack");
+        assert_eq!(value["ntfy"]["topic"], "***");
+        assert_eq!(value["ntfy"]["server"], "https://ntfy.sh");
+        assert_eq!(value["targets"][0]["address"], "https://h");
+        assert_eq!(value["targets"][0]["headers"], "***");
+    }
+}
@@ -0,0 +1,195 @@
+//! Vencimento de domínios pelo RDAP.
+
+use super::*;
+
+/// Segundos níveis que, sob um ccTLD, fazem parte do sufixo (`example.com.br`).
+pub(crate) const SECOND_LEVEL_SUFFIXES: [&str; 8] = ["com", "net", "org", "gov", "edu", "co", "ac", "ind"];
+pub(crate) const RDAP_BOOTSTRAP_URL: &str = "https://data.iana.org/rdap/dns.json";
+/// Intervalo mínimo entre rodadas RDAP, mesmo quando a anterior falhou.
+pub(crate) const RDAP_RETRY_SECS: u64 = 60 * 60;
+
+/// Domínio registrável do endereço, por heurística (sem a Public Suffix
+/// List): os dois últimos rótulos, ou três sob `com.br`, `co.uk` e afins.
+pub(crate) fn registrable_domain(address: &str) -> Option<String> {
+    let (host, _) = split_host_port(address.split_once("://").map_or(address, |(_, rest)| rest).split('/').next()?)?;
+    if host.parse::<std::net::IpAddr>().is_ok() || host.starts_with('[') {
+        return None;
+    }
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    let labels: Vec<&str> = host.split('.').collect();
+    let keep = match labels.as_slice() {
+        [.., second, tld] if tld.len() == 2 && SECOND_LEVEL_SUFFIXES.contains(second) => 3,
+        _ => 2,
+    };
+    (labels.len() >= keep).then(|| labels[labels.len() - keep..].join("."))
+}
+
+/// Última consulta RDAP de um domínio, em `domain_state.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct DomainRecord {
+    pub checked: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub(crate) fn domain_state_path() -> PathBuf {
+    get_data_dir().join("domain_state.json")
+}
+
+pub(crate) fn load_domain_records() -> HashMap<String, DomainRecord> {
+    fs::read_to_string(domain_state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// URL base do serviço RDAP do TLD, pelo bootstrap da IANA.
+pub(crate) fn rdap_base(bootstrap: &serde_json::Value, domain: &str) -> Option<String> {
+    let tld = domain.rsplit('.').next()?;
+    bootstrap.get("services")?.as_array()?.iter().find_map(|service| {
+        let tlds = service.get(0)?.as_array()?;
+        if !tlds.iter().any(|t| t.as_str().is_some_and(|t| t.eq_ignore_ascii_case(tld))) {
+            return None;
+        }
+        let url = service.get(1)?.as_array()?.iter().filter_map(|u| u.as_str()).find(|u| u.starts_with("https://"))?;
+        Some(format!("{}/", url.trim_end_matches('/')))
+    })
+}
+
+/// Data de vencimento (evento `expiration`) do registro do domínio.
+pub(crate) fn rdap_expiration(client: &Client, bootstrap: &serde_json::Value, domain: &str) -> Result<i64, String> {
+    let base = rdap_base(bootstrap, domain).ok_or_else(|| format!("TLD de {} sem serviço RDAP", domain))?;
+    let body: serde_json::Value = client
+        .get(format!("{}domain/{}", base, domain))
+        .header("Accept", "application/rdap+json")
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.json())
+        .map_err(|e| e.to_string())?;
+    body.get("events")
+        .and_then(|events| events.as_array())
+        .and_then(|events| {
+            events.iter().find(|e| e.get("eventAction").and_then(|a| a.as_str()) == Some("expiration"))
+        })
+        .and_then(|event| event.get("eventDate")?.as_str())
+        .and_then(|date| chrono::DateTime::parse_from_rfc3339(date).ok())
+        .map(|date| date.timestamp())
+        .ok_or_else(|| format!("RDAP sem data de vencimento para {}", domain))
+}
+
+/// Consultas RDAP em segundo plano, uma vez por dia por domínio, para não
+/// atrasar o ciclo de checagens. O cache fica em memória; só a thread da
+/// consulta lê a rede e grava `domain_state.json`.
+pub(crate) struct DomainWatch {
+    pending: Option<mpsc::Receiver<HashMap<String, DomainRecord>>>,
+    last_run: Option<Instant>,
+    records: HashMap<String, DomainRecord>,
+}
+
+impl DomainWatch {
+    pub fn load() -> Self {
+        Self { pending: None, last_run: None, records: load_domain_records() }
+    }
+
+    /// Chamado a cada ciclo: recolhe a rodada anterior, se terminou, e inicia
+    /// outra quando algum domínio está há um dia sem consulta. Devolve
+    /// (destino, domínio, vencimento) dos registros que vencem dentro do prazo.
+    pub fn poll(&mut self, targets: &[TargetConfig], client: Option<&Client>) -> Vec<(String, String, i64)> {
+        let watched: Vec<(String, String, u32)> = targets
+            .iter()
+            .filter_map(|t| Some((t.address.trim().to_string(), t.registered_domain()?, t.domain_expiry_days?)))
+            .collect();
+        let mut expiring = Vec::new();
+        if let Some(rx) = &self.pending {
+            match rx.try_recv() {
+                Ok(updated) => {
+                    self.records.extend(updated.clone());
+                    let now = Local::now().timestamp();
+                    for (host, domain, days) in &watched {
+                        if let Some(expires) = updated.get(domain).and_then(|r| r.expires) {
+                            if expires - now <= *days as i64 * DAY_SECS {
+                                expiring.push((host.clone(), domain.clone(), expires));
+                            }
+                        }
+                    }
+                    self.pending = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => return expiring,
+                Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+
+        let retry = Duration::from_secs(RDAP_RETRY_SECS);
+        if watched.is_empty() || self.last_run.is_some_and(|at| at.elapsed() < retry) {
+            return expiring;
+        }
+        let Some(client) = client else {
+            return expiring;
+        };
+        let now = Local::now().timestamp();
+        let mut due: Vec<String> = watched
+            .iter()
+            .filter(|(_, domain, _)| self.records.get(domain).is_none_or(|r| now - r.checked >= DAY_SECS))
+            .map(|(_, domain, _)| domain.clone())
+            .collect();
+        due.sort();
+        due.dedup();
+        if due.is_empty() {
+            return expiring;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let client = client.clone();
+        let mut records = self.records.clone();
+        thread::spawn(move || {
+            let bootstrap: Option<serde_json::Value> = client
+                .get(RDAP_BOOTSTRAP_URL)
+                .send()
+                .and_then(|resp| resp.error_for_status())
+                .and_then(|resp| resp.json())
+                .map_err(|e| warn!("[DOMÍNIO] Bootstrap RDAP indisponível: {}", e))
+                .ok();
+            // Sem o bootstrap, tenta de novo depois de `RDAP_RETRY_SECS`
+            let Some(bootstrap) = bootstrap else { return };
+            let mut updated = HashMap::new();
+            for domain in due {
+                let checked = Local::now().timestamp();
+                let record = match rdap_expiration(&client, &bootstrap, &domain) {
+                    Ok(expires) => {
+                        info!("[DOMÍNIO] {} vence em {}", domain, format_timestamp(expires, "%d/%m/%Y"));
+                        DomainRecord { checked, expires: Some(expires), error: None }
+                    }
+                    Err(e) => {
+                        warn!("[DOMÍNIO] {}: {}", domain, e);
+                        DomainRecord { checked, expires: None, error: Some(e) }
+                    }
+                };
+                records.insert(domain.clone(), record.clone());
+                updated.insert(domain, record);
+            }
+            let json = serde_json::to_string_pretty(&records).unwrap_or_default();
+            if let Err(e) = fs::write(domain_state_path(), json) {
+                error!("Erro ao gravar estado dos domínios: {}", e);
+            }
+            let _ = tx.send(updated);
+        });
+        self.pending = Some(rx);
+        self.last_run = Some(Instant::now());
+        expiring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registrable_domains() {
+        assert_eq!(registrable_domain("https://www.exemplo.com.br/x").as_deref(), Some("exemplo.com.br"));
+        assert_eq!(registrable_domain("api.github.com:443").as_deref(), Some("github.com"));
+        assert_eq!(registrable_domain("1.1.1.1"), None);
+        assert_eq!(registrable_domain("localhost"), None);
+    }
+}
@@ -0,0 +1,253 @@
+//! Histórico persistente das checagens e uptime.
+
+use super::*;
+
+pub const HISTORY_RETENTION_SECS: i64 = 30 * 24 * 3600;
+pub(crate) const HISTORY_COMPACT_EVERY_SECS: i64 = 24 * 3600;
+pub const DAY_SECS: i64 = 24 * 3600;
+/// Limites superiores (ms) das faixas do mapa de calor; a última faixa é aberta.
+pub(crate) const HEATMAP_BUCKETS_MS: [f64; 10] = [2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0, 2000.0];
+pub(crate) const HEATMAP_COLUMN_SECS: i64 = 3600;
+pub const SPARKLINE_SAMPLES: usize = 20;
+pub(crate) const SPARKLINE_LEVELS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Uma checagem registrada em `history.jsonl` (uma linha JSON por amostra).
+#[derive(Serialize, Deserialize, Clone)]
+pub struct HistorySample {
+    pub ts: i64,
+    pub host: String,
+    pub up: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<f64>,
+}
+
+/// Percentual de checagens com sucesso em cada janela; vazio sem amostras.
+#[derive(Clone, Copy, Default, Serialize)]
+pub struct UptimeStats {
+    pub day: Option<f64>,
+    pub week: Option<f64>,
+    pub month: Option<f64>,
+}
+
+/// Histórico persistente das checagens, mantido também em memória para consultas.
+pub struct HistoryStore {
+    pub path: PathBuf,
+    pub samples: HashMap<String, VecDeque<HistorySample>>,
+    pub last_compaction: i64,
+}
+
+pub fn get_data_dir() -> PathBuf {
+    let dirs = directories::ProjectDirs::from("com", "cosmicpinger", "cosmic_pinger")
+        .expect("Não foi possível determinar o diretório de dados");
+    let path = dirs.data_dir();
+    if let Err(e) = fs::create_dir_all(path) {
+        error!("Erro ao criar diretório de dados: {}", e);
+    }
+    path.to_path_buf()
+}
+
+pub(crate) fn history_path() -> PathBuf {
+    get_data_dir().join("history.jsonl")
+}
+
+impl HistoryStore {
+    /// Lê do disco as amostras a partir de `since`, sem depender do monitor em execução.
+    pub fn read_since(since: i64) -> Vec<HistorySample> {
+        let Ok(content) = fs::read_to_string(history_path()) else {
+            return Vec::new();
+        };
+        let mut samples: Vec<HistorySample> = content
+            .lines()
+            .filter_map(|line| serde_json::from_str::<HistorySample>(line).ok())
+            .filter(|s| s.ts >= since)
+            .collect();
+        samples.sort_by_key(|s| s.ts);
+        samples
+    }
+
+    pub fn open() -> Self {
+        let mut store = Self {
+            path: history_path(),
+            samples: HashMap::new(),
+            last_compaction: 0,
+        };
+        if let Ok(content) = fs::read_to_string(&store.path) {
+            for line in content.lines() {
+                // Linhas truncadas (ex.: queda de energia) são simplesmente ignoradas
+                if let Ok(sample) = serde_json::from_str::<HistorySample>(line) {
+                    store.samples.entry(sample.host.clone()).or_default().push_back(sample);
+                }
+            }
+        }
+        store.compact(Local::now().timestamp());
+        store
+    }
+
+    pub fn record(&mut self, samples: &[HistorySample]) {
+        use std::io::Write;
+
+        if samples.is_empty() {
+            return;
+        }
+        let mut lines = String::new();
+        for sample in samples {
+            if let Ok(json) = serde_json::to_string(sample) {
+                lines.push_str(&json);
+                lines.push('\n');
+            }
+            self.samples.entry(sample.host.clone()).or_default().push_back(sample.clone());
+        }
+
+        let appended = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(lines.as_bytes()));
+        if let Err(e) = appended {
+            error!("Erro ao gravar histórico: {}", e);
+        }
+
+        let now = samples[0].ts;
+        if now - self.last_compaction >= HISTORY_COMPACT_EVERY_SECS {
+            self.compact(now);
+        }
+    }
+
+    /// Descarta amostras fora da retenção e regrava o arquivo.
+    pub fn compact(&mut self, now: i64) {
+        let cutoff = now - HISTORY_RETENTION_SECS;
+        let mut lines = String::new();
+        for queue in self.samples.values_mut() {
+            while queue.front().map(|s| s.ts < cutoff).unwrap_or(false) {
+                queue.pop_front();
+            }
+        }
+        self.samples.retain(|_, queue| !queue.is_empty());
+
+        let mut all: Vec<&HistorySample> = self.samples.values().flatten().collect();
+        all.sort_by_key(|s| s.ts);
+        for sample in all {
+            if let Ok(json) = serde_json::to_string(sample) {
+                lines.push_str(&json);
+                lines.push('\n');
+            }
+        }
+
+        let tmp = self.path.with_extension("jsonl.tmp");
+        if let Err(e) = fs::write(&tmp, lines).and_then(|_| fs::rename(&tmp, &self.path)) {
+            error!("Erro ao compactar histórico: {}", e);
+        }
+        self.last_compaction = now;
+    }
+
+    pub fn uptime(&self, host: &str, window_secs: i64, now: i64) -> Option<f64> {
+        let queue = self.samples.get(host)?;
+        let cutoff = now - window_secs;
+        let (total, up) = queue
+            .iter()
+            .rev()
+            .take_while(|s| s.ts >= cutoff)
+            .fold((0u32, 0u32), |(total, up), s| (total + 1, up + s.up as u32));
+        (total > 0).then(|| up as f64 * 100.0 / total as f64)
+    }
+
+    /// A queda mais recente do destino no histórico; `end` é `now` se ainda em andamento.
+    pub fn last_outage(&self, host: &str, now: i64) -> Option<Outage> {
+        let mut newest_first = self.samples.get(host)?.iter().rev().peekable();
+        // A amostra online mais antiga depois da queda marca o fim dela
+        let mut end = None;
+        while let Some(sample) = newest_first.next_if(|s| s.up) {
+            end = Some(sample.ts);
+        }
+        let mut start = None;
+        while let Some(sample) = newest_first.next_if(|s| !s.up) {
+            start = Some(sample.ts);
+        }
+        Some(Outage { start: start?, end: end.unwrap_or(now), ongoing: end.is_none() })
+    }
+
+    /// Latências das últimas `limit` checagens, da mais antiga à mais recente;
+    /// `None` marca uma falha. Sucessos sem latência medida ficam de fora.
+    pub fn recent_latencies(&self, host: &str, limit: usize) -> Vec<Option<f64>> {
+        let Some(queue) = self.samples.get(host) else {
+            return Vec::new();
+        };
+        let mut recent: Vec<Option<f64>> = queue
+            .iter()
+            .rev()
+            .filter_map(|s| if s.up { s.latency_ms.map(Some) } else { Some(None) })
+            .take(limit)
+            .collect();
+        recent.reverse();
+        recent
+    }
+
+    pub fn uptime_stats(&self, host: &str, now: i64) -> UptimeStats {
+        UptimeStats {
+            day: self.uptime(host, DAY_SECS, now),
+            week: self.uptime(host, 7 * DAY_SECS, now),
+            month: self.uptime(host, 30 * DAY_SECS, now),
+        }
+    }
+}
+
+/// Contagem de checagens por hora e faixa de latência, no estilo do Smokeping.
+#[derive(Serialize, Default)]
+pub(crate) struct HeatmapSeries {
+    /// Uma linha por coluna de tempo, com `HEATMAP_BUCKETS_MS.len() + 1` faixas.
+    pub counts: Vec<Vec<u32>>,
+    pub lost: Vec<u32>,
+    pub total: Vec<u32>,
+}
+
+#[derive(Serialize)]
+pub(crate) struct Heatmap {
+    pub from: i64,
+    pub column_secs: i64,
+    pub buckets_ms: &'static [f64],
+    pub targets: HashMap<String, HeatmapSeries>,
+}
+
+/// Agrupa as amostras de `[from, to)` em colunas de uma hora por destino.
+/// Falhas entram só em `lost`; sucessos sem latência medida contam no total.
+pub(crate) fn latency_heatmap(samples: &[HistorySample], from: i64, to: i64) -> Heatmap {
+    let columns = ((to - from).max(0) + HEATMAP_COLUMN_SECS - 1) / HEATMAP_COLUMN_SECS;
+    let mut targets: HashMap<String, HeatmapSeries> = HashMap::new();
+    for sample in samples.iter().filter(|s| s.ts >= from && s.ts < to) {
+        let series = targets.entry(sample.host.clone()).or_insert_with(|| HeatmapSeries {
+            counts: vec![vec![0; HEATMAP_BUCKETS_MS.len() + 1]; columns as usize],
+            lost: vec![0; columns as usize],
+            total: vec![0; columns as usize],
+        });
+        let column = ((sample.ts - from) / HEATMAP_COLUMN_SECS) as usize;
+        series.total[column] += 1;
+        if !sample.up {
+            series.lost[column] += 1;
+        } else if let Some(latency) = sample.latency_ms {
+            let bucket = HEATMAP_BUCKETS_MS.iter().position(|&limit| latency < limit).unwrap_or(HEATMAP_BUCKETS_MS.len());
+            series.counts[column][bucket] += 1;
+        }
+    }
+    Heatmap { from, column_secs: HEATMAP_COLUMN_SECS, buckets_ms: &HEATMAP_BUCKETS_MS, targets }
+}
+
+/// Minigráfico em texto (▁▂▃▅▇) das latências, escalado entre a menor e a
+/// maior da série; falhas aparecem como `×`.
+pub fn sparkline(latencies: &[Option<f64>]) -> String {
+    let values = latencies.iter().flatten();
+    let min = values.clone().copied().fold(f64::INFINITY, f64::min);
+    let max = values.copied().fold(f64::NEG_INFINITY, f64::max);
+    let top = (SPARKLINE_LEVELS.len() - 1) as f64;
+    latencies
+        .iter()
+        .map(|latency| match latency {
+            None => '×',
+            Some(_) if max <= min => SPARKLINE_LEVELS[0],
+            Some(ms) => SPARKLINE_LEVELS[((ms - min) / (max - min) * top).round() as usize],
+        })
+        .collect()
+}
+
+pub fn format_uptime(value: Option<f64>) -> String {
+    value.map(|v| format!("{:.2}%", v)).unwrap_or_else(|| "—".to_string())
+}
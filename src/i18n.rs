@@ -0,0 +1,413 @@
+//! Textos da interface em português e inglês.
+
+use super::*;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum Lang {
+    Pt,
+    En,
+}
+
+impl Lang {
+    /// Aceita códigos como `pt`, `pt_BR.UTF-8` ou `en-US`.
+    pub fn parse(raw: &str) -> Option<Lang> {
+        let code = raw.trim().to_ascii_lowercase();
+        let code = code.split(['_', '-', '.', '@']).next().unwrap_or_default();
+        match code {
+            "pt" => Some(Lang::Pt),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// Idioma do ambiente, na ordem de prioridade do gettext. Só um locale
+    /// `en` troca para o inglês: sem locale, `C`/`POSIX` ou idiomas sem
+    /// tradução o app continua em português, como sempre foi.
+    pub fn from_env() -> Lang {
+        for var in ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"] {
+            let Ok(value) = std::env::var(var) else {
+                continue;
+            };
+            // LANGUAGE aceita uma lista de preferência separada por ':'
+            let mut entries = value.split(':').filter(|v| !v.trim().is_empty()).peekable();
+            if entries.peek().is_none() {
+                continue;
+            }
+            return entries.find_map(Lang::parse).unwrap_or(Lang::Pt);
+        }
+        Lang::Pt
+    }
+}
+
+pub(crate) static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// Idioma da interface: `language` em `sites.json` tem prioridade sobre o locale.
+/// Lido uma vez por processo; trocar o idioma exige reiniciar o app.
+pub(crate) fn lang() -> Lang {
+    *LANG.get_or_init(|| {
+        let configured = load_config().ok().and_then(|cfg| cfg.language);
+        match configured.as_deref().map(str::trim) {
+            None | Some("") | Some("auto") => Lang::from_env(),
+            Some(raw) => Lang::parse(raw).unwrap_or_else(|| {
+                warn!("Idioma desconhecido em language: '{}'; usando o do sistema", raw);
+                Lang::from_env()
+            }),
+        }
+    })
+}
+
+/// Catálogo de mensagens da interface: (chave, português, inglês). Marcadores
+/// `{nome}` são substituídos por `trf`.
+pub(crate) const MESSAGES: &[(&str, &str, &str)] = &[
+    // Alertas
+    ("alert.up", "✅ {host} voltou a responder", "✅ {host} is responding again"),
+    ("alert.down", "❌ {host} ficou OFFLINE", "❌ {host} went OFFLINE"),
+    ("alert.latency", " — latência {latency} ms", " — latency {latency} ms"),
+    ("alert.outage", " — ficou fora por {duration}", " — was down for {duration}"),
+    ("alert.grouped_up", "✅ {count} destinos voltaram a responder: {hosts}", "✅ {count} targets are responding again: {hosts}"),
+    ("alert.grouped_down", "❌ {count} destinos ficaram OFFLINE: {hosts}", "❌ {count} targets went OFFLINE: {hosts}"),
+    (
+        "alert.flap_started",
+        "〰️ {host} está oscilando; alertas individuais suspensos até estabilizar.",
+        "〰️ {host} is flapping; individual alerts are suspended until it stabilizes.",
+    ),
+    ("alert.flap_ended_up", "{host} estabilizou e está ONLINE.", "{host} stabilized and is ONLINE."),
+    ("alert.group_down", "❌ Grupo {group}: {down} de {total} destinos OFFLINE ({hosts})", "❌ Group {group}: {down} of {total} targets OFFLINE ({hosts})"),
+    ("alert.group_up", "✅ Grupo {group} normalizado ({down} de {total} offline)", "✅ Group {group} back to normal ({down} of {total} offline)"),
+    ("alert.flap_ended_down", "{host} estabilizou e está OFFLINE.", "{host} stabilized and is OFFLINE."),
+    (
+        "alert.latency_anomaly",
+        "🐢 {host} respondendo em {latency} ms (normal ~{baseline} ms).",
+        "🐢 {host} responding in {latency} ms (usually ~{baseline} ms).",
+    ),
+    ("alert.degraded", "🟡 {host} está DEGRADADO: {detail}", "🟡 {host} is DEGRADED: {detail}"),
+    ("alert.degraded_recovered", "✅ {host} voltou ao normal ({detail})", "✅ {host} is back to normal ({detail})"),
+    // Notificações avulsas
+    (
+        "notify.config_error",
+        "⚠️ Configuração inválida, usando a última lista válida.\n{error}\nUse \"Restaurar backup\" no menu.",
+        "⚠️ Invalid configuration, using the last valid list.\n{error}\nUse \"Restore backup\" from the menu.",
+    ),
+    (
+        "notify.icmp_hint",
+        "Pings usando o binário do sistema (ICMP nativo bloqueado).\nPara liberar sem root: sudo sysctl -w net.ipv4.ping_group_range=\"0 2147483647\"\nou: sudo setcap cap_net_raw+ep {exe}",
+        "Pinging through the system binary (native ICMP is blocked).\nTo allow it without root: sudo sysctl -w net.ipv4.ping_group_range=\"0 2147483647\"\nor: sudo setcap cap_net_raw+ep {exe}",
+    ),
+    ("notify.acknowledge", "Reconhecer", "Acknowledge"),
+    ("history.empty", "{host}: nenhuma checagem nas últimas 24 h", "{host}: no checks in the last 24 h"),
+    ("history.header", "{host} — últimas checagens:\n{lines}", "{host} — latest checks:\n{lines}"),
+    // Relatórios
+    ("report.daily", "diário", "Daily"),
+    ("report.weekly", "semanal", "Weekly"),
+    ("report.summary", "📊 Resumo {period}: {count} queda(s).", "📊 {period} summary: {count} outage(s)."),
+    ("report.summary_line", "\n• {host}: {duration} fora ({count} queda(s))", "\n• {host}: {duration} down ({count} outage(s))"),
+    ("report.details", "\nDetalhes: {path}", "\nDetails: {path}"),
+    ("report.html_lang", "pt-BR", "en"),
+    ("report.title", "Relatório", "Report"),
+    ("report.period", "Período: {from} até {to}", "Period: {from} to {to}"),
+    ("report.col_target", "Destino", "Target"),
+    ("report.col_uptime", "Uptime", "Uptime"),
+    ("report.col_outages", "Quedas", "Outages"),
+    ("report.col_downtime", "Tempo fora", "Downtime"),
+    ("report.col_worst_latency", "Pior latência", "Worst latency"),
+    ("report.ongoing", " — em andamento", " — ongoing"),
+    ("status_page.all_up", "Todos os sistemas operacionais", "All systems operational"),
+    ("status_page.some_down", "{count} destino(s) fora do ar", "{count} target(s) down"),
+    ("status_page.updated", "Atualizado em {time}", "Updated at {time}"),
+    ("status_page.col_status", "Status", "Status"),
+    ("status_page.online", "Online", "Online"),
+    ("status_page.offline", "Offline", "Offline"),
+    ("status_page.degraded", "Degradado", "Degraded"),
+    ("status_page.paused", "Pausado", "Paused"),
+    ("status_page.incidents", "Quedas nos últimos 7 dias", "Outages in the last 7 days"),
+    ("status_page.no_incidents", "Nenhuma queda registrada.", "No outages recorded."),
+    // Resultados de checagem
+    ("onboarding.title", "Bem-vindo ao Cosmic Pinger", "Welcome to Cosmic Pinger"),
+    ("onboarding.intro", "Escolha o que monitorar. Estes destinos foram detectados na sua rede:", "Pick what to monitor. These targets were detected on your network:"),
+    ("onboarding.gateway", "{address} — roteador (gateway padrão)", "{address} — router (default gateway)"),
+    ("onboarding.dns", "{address} — servidor DNS", "{address} — DNS server"),
+    ("onboarding.internet", "{address} — destino público (Cloudflare), para saber se a internet caiu", "{address} — public target (Cloudflare), to tell whether the internet is down"),
+    ("onboarding.extra", "Outro destino (opcional): site, IP ou URL", "Another target (optional): site, IP or URL"),
+    ("onboarding.invalid", "Destino inválido: {address}", "Invalid target: {address}"),
+    ("onboarding.tray", "O app fica no ícone da bandeja: verde quando tudo responde, vermelho quando algum destino cai. O clique esquerdo abre o dashboard ou as configurações, o do meio checa na hora, e cada destino tem um submenu com histórico, pausa e diagnóstico. Quedas viram notificações.", "The app lives in the tray icon: green when everything answers, red when a target goes down. Left click opens the dashboard or the settings, middle click checks right away, and each target has a submenu with history, pause and diagnostics. Outages become notifications."),
+    ("onboarding.start", "Começar a monitorar", "Start monitoring"),
+    ("onboarding.skip", "Pular", "Skip"),
+    ("onboarding.none", "Escolha pelo menos um destino", "Pick at least one target"),
+    ("bundle.button", "Gerar pacote de suporte", "Create support bundle"),
+    ("bundle.running", "Gravando pacote de suporte…", "Writing support bundle…"),
+    ("bundle.saved", "Pacote de suporte salvo em {path}", "Support bundle saved to {path}"),
+    ("bundle.failed", "Falha ao gerar o pacote de suporte: {error}", "Failed to create the support bundle: {error}"),
+    ("diag.check", "Checagem", "Check"),
+    ("diag.ping", "Ping", "Ping"),
+    ("diag.ping_summary", "{backend}: {sent} enviados, {received} recebidos, {loss}% de perda", "{backend}: {sent} sent, {received} received, {loss}% loss"),
+    ("diag.dns", "DNS", "DNS"),
+    ("diag.dns_system", "Resolvedor do sistema ({ms} ms): {ips}", "System resolver ({ms} ms): {ips}"),
+    ("diag.dns_system_failed", "Resolvedor do sistema falhou", "System resolver failed"),
+    ("diag.traceroute", "Rota", "Route"),
+    ("diag.no_traceroute", "traceroute/tracepath não encontrado", "traceroute/tracepath not found"),
+    ("diag.http", "HTTP", "HTTP"),
+    ("diag.report_heading", "Diagnóstico de {address} — {time} (Cosmic Pinger {version})", "Diagnostics for {address} — {time} (Cosmic Pinger {version})"),
+    ("diag.heading", "Diagnóstico de {address}", "Diagnostics for {address}"),
+    ("diag.running", "Rodando ping, DNS, rota e HTTP…", "Running ping, DNS, route and HTTP…"),
+    ("diag.copy", "Copiar", "Copy"),
+    ("diag.copied", "Copiado para a área de transferência", "Copied to the clipboard"),
+    ("tray.diagnose", "Diagnosticar…", "Diagnose…"),
+    ("config.diagnose", "Diagnosticar", "Diagnose"),
+    ("cert.empty", "O servidor não apresentou certificado", "The server presented no certificate"),
+    ("alert.domain_expiring", "📅 O domínio {domain} vence em {days} dia(s) ({date})", "📅 Domain {domain} expires in {days} day(s) ({date})"),
+    ("alert.content_changed", "📝 O conteúdo de {host} mudou", "📝 The content of {host} changed"),
+    ("content.failed", "acompanhamento de conteúdo falhou: {error}", "content tracking failed: {error}"),
+    ("content.bad_selector", "seletor inválido: {error}", "invalid selector: {error}"),
+    ("content.no_match", "nada corresponde a {selector}", "nothing matches {selector}"),
+    ("feed.fresh", "último item há {age}", "newest item {age} ago"),
+    ("feed.stale", "Feed parado: último item há {age} (limite {limit})", "Stale feed: newest item {age} ago (limit {limit})"),
+    ("feed.empty", "Feed sem itens datados", "Feed has no dated items"),
+    ("feed.error", "Feed ilegível: {error}", "Unreadable feed: {error}"),
+    ("headers.missing", "sem cabeçalho {headers}", "missing header {headers}"),
+    ("headers.failed", "auditoria de cabeçalhos falhou: {error}", "header audit failed: {error}"),
+    ("revocation.revoked", "certificado revogado", "certificate revoked"),
+    ("revocation.unknown", "revogação indeterminada: {error}", "revocation unknown: {error}"),
+    ("revocation.no_crl", "certificado sem ponto de distribuição de CRL", "certificate has no CRL distribution point"),
+    ("cert.heading", "Certificado de {address}", "Certificate for {address}"),
+    ("cert.loading", "Conectando…", "Connecting…"),
+    ("cert.trusted", "🟢 Cadeia confiável", "🟢 Trusted chain"),
+    ("cert.untrusted", "🔴 Não confiável: {error}", "🔴 Not trusted: {error}"),
+    ("cert.issuer", "Emitido por: {issuer}", "Issued by: {issuer}"),
+    ("cert.validity", "Válido de {from} até {to} ({remaining})", "Valid from {from} to {to} ({remaining})"),
+    ("cert.expires_in", "expira em {days} dia(s)", "expires in {days} day(s)"),
+    ("cert.expired", "expirou há {days} dia(s)", "expired {days} day(s) ago"),
+    ("cert.sans", "Nomes: {names}", "Names: {names}"),
+    ("check.no_valid_targets", "Nenhum site válido", "No valid sites"),
+    ("check.error", "Erro", "Error"),
+    ("check.timeout", "Tempo esgotado", "Timed out"),
+    ("check.http_unavailable", "HTTP indisponível", "HTTP unavailable"),
+    ("check.http_error", "HTTP erro", "HTTP error"),
+    ("check.ping_stats", "{avg} ms (mín {min} / máx {max}, perda {loss}%)", "{avg} ms (min {min} / max {max}, {loss}% loss)"),
+    ("check.ping_loss", "OK (perda {loss}%)", "OK ({loss}% loss)"),
+    ("check.icmp_filtered", "ICMP filtrado · TCP {port} em {latency} ms", "ICMP filtered · TCP {port} in {latency} ms"),
+    ("check.tcp_open", "TCP {port} em {latency} ms", "TCP {port} in {latency} ms"),
+    ("check.tcp_closed", "TCP {port} fechada", "TCP {port} closed"),
+    ("check.tcp_no_port", "Sem porta em {target}", "No port in {target}"),
+    ("check.ports_open", "Portas abertas: {ports}", "Open ports: {ports}"),
+    ("check.ports_closed", "Fechadas: {closed} (abertas: {open})", "Closed: {closed} (open: {open})"),
+    ("check.ports_all_closed", "Todas fechadas: {ports}", "All closed: {ports}"),
+    ("check.dns_records", "{kind}: {records}", "{kind}: {records}"),
+    ("check.dns_mismatch", "{kind} inesperado: {records} (esperado {expected})", "Unexpected {kind}: {records} (expected {expected})"),
+    ("check.dns_nxdomain", "{name} não existe (NXDOMAIN)", "{name} does not exist (NXDOMAIN)"),
+    ("check.dns_empty", "Sem registros {kind} para {name}", "No {kind} records for {name}"),
+    ("check.dns_rcode", "Servidor DNS respondeu com erro (rcode {rcode})", "DNS server answered with an error (rcode {rcode})"),
+    ("check.dns_bad_target", "Consulta DNS inválida: {target}", "Invalid DNS query: {target}"),
+    ("check.dns_no_server", "Nenhum servidor DNS em /etc/resolv.conf; use ?server=", "No DNS server in /etc/resolv.conf; use ?server="),
+    ("check.dns_bad_server", "Servidor DNS inválido: {server}", "Invalid DNS server: {server}"),
+    ("check.dns_truncated", "Resposta DNS truncada", "Truncated DNS response"),
+    ("check.dns_bad_response", "Resposta DNS inválida", "Invalid DNS response"),
+    ("check.fail_count", "{message} (falha {count}/{threshold})", "{message} (failure {count}/{threshold})"),
+    ("check.script_error", "Erro no script: {error}", "Script error: {error}"),
+    ("check.script_no_result", "Script sem resultado", "Script returned no result"),
+    ("check.unsupported_scheme", "Tipo de checagem desconhecido: {scheme}://", "Unknown check type: {scheme}://"),
+    // Bandeja e barras de status
+    ("status.paused", "⏸️ pausado", "⏸️ paused"),
+    ("status.waiting", "Aguardando...", "Waiting..."),
+    ("tray.title_down", "{app} ({count} offline)", "{app} ({count} offline)"),
+    ("tray.tip_paused", "⏸️ Pausado até {time}", "⏸️ Paused until {time}"),
+    ("tray.tip_starting", "Iniciando...", "Starting..."),
+    ("tray.tip_ok", "Online - {count} sites monitorados", "Online - {count} sites monitored"),
+    ("tray.tip_empty", "Nenhum destino monitorado", "No targets monitored"),
+    ("tray.add_targets", "➕ Adicionar destinos…", "➕ Add targets…"),
+    ("status.empty", "⚪ sem destinos", "⚪ no targets"),
+    ("tray.tip_down", "⚠️ OFFLINE DETECTADO", "⚠️ OFFLINE DETECTED"),
+    ("tray.tip_degraded", "🟡 {count} destino(s) degradado(s)", "🟡 {count} target(s) degraded"),
+    ("tray.last_check", "Última checagem: {time}", "Last check: {time}"),
+    ("tray.paused_until", " — ⏸️ pausado até {time}", " — ⏸️ paused until {time}"),
+    ("tray.config_invalid", "⚠️ sites.json inválido", "⚠️ sites.json is invalid"),
+    ("tray.restore_backup", "Restaurar backup da configuração", "Restore configuration backup"),
+    ("tray.note_flapping", " — oscilando", " — flapping"),
+    ("tray.note_slow", " — 🐢 lenta (normal ~{baseline} ms)", " — 🐢 slow (usually ~{baseline} ms)"),
+    ("tray.note_sla", " — abaixo do SLA", " — below SLA"),
+    ("tray.note_degraded", " — degradado", " — degraded"),
+    ("tray.note_acknowledged", " — reconhecida", " — acknowledged"),
+    ("tray.note_down_for", " — fora há {duration}", " — down for {duration}"),
+    ("tray.last_outage", "Última queda: {start} – {end} ({duration})", "Last outage: {start} – {end} ({duration})"),
+    ("tray.last_outage_ongoing", "Queda em andamento desde {start} ({duration})", "Outage in progress since {start} ({duration})"),
+    ("tray.no_outage", "Nenhuma queda em 30 dias", "No outages in 30 days"),
+    ("tray.uptime_day", "Uptime 24h: {uptime}", "Uptime 24h: {uptime}"),
+    ("tray.uptime_week", "Uptime 7d: {uptime}", "Uptime 7d: {uptime}"),
+    ("tray.uptime_month", "Uptime 30d: {uptime}", "Uptime 30d: {uptime}"),
+    ("tray.sla", "SLA: {percent}%{mark}", "SLA: {percent}%{mark}"),
+    ("tray.note_paused", " — pausado até {time}", " — paused until {time}"),
+    ("tray.note_next_check", " — próxima checagem {time}", " — next check {time}"),
+    ("tray.suppressed", "🔕 {count} alerta(s) suprimido(s) na última hora", "🔕 {count} alert(s) suppressed in the last hour"),
+    ("tray.update_available", "⬆️ Nova versão disponível: {version}", "⬆️ New version available: {version}"),
+    ("tray.network", "🌐 Rede: {network}", "🌐 Network: {network}"),
+    (
+        "tray.no_ping",
+        "⚠️ Sem ping no sistema: checando pelas portas TCP 443/80",
+        "⚠️ No ping on this system: checking over TCP ports 443/80",
+    ),
+    ("tray.dns_time", "🔎 Resolução DNS: {ms} ms", "🔎 DNS resolution: {ms} ms"),
+    ("tray.tip_muted", "🔕 Notificações silenciadas até {time}", "🔕 Notifications muted until {time}"),
+    ("tray.tip_muted_resume", "🔕 Notificações silenciadas", "🔕 Notifications muted"),
+    ("tray.mute_hour", "🔕 Silenciar notificações por 1 h", "🔕 Mute notifications for 1 h"),
+    ("tray.mute_resume", "🔕 Silenciar notificações até reativar", "🔕 Mute notifications until resumed"),
+    ("tray.unmute", "🔔 Reativar notificações", "🔔 Unmute notifications"),
+    ("tray.check_now", "🔄 Checar agora", "🔄 Check now"),
+    ("tray.open_browser", "🌐 Abrir no navegador", "🌐 Open in browser"),
+    ("tray.copy_address", "📋 Copiar endereço", "📋 Copy address"),
+    ("tray.resume", "▶️ Retomar", "▶️ Resume"),
+    ("tray.pause_hour", "⏸️ Pausar por 1 h", "⏸️ Pause for 1 h"),
+    ("tray.watch", "Acompanhar ao vivo", "Watch live"),
+    ("tray.certificate", "Certificado…", "Certificate info…"),
+    ("tray.show_history", "📈 Mostrar histórico", "📈 Show history"),
+    ("tray.acknowledge", "🔕 Reconhecer queda", "🔕 Acknowledge outage"),
+    ("tray.event_outage", " — fora por {duration}", " — down for {duration}"),
+    ("tray.no_events", "Nenhuma mudança de estado", "No state changes"),
+    ("tray.recent_events", "🕘 Eventos recentes", "🕘 Recent events"),
+    ("tray.open_event_log", "📄 Abrir log de eventos", "📄 Open event log"),
+    ("tray.configure", "⚙️ Configurar Sites", "⚙️ Configure Sites"),
+    ("tray.open_dashboard", "📊 Abrir dashboard", "📊 Open dashboard"),
+    ("tray.quit", "Sair", "Quit"),
+    // Janela de configuração
+    ("config.title", "Configuração", "Settings"),
+    ("config.heading", "Monitoramento", "Monitoring"),
+    ("config.placeholder", "Ex: google.com", "e.g. google.com"),
+    ("config.add", " + Adicionar ", " + Add "),
+    ("config.remove", " Remover ", " Remove "),
+    ("config.removed", "🗑️ {address} removido", "🗑️ {address} removed"),
+    ("config.undo", "Desfazer", "Undo"),
+    ("config.test", " Testar ", " Test "),
+    ("config.watch", "Ao vivo", "Watch"),
+    ("config.certificate", "Certificado", "Certificate"),
+    ("config.edit", " Editar… ", " Edit… "),
+    ("editor.title", "Editar destino", "Edit target"),
+    ("editor.address", "Endereço", "Address"),
+    ("editor.tags", "Tags (separadas por vírgula)", "Tags (comma separated)"),
+    ("editor.sla", "SLA (% em 30 dias)", "SLA (% over 30 days)"),
+    ("editor.attempts", "Tentativas de ping", "Ping attempts"),
+    ("watch.heading", "Ao vivo: {address}", "Watching {address}"),
+    ("watch.summary", "{sent} enviados, {received} respondidos, perda {loss}%", "{sent} sent, {received} answered, {loss}% loss"),
+    ("watch.latency", " · mín {min} / méd {avg} / máx {max} ms", " · min {min} / avg {avg} / max {max} ms"),
+    ("watch.close", "Fechar", "Close"),
+    ("editor.probes", "Pings por checagem (estatísticas)", "Pings per check (statistics)"),
+    ("editor.check", "Tipo de checagem (ping, http, https, tcp)", "Check type (ping, http, https, tcp)"),
+    ("editor.checks", "Checagens extras (separadas por vírgula)", "Extra checks (comma-separated)"),
+    ("editor.policy", "Combinação (all, any)", "Combination (all, any)"),
+    ("editor.degraded_ms", "Degradado acima de (ms)", "Degraded above (ms)"),
+    ("editor.timeout_ms", "Timeout da checagem (ms)", "Check timeout (ms)"),
+    ("editor.fail_threshold", "Falhas seguidas até offline", "Failures in a row before offline"),
+    ("editor.max_per_hour", "Alertas por hora", "Alerts per hour"),
+    ("editor.schedule", "Agendamento (cron)", "Schedule (cron)"),
+    ("editor.disabled_notifiers", "Canais desligados (vírgula)", "Disabled channels (comma separated)"),
+    ("editor.notifiers", "Só estes canais (vírgula)", "Only these channels (comma separated)"),
+    ("editor.urgency", "Gravidade (low, normal, critical)", "Urgency (low, normal, critical)"),
+    ("editor.notify", "Enviar alertas deste destino", "Send alerts for this target"),
+    ("editor.kuma", "URL push do Uptime Kuma", "Uptime Kuma push URL"),
+    (
+        "editor.hint",
+        "Campos vazios usam o padrão global. Canais: desktop, webhook, email, ntfy, matrix, gotify, pushover, pagerduty, opsgenie.",
+        "Empty fields use the global default. Channels: desktop, webhook, email, ntfy, matrix, gotify, pushover, pagerduty, opsgenie.",
+    ),
+    ("editor.save", "Salvar", "Save"),
+    ("editor.cancel", "Cancelar", "Cancel"),
+    ("editor.empty_address", "O endereço não pode ficar vazio", "The address cannot be empty"),
+    ("editor.invalid_number", "{field}: número inválido", "{field}: invalid number"),
+    ("editor.invalid_sla", "O SLA deve estar entre 0 e 100", "The SLA must be between 0 and 100"),
+    ("editor.invalid_policy", "Combinação inválida: use all ou any", "Invalid combination: use all or any"),
+    ("editor.invalid_urgency", "Gravidade inválida: use low, normal ou critical", "Invalid urgency: use low, normal or critical"),
+    ("editor.invalid_schedule", "Agendamento inválido: {error}", "Invalid schedule: {error}"),
+    ("config.testing", "Testando…", "Testing…"),
+    ("config.bulk_open", "📋 Colar uma lista de destinos", "📋 Paste a list of targets"),
+    ("config.bulk_close", "Fechar a lista", "Close the list"),
+    ("config.bulk_add", "Adicionar todos", "Add all"),
+    (
+        "config.bulk_summary",
+        "{added} adicionado(s), {duplicates} repetido(s), {invalid} inválido(s).",
+        "{added} added, {duplicates} duplicate(s), {invalid} invalid.",
+    ),
+    ("config.bulk_invalid", " Inválidos: {entries}", " Invalid: {entries}"),
+    ("presets.open", "⭐ Adicionar destinos prontos", "⭐ Add ready-made targets"),
+    ("presets.close", "Fechar os destinos prontos", "Close ready-made targets"),
+    ("presets.local", "Sua rede", "Your network"),
+    ("presets.resolvers", "DNS públicos", "Public DNS resolvers"),
+    ("presets.services", "Serviços comuns", "Common services"),
+    ("presets.added", "já monitorado", "already monitored"),
+    ("config.count", "Sites monitorados: {count}", "Monitored sites: {count}"),
+    ("config.count_filtered", " (mostrando {count})", " (showing {count})"),
+    ("config.filter_placeholder", "Filtrar por endereço ou tag", "Filter by address or tag"),
+    (
+        "config.backup_notice",
+        "A lista abaixo veio do backup. Salvar irá sobrescrever o arquivo inválido.",
+        "The list below came from the backup. Saving will overwrite the invalid file.",
+    ),
+    ("config.restore_backup", " Restaurar backup ", " Restore backup "),
+    (
+        "config.newer_version",
+        "O sites.json está no esquema v{version}, mais novo que o suportado (v{supported}). As alterações feitas aqui não serão salvas; atualize o Cosmic Pinger.",
+        "sites.json uses schema v{version}, newer than this build supports (v{supported}). Changes made here will not be saved; update Cosmic Pinger.",
+    ),
+    ("config.restore_failed", "Falha ao restaurar backup: {error}", "Failed to restore backup: {error}"),
+    ("config.save_close", "Salvar e Fechar", "Save and Close"),
+    ("config.tab_targets", "Destinos", "Targets"),
+    ("config.theme", "Tema da janela", "Window theme"),
+    ("config.theme_system", "Sistema", "System"),
+    ("config.theme_light", "Claro", "Light"),
+    ("config.theme_dark", "Escuro", "Dark"),
+    ("config.tab_settings", "Ajustes", "Settings"),
+    ("config.tab_notifications", "Notificações", "Notifications"),
+    ("config.tab_status", "Status", "Status"),
+    ("config.tab_history", "Histórico", "History"),
+    ("history.all_targets", "Todos os destinos", "All targets"),
+    ("history.date_placeholder", "Data (AAAA-MM-DD)", "Date (YYYY-MM-DD)"),
+    ("history.count", "{count} evento(s) nos últimos 30 dias", "{count} event(s) in the last 30 days"),
+    (
+        "config.status_no_api",
+        "O status ao vivo usa a API local da instância em execução. Configure \"api\" em sites.json e reinicie o app para vê-lo aqui.",
+        "The live status uses the running instance's local API. Set up \"api\" in sites.json and restart the app to see it here.",
+    ),
+    ("config.status_loading", "Consultando a instância em execução…", "Querying the running instance…"),
+    ("config.status_error", "⚠️ Não foi possível consultar a instância em execução: {error}", "⚠️ Could not reach the running instance: {error}"),
+    ("config.interval", "Intervalo entre ciclos (s)", "Check interval (s)"),
+    ("config.http_timeout", "Timeout HTTP (s)", "HTTP timeout (s)"),
+    ("config.outage_check", "Rechecagem durante quedas (s, 0 desliga)", "Recheck during outages (s, 0 disables)"),
+    ("config.check_budget", "Tempo máximo por checagem (s)", "Time budget per check (s)"),
+    ("config.fail_threshold", "Falhas seguidas até ficar offline", "Failures in a row before offline"),
+    ("config.parallel", "Checagens em paralelo", "Parallel checks"),
+    ("config.recent_samples", "Amostras recentes em memória por destino", "Recent samples kept in memory per target"),
+    ("config.latency_factor", "Fator do alerta de latência (0 desliga)", "Latency alert factor (0 disables)"),
+    ("config.max_per_hour", "Alertas por destino por hora (0 sem limite)", "Alerts per target per hour (0 = no limit)"),
+    ("config.invalid_value", "⚠️ valor inválido", "⚠️ invalid value"),
+    (
+        "config.settings_hint",
+        "As mudanças valem a partir do próximo ciclo depois de salvar; o timeout HTTP exige reiniciar o app.",
+        "Changes apply from the next cycle after saving; the HTTP timeout requires restarting the app.",
+    ),
+    ("config.desktop", "Notificações de desktop", "Desktop notifications"),
+    ("config.group", "Agrupar mudanças do mesmo ciclo", "Group changes from the same cycle"),
+    ("config.remote_channels", "Canais remotos", "Remote channels"),
+    ("config.not_configured", "não configurado", "not configured"),
+    (
+        "config.remote_hint",
+        "Os canais remotos são configurados em sites.json; aqui eles só são ligados ou desligados.",
+        "Remote channels are set up in sites.json; here they can only be switched on or off.",
+    ),
+];
+
+/// Texto da chave no idioma atual. Chaves ausentes aparecem como estão, para
+/// que a falta de tradução fique visível sem derrubar a interface.
+pub fn tr(key: &'static str) -> &'static str {
+    match MESSAGES.iter().find(|(k, _, _)| *k == key) {
+        Some((_, pt, en)) => match lang() {
+            Lang::Pt => pt,
+            Lang::En => en,
+        },
+        None => {
+            debug!("[IDIOMA] Mensagem sem tradução: {}", key);
+            key
+        }
+    }
+}
+
+/// Como `tr`, substituindo os marcadores `{nome}` pelos argumentos.
+pub fn trf(key: &'static str, args: &[(&str, &dyn std::fmt::Display)]) -> String {
+    args.iter()
+        .fold(tr(key).to_string(), |text, (name, value)| text.replace(&format!("{{{}}}", name), &value.to_string()))
+}
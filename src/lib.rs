@@ -0,0 +1,154 @@
+//! Motor de monitoramento do Cosmic Pinger: destinos, checagens, histerese,
+//! agendamento, histórico e notificadores. A bandeja, a barra de status e a
+//! janela de configuração são binários finos sobre esta biblioteca.
+
+use notify_rust::Notification;
+#[cfg(target_os = "linux")]
+use notify_rust::Urgency;
+use reqwest::{blocking::Client, StatusCode};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use std::process::{self, Command as SysCommand};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{debug, error, info, warn};
+
+mod api;
+mod certs;
+mod checks;
+mod config;
+mod cron;
+mod diagnostics;
+mod domains;
+mod history;
+mod i18n;
+mod logs;
+mod monitor;
+mod network;
+mod notifiers;
+mod onboarding;
+mod ping;
+mod reports;
+mod scripts;
+mod status;
+mod tray;
+mod updates;
+
+// --- API PÚBLICA ---
+// Os módulos ficam privados; só o que está aqui faz parte da biblioteca.
+// Os internos entram nos módulos irmãos por `use super::*`.
+
+// Configuração e parâmetros de execução
+pub use config::{
+    config_newer_than_supported, load_config, load_config_backup, normalize_target, parse_log_level, parse_secs,
+    parse_target_list, restore_config_backup, runtime, save_config, ApiConfig, AppConfig, BulkImport, CheckPolicy,
+    DesktopConfig, EmailConfig, GeoIpConfig, GotifyConfig, GroupRule, InfluxConfig, MatrixConfig, MetricsConfig,
+    NotifierFilter, NtfyConfig, OpsgenieConfig, PagerDutyConfig, PushoverConfig, ReportConfig, ReportPeriod,
+    RuntimeSettings, Severity, StatusPageConfig, TargetConfig, ThemeChoice, UpdateCheckConfig, WebhookConfig,
+    ZabbixConfig, CONFIG_VERSION, RUNTIME,
+};
+pub use cron::CronSchedule;
+
+// Checagens
+pub use certs::{fetch_certificate_chain, CertReport, CertSummary};
+pub use checks::{check_target, checks, install_checks, run_check, test_target, Check, CheckContext, CheckLimits, CheckOutcome, CheckRegistry};
+pub use network::NetworkInfo;
+pub use ping::{pinger, PingFailure, Pinger};
+
+// Monitor e estado publicado
+pub use monitor::{
+    current_state, request_shutdown, run_monitor, state_channel, Health, MonitorCommand, Mute, PingerState, StateEvent,
+    StateReceiver, StateSender, TargetStatus,
+};
+pub use updates::ReleaseInfo;
+
+// Histórico e relatórios
+pub use history::{
+    format_uptime, get_data_dir, sparkline, HistorySample, HistoryStore, UptimeStats, DAY_SECS, HISTORY_RETENTION_SECS,
+    SPARKLINE_SAMPLES,
+};
+pub use reports::{format_duration, format_timestamp, html_escape, Outage, TargetReport};
+
+// API local, checagem avulsa e diagnóstico
+pub use api::{
+    api_base_url, dashboard_url, fetch_live_status, issue_dashboard_code, nagios_output, run_single_check, LiveStatus,
+    LiveTarget,
+};
+pub use diagnostics::{default_support_bundle_path, diagnose_target, render_diagnostics, write_support_bundle, DiagnosticSection};
+
+// Frontends: textos, notificações, logs, primeira execução e bandeja
+pub use i18n::{tr, trf};
+pub use logs::{event_log_path, init_logging};
+pub use notifiers::show_notification;
+pub use onboarding::{needs_onboarding, onboarding_suggestions, target_presets, Preset, Suggestion};
+pub use status::{outcome_icon, render_text_line, status_summary, statusbar_line, ColorStyle, StatusSummary, TextOutput, DEFAULT_TEXT_FORMAT};
+pub use tray::{
+    target_history_text, tray_badge_pixmap, tray_icon_name, tray_menu, tray_needs_attention, tray_pixmap, tray_title,
+    tray_tooltip, TrayAction, TrayMenuItem, TRAY_ICON_SIZE,
+};
+
+// Internos compartilhados entre os módulos
+use {
+    api::*, certs::*, checks::*, config::*, domains::*, history::*, i18n::*, logs::*, monitor::*, network::*,
+    notifiers::*, ping::*, reports::*, scripts::*, updates::*,
+};
+
+pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const APP_NAME: &str = "Cosmic Pinger";
+
+// Monitoring settings
+pub(crate) const MONITOR_INTERVAL_SECS: u64 = 180;
+pub const PING_ATTEMPTS: u8 = 3;
+pub(crate) const PING_RETRY_DELAY_MS: u64 = 500;
+pub(crate) const PING_PROBE_INTERVAL_MS: u64 = 200;
+/// Espera por cada resposta de ping, quando o destino não define `timeout_ms`.
+pub(crate) const PING_WAIT_MS: u64 = 1000;
+/// Portas tentadas quando o host não responde ao ICMP, antes de dá-lo como offline.
+pub(crate) const ICMP_FALLBACK_PORTS: [u16; 2] = [443, 80];
+pub(crate) const TCP_CONNECT_TIMEOUT_MS: u64 = 1500;
+pub(crate) const DNS_TIMEOUT_MS: u64 = 2000;
+pub(crate) const HTTP_TIMEOUT_SECS: u64 = 5;
+/// Por quanto tempo uma CRL baixada é reaproveitada entre os ciclos.
+pub(crate) const CRL_CACHE_SECS: u64 = 60 * 60;
+pub(crate) const FAIL_STREAK_THRESHOLD: u8 = 2;
+pub(crate) const NOTIFICATION_TIMEOUT_MS: i32 = 5000;
+pub(crate) const FLAP_WINDOW_SECS: u64 = 30 * 60;
+pub(crate) const FLAP_TRANSITION_THRESHOLD: usize = 4;
+pub(crate) const DEFAULT_LATENCY_ALERT_FACTOR: f64 = 3.0;
+pub(crate) const LATENCY_EWMA_ALPHA: f64 = 0.2;
+pub(crate) const LATENCY_MIN_SAMPLES: u32 = 5;
+pub(crate) const LATENCY_MIN_DELTA_MS: f64 = 20.0;
+pub(crate) const DEFAULT_MAX_NOTIFICATIONS_PER_HOUR: u32 = 6;
+pub(crate) const RATE_LIMIT_WINDOW_SECS: u64 = 3600;
+pub(crate) const RECENT_EVENTS_LIMIT: usize = 10;
+pub(crate) const DEFAULT_OUTAGE_CHECK_SECS: u64 = 20;
+pub(crate) const DEFAULT_MAX_PARALLEL_CHECKS: usize = 8;
+pub(crate) const DEFAULT_CHECK_BUDGET_SECS: u64 = 20;
+pub(crate) const DEFAULT_CHECK_JITTER_MS: u64 = 500;
+/// Sem `check_spread_secs`, os inícios se espalham por esta fração do intervalo,
+/// até `DEFAULT_CHECK_SPREAD_MAX_SECS`, para um "Checar agora" não demorar.
+pub(crate) const DEFAULT_CHECK_SPREAD_DIVISOR: u32 = 10;
+pub(crate) const DEFAULT_CHECK_SPREAD_MAX_SECS: u64 = 30;
+/// Amostras recentes guardadas em memória por destino (`recent_samples`).
+pub(crate) const DEFAULT_RECENT_SAMPLES: usize = 360;
+pub(crate) const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+pub(crate) const LOG_FILE_KEEP: usize = 3;
+pub(crate) const EVENT_LOG_MAX_BYTES: u64 = 512 * 1024;
+pub(crate) const EVENT_LOG_KEEP: usize = 5;
+pub(crate) const LIVE_STATUS_TIMEOUT_SECS: u64 = 3;
+#[cfg(feature = "scripting")]
+pub(crate) const SCRIPT_MAX_OPERATIONS: u64 = 1_000_000;
+pub(crate) const HOOK_TIMEOUT_SECS: u64 = 30;
+pub(crate) const SHUTDOWN_POLL_MS: u64 = 100;
+
+
+// Environment overrides
+pub(crate) const ENV_INTERVAL: &str = "COSMIC_PINGER_INTERVAL";
+pub(crate) const ENV_TIMEOUT: &str = "COSMIC_PINGER_TIMEOUT";
+pub(crate) const ENV_CONFIG_PATH: &str = "COSMIC_PINGER_CONFIG";
+pub(crate) const ENV_LOG_LEVEL: &str = "COSMIC_PINGER_LOG";
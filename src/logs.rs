@@ -0,0 +1,185 @@
+//! Log do programa e `events.log`.
+
+use super::*;
+
+/// Arquivo com rotação por tamanho: ao passar de `max_bytes`, `x.log` vira
+/// `x.log.1`, `x.log.1` vira `x.log.2` e assim por diante até `keep`.
+pub(crate) struct RotatingFile {
+    pub path: PathBuf,
+    pub max_bytes: u64,
+    pub keep: usize,
+    pub file: Option<fs::File>,
+    pub size: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, max_bytes: u64, keep: usize) -> Self {
+        let file = fs::OpenOptions::new().create(true).append(true).open(&path).ok();
+        let size = file.as_ref().and_then(|f| f.metadata().ok()).map(|m| m.len()).unwrap_or(0);
+        Self { path, max_bytes, keep, file, size }
+    }
+
+    pub fn rotated(&self, index: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    pub fn rotate(&mut self) {
+        self.file = None;
+        for index in (1..self.keep).rev() {
+            let _ = fs::rename(self.rotated(index), self.rotated(index + 1));
+        }
+        let _ = fs::rename(&self.path, self.rotated(1));
+        *self = Self::open(self.path.clone(), self.max_bytes, self.keep);
+    }
+
+    pub fn write_line(&mut self, line: &str) {
+        use std::io::Write;
+
+        if self.size + line.len() as u64 + 1 > self.max_bytes {
+            self.rotate();
+        }
+        if let Some(file) = self.file.as_mut() {
+            if writeln!(file, "{}", line).is_ok() {
+                self.size += line.len() as u64 + 1;
+            }
+        }
+    }
+
+    /// Garante que as linhas escritas chegaram ao disco (usado ao encerrar).
+    pub fn sync(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            let _ = file.sync_data();
+        }
+    }
+}
+
+/// Junta a mensagem e os campos estruturados de um evento do tracing.
+#[derive(Default)]
+pub(crate) struct LogLine {
+    pub message: String,
+    pub fields: String,
+}
+
+impl tracing::field::Visit for LogLine {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.fields.push_str(&format!(" {}={:?}", field.name(), value));
+        }
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields.push_str(&format!(" {}={}", field.name(), value));
+        }
+    }
+}
+
+/// Subscriber mínimo: sempre em stderr (stdout fica livre para `--statusbar` e
+/// `--text`), com prioridades `<N>` do sd-daemon quando a saída é o journald,
+/// e opcionalmente num arquivo com rotação.
+pub(crate) struct LogSubscriber {
+    pub max_level: tracing::Level,
+    pub journald: bool,
+    pub file: Option<Mutex<RotatingFile>>,
+}
+
+impl tracing::Subscriber for LogSubscriber {
+    fn enabled(&self, metadata: &tracing::Metadata<'_>) -> bool {
+        *metadata.level() <= self.max_level
+    }
+
+    fn max_level_hint(&self) -> Option<tracing::level_filters::LevelFilter> {
+        Some(tracing::level_filters::LevelFilter::from_level(self.max_level))
+    }
+
+    // Sem spans por enquanto: só eventos
+    fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        tracing::span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, event: &tracing::Event<'_>) {
+        use std::io::Write;
+
+        let mut line = LogLine::default();
+        event.record(&mut line);
+        let level = *event.metadata().level();
+        let text = format!("{}{}", line.message, line.fields);
+
+        let mut err = std::io::stderr().lock();
+        if self.journald {
+            let priority = match level {
+                tracing::Level::ERROR => 3,
+                tracing::Level::WARN => 4,
+                tracing::Level::INFO => 6,
+                _ => 7,
+            };
+            let _ = writeln!(err, "<{}>{}", priority, text);
+        } else {
+            let _ = writeln!(err, "{} {:>5} {}", Local::now().format("%H:%M:%S"), level, text);
+        }
+
+        if let Some(file) = &self.file {
+            let stamped = format!("{} {:>5} {}", Local::now().format("%Y-%m-%d %H:%M:%S"), level, text);
+            match file.lock() {
+                Ok(mut file) => file.write_line(&stamped),
+                Err(poisoned) => poisoned.into_inner().write_line(&stamped),
+            }
+        }
+    }
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+pub(crate) static EVENT_LOG: OnceLock<Mutex<RotatingFile>> = OnceLock::new();
+
+pub fn event_log_path() -> PathBuf {
+    get_data_dir().join("events.log")
+}
+
+/// Registra um evento legível em `events.log`: mudanças de estado, recargas de
+/// configuração e erros. Independe do nível de log e de `--log-file`.
+pub(crate) fn log_event(text: &str) {
+    let log = EVENT_LOG.get_or_init(|| Mutex::new(RotatingFile::open(event_log_path(), EVENT_LOG_MAX_BYTES, EVENT_LOG_KEEP)));
+    let line = format!("{}  {}", Local::now().format("%Y-%m-%d %H:%M:%S"), text);
+    match log.lock() {
+        Ok(mut log) => log.write_line(&line),
+        Err(poisoned) => poisoned.into_inner().write_line(&line),
+    }
+}
+
+/// Descarrega `events.log` no disco antes de o processo sair.
+pub(crate) fn flush_event_log() {
+    if let Some(log) = EVENT_LOG.get() {
+        match log.lock() {
+            Ok(mut log) => log.sync(),
+            Err(poisoned) => poisoned.into_inner().sync(),
+        }
+    }
+}
+
+pub fn init_logging() {
+    let settings = runtime();
+    let subscriber = LogSubscriber {
+        max_level: settings.log_level,
+        // systemd define JOURNAL_STREAM quando stdout/stderr vão direto para o journal
+        journald: std::env::var_os("JOURNAL_STREAM").is_some(),
+        file: settings
+            .log_file
+            .then(|| Mutex::new(RotatingFile::open(get_data_dir().join("cosmic_pinger.log"), LOG_FILE_MAX_BYTES, LOG_FILE_KEEP))),
+    };
+    if tracing::subscriber::set_global_default(subscriber).is_err() {
+        eprintln!("Logger já inicializado");
+    }
+}
@@ -0,0 +1,1251 @@
+//! Laço do monitor, estado publicado e comandos.
+
+use super::*;
+
+/// Saúde de um destino: degradado ainda conta como online para quedas,
+/// uptime e grupos, mas tem ícone e alertas próprios.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Health {
+    Up,
+    Degraded,
+    Down,
+}
+
+/// Resultado consolidado de um destino após aplicar a histerese.
+#[derive(Clone, Serialize)]
+pub struct TargetStatus {
+    pub host: String,
+    pub is_up: bool,
+    /// `Down` sempre que `is_up` é falso.
+    pub health: Health,
+    pub detail: String,
+    /// Alternou de estado `FLAP_TRANSITION_THRESHOLD` vezes dentro da janela de flapping.
+    pub flapping: bool,
+    /// Latência de referência no momento em que a amostra atual foi considerada anômala.
+    pub latency_anomaly: Option<f64>,
+    pub uptime: UptimeStats,
+    pub sla: Option<f64>,
+    /// Timestamp (Unix) em que o destino passou a ser considerado offline.
+    pub down_since: Option<i64>,
+    /// Checagens deste destino suspensas até este timestamp (Unix), pelo menu.
+    pub paused_until: Option<i64>,
+    /// Fora do agendamento cron: próxima checagem prevista (Unix).
+    pub next_check: Option<i64>,
+    /// Queda mais recente registrada no histórico (sobrevive a reinícios).
+    pub last_outage: Option<Outage>,
+    /// Últimas `SPARKLINE_SAMPLES` latências para o minigráfico do menu.
+    pub recent_latency: Vec<Option<f64>>,
+    /// ASN/dono e país do IP, com `geoip` configurado.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkInfo>,
+    /// Nome reverso (PTR) de destinos que são IPs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    /// Tempo de resolução do nome na última checagem, fora da latência.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dns_ms: Option<f64>,
+}
+
+impl TargetStatus {
+    /// Endereço com o nome reverso ao lado, quando houver: `192.168.0.1 · roteador.lan`.
+    pub fn display_host(&self) -> String {
+        match &self.hostname {
+            Some(name) => format!("{} · {}", self.host, name),
+            None => self.host.clone(),
+        }
+    }
+
+    /// Pausado ou fora do agendamento: mantém o último estado sem contar como queda.
+    pub fn is_idle(&self) -> bool {
+        self.paused_until.is_some() || self.next_check.is_some()
+    }
+
+    /// O uptime de 30 dias ficou abaixo do SLA configurado.
+    pub fn sla_breached(&self) -> bool {
+        matches!((self.sla, self.uptime.month), (Some(sla), Some(up)) if up < sla)
+    }
+}
+
+/// Uma checagem guardada no buffer em memória do destino.
+#[derive(Clone, Copy)]
+pub(crate) struct RecentSample {
+    pub ts: i64,
+    /// Resultado bruto da checagem.
+    pub success: bool,
+    /// Estado efetivo, depois da histerese.
+    pub up: bool,
+    pub latency_ms: Option<f64>,
+}
+
+/// Últimas amostras de um destino, com capacidade fixa: a mais antiga sai
+/// quando o buffer enche.
+#[derive(Clone, Default)]
+pub(crate) struct SampleRing {
+    pub capacity: usize,
+    pub samples: VecDeque<RecentSample>,
+}
+
+impl SampleRing {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(SPARKLINE_SAMPLES);
+        Self { capacity, samples: VecDeque::with_capacity(capacity) }
+    }
+
+    /// Ajusta a capacidade (mudança de `recent_samples`), descartando as mais antigas.
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity.max(SPARKLINE_SAMPLES);
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.shrink_to(self.capacity);
+    }
+
+    pub fn push(&mut self, sample: RecentSample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// Como `HistoryStore::recent_latencies`, a partir das amostras em memória.
+    pub fn latencies(&self, limit: usize) -> Vec<Option<f64>> {
+        let mut recent: Vec<Option<f64>> = self
+            .samples
+            .iter()
+            .rev()
+            .filter_map(|s| if s.success { s.latency_ms.map(Some) } else { Some(None) })
+            .take(limit)
+            .collect();
+        recent.reverse();
+        recent
+    }
+
+    /// Mudanças do estado efetivo registradas a partir de `since` (Unix).
+    pub fn transitions_since(&self, since: i64) -> usize {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .filter(|(prev, next)| next.ts >= since && prev.up != next.up)
+            .count()
+    }
+}
+
+/// Buffers iniciais a partir do histórico em disco, para o minigráfico não
+/// recomeçar vazio a cada reinício.
+pub(crate) fn seed_sample_rings(history: &HistoryStore, capacity: usize) -> HashMap<String, SampleRing> {
+    history
+        .samples
+        .iter()
+        .map(|(host, queue)| {
+            let mut ring = SampleRing::new(capacity);
+            let skip = queue.len().saturating_sub(ring.capacity);
+            for sample in queue.iter().skip(skip) {
+                ring.push(RecentSample { ts: sample.ts, success: sample.up, up: sample.up, latency_ms: sample.latency_ms });
+            }
+            (host.clone(), ring)
+        })
+        .collect()
+}
+
+/// Média móvel exponencial da latência de um destino.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct LatencyBaseline {
+    pub ewma_ms: f64,
+    pub samples: u32,
+}
+
+impl LatencyBaseline {
+    /// Incorpora a amostra e retorna a referência anterior se ela for anômala.
+    pub fn observe(&mut self, sample_ms: f64, factor: f64) -> Option<f64> {
+        let reference = self.ewma_ms;
+        let anomalous = factor > 0.0
+            && self.samples >= LATENCY_MIN_SAMPLES
+            && sample_ms > reference * factor
+            && sample_ms - reference >= LATENCY_MIN_DELTA_MS;
+
+        if self.samples == 0 {
+            self.ewma_ms = sample_ms;
+        } else {
+            self.ewma_ms += LATENCY_EWMA_ALPHA * (sample_ms - self.ewma_ms);
+        }
+        self.samples = self.samples.saturating_add(1);
+
+        anomalous.then_some(reference)
+    }
+}
+
+/// Mudança de estado efetiva, guardada para o submenu de eventos recentes e
+/// reconstruída do histórico pela janela de configuração.
+#[derive(Clone)]
+pub struct StateEvent {
+    pub ts: i64,
+    pub host: String,
+    pub is_up: bool,
+    /// Duração da queda encerrada por esta recuperação.
+    pub outage_secs: Option<i64>,
+}
+
+/// Eventos gerados em um ciclo que viram notificações após liberar o lock.
+pub(crate) enum MonitorNotice {
+    StateChanged { host: String, is_up: bool, latency_ms: Option<f64>, outage_secs: Option<i64> },
+    FlapStarted { host: String },
+    FlapEnded { host: String, is_up: bool },
+    LatencyAnomaly { host: String, latency_ms: f64, baseline_ms: f64 },
+    /// O destino seguiu online, mas entrou ou saiu do estado degradado.
+    DegradedChanged { host: String, degraded: bool, detail: String },
+    /// O registro do domínio do destino vence dentro do prazo configurado.
+    DomainExpiring { host: String, domain: String, expires: i64 },
+    /// O corpo (ou o trecho selecionado) mudou desde a última checagem.
+    ContentChanged { host: String },
+    /// Um grupo cruzou o limite `min_down`, em qualquer direção.
+    GroupChanged { name: String, is_down: bool, down: Vec<String>, total: usize },
+}
+
+#[derive(Clone)]
+pub struct PingerState {
+    pub results: Vec<TargetStatus>,
+    pub last_update_text: String,
+    pub update_counter: u64,
+    pub all_up: bool,
+    pub first_run: bool,
+    pub fail_streaks: HashMap<String, u8>,
+    pub config_error: Option<String>,
+    /// Checagens suspensas até este instante (via API).
+    pub paused_until: Option<chrono::DateTime<Local>>,
+    /// Alertas descartados pelo limite de taxa na janela atual, por destino.
+    pub suppressed_alerts: HashMap<String, u32>,
+    /// Destinos com queda reconhecida; limpo quando o destino volta.
+    pub acknowledged: HashSet<String>,
+    /// Destinos pausados individualmente pelo menu, até o instante indicado.
+    pub paused_targets: HashMap<String, chrono::DateTime<Local>>,
+    /// Últimas mudanças de estado, da mais antiga para a mais recente.
+    pub recent_events: VecDeque<StateEvent>,
+    /// Alertas silenciados em todos os canais; checagens e ícone seguem normais.
+    pub muted: Option<Mute>,
+    /// Grupos (`GroupRule::name`) com o limite de quedas atingido.
+    pub groups_down: HashSet<String>,
+    /// Release mais nova que a versão em execução (`update_check`).
+    pub update_available: Option<ReleaseInfo>,
+}
+
+/// Memória de trabalho do monitor que os leitores não usam. Fica fora do
+/// `PingerState` para cada publicação não copiar os buffers de amostras.
+#[derive(Default)]
+pub(crate) struct MonitorMemory {
+    /// Últimas `recent_samples` checagens de cada destino.
+    pub recent_samples: HashMap<String, SampleRing>,
+    pub latency_baselines: HashMap<String, LatencyBaseline>,
+    /// Alertas entregues na última hora, por destino (limite de taxa).
+    pub sent_alerts: HashMap<String, VecDeque<Instant>>,
+}
+
+/// Silenciamento global dos alertas pelo menu da bandeja.
+#[derive(Clone, Copy)]
+pub enum Mute {
+    Until(chrono::DateTime<Local>),
+    UntilResumed,
+}
+
+/// Por que um destino não foi checado neste ciclo.
+pub(crate) enum SkipReason {
+    /// Pausado pelo menu até este timestamp.
+    Paused(i64),
+    /// Agendamento cron; próxima checagem neste timestamp.
+    OffSchedule(i64),
+    /// Estável, aguardando o próximo ciclo completo (rechecagem de quedas).
+    NotDue,
+}
+
+/// Pedidos externos (API, menu) ao laço de monitoramento.
+pub enum MonitorCommand {
+    CheckNow,
+    /// Checa só este destino, sem esperar nem antecipar o ciclo dos demais.
+    CheckTarget(String),
+    Pause(Duration),
+    Resume,
+    /// Silencia os alertas da queda atual do destino até ele voltar.
+    Acknowledge(String),
+    /// Suspende as checagens de um destino até o instante indicado.
+    PauseTarget(String, chrono::DateTime<Local>),
+    ResumeTarget(String),
+    /// Liga (`Some`) ou desliga o silenciamento global dos alertas.
+    Mute(Option<Mute>),
+    /// Encerra o monitor ao fim da espera atual (sinal ou item "Sair").
+    Shutdown,
+}
+
+/// Por que `wait_for_next_cycle` retornou.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Wake {
+    Timeout,
+    /// Checagem imediata pedida (ela ignora a pausa uma única vez).
+    CheckNow,
+    /// Checagem imediata de um único destino.
+    CheckTarget(String),
+    Shutdown,
+}
+
+/// Ligada no primeiro pedido de encerramento. As checagens ainda não iniciadas
+/// e as esperas do espalhamento olham para ela, e o monitor sai sem esperar o
+/// fim do ciclo.
+pub(crate) static SHUTTING_DOWN: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+pub(crate) fn shutdown_requested() -> bool {
+    SHUTTING_DOWN.load(std::sync::atomic::Ordering::Acquire)
+}
+
+/// Marca o encerramento e acorda o monitor (sinal ou item "Sair").
+pub fn request_shutdown(commands: &mpsc::Sender<MonitorCommand>) -> Result<(), mpsc::SendError<MonitorCommand>> {
+    SHUTTING_DOWN.store(true, std::sync::atomic::Ordering::Release);
+    commands.send(MonitorCommand::Shutdown)
+}
+
+/// Dorme até `duration`, acordando antes se o encerramento for pedido.
+pub(crate) fn sleep_unless_shutdown(duration: Duration) {
+    let deadline = Instant::now() + duration;
+    while !shutdown_requested() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            break;
+        }
+        thread::sleep(remaining.min(Duration::from_millis(SHUTDOWN_POLL_MS)));
+    }
+}
+
+/// Converte SIGTERM/SIGINT em `MonitorCommand::Shutdown` para o monitor gravar
+/// o que falta e a bandeja sair do StatusNotifier. Um segundo sinal encerra na
+/// hora, com o código 128 + número do sinal, como o shell.
+pub(crate) fn spawn_signal_listener(commands: mpsc::Sender<MonitorCommand>) {
+    thread::spawn(move || {
+        let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                warn!("[SINAL] Falha ao criar o runtime de sinais: {}", e);
+                return;
+            }
+        };
+        runtime.block_on(async move {
+            #[cfg(unix)]
+            let mut terminate = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+                Ok(signal) => signal,
+                Err(e) => {
+                    warn!("[SINAL] Falha ao escutar SIGTERM: {}", e);
+                    return;
+                }
+            };
+            let mut received = false;
+            loop {
+                #[cfg(unix)]
+                let (name, signo) = tokio::select! {
+                    _ = terminate.recv() => ("SIGTERM", 15),
+                    _ = tokio::signal::ctrl_c() => ("SIGINT", 2),
+                };
+                #[cfg(not(unix))]
+                let (name, signo) = match tokio::signal::ctrl_c().await {
+                    Ok(()) => ("Ctrl+C", 2),
+                    Err(_) => return,
+                };
+                if received {
+                    warn!("[SINAL] {} de novo; saindo sem esperar", name);
+                    process::exit(128 + signo);
+                }
+                received = true;
+                info!("[SINAL] {} recebido; encerrando", name);
+                if request_shutdown(&commands).is_err() {
+                    process::exit(0);
+                }
+            }
+        });
+    });
+}
+
+/// O monitor é o único dono do `PingerState` e publica cópias imutáveis a cada
+/// mudança; bandeja, API e barras de status leem a mais recente sem esperar o
+/// ciclo de checagens. Alterações chegam ao monitor como `MonitorCommand`.
+pub type StateSender = tokio::sync::watch::Sender<Arc<PingerState>>;
+pub type StateReceiver = tokio::sync::watch::Receiver<Arc<PingerState>>;
+
+pub fn state_channel() -> (StateSender, StateReceiver) {
+    tokio::sync::watch::channel(Arc::new(PingerState::new()))
+}
+
+/// Última cópia publicada; o `Arc` é clonado para não segurar o canal.
+pub fn current_state(state: &StateReceiver) -> Arc<PingerState> {
+    state.borrow().clone()
+}
+
+impl Default for PingerState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PingerState {
+    /// O silenciamento em vigor; um `Until` vencido conta como inativo.
+    pub fn active_mute(&self) -> Option<Mute> {
+        self.muted.filter(|mute| match mute {
+            Mute::Until(until) => *until > Local::now(),
+            Mute::UntilResumed => true,
+        })
+    }
+
+    pub fn new() -> Self {
+        Self {
+            results: vec![],
+            last_update_text: tr("status.waiting").to_string(),
+            update_counter: 0,
+            all_up: true,
+            first_run: true,
+            fail_streaks: HashMap::new(),
+            config_error: None,
+            paused_until: None,
+            suppressed_alerts: HashMap::new(),
+            acknowledged: HashSet::new(),
+            paused_targets: HashMap::new(),
+            recent_events: VecDeque::new(),
+            muted: None,
+            groups_down: HashSet::new(),
+            update_available: None,
+        }
+    }
+
+    /// Ciclo concluído sem nenhum destino (lista vazia ou só endereços inválidos).
+    pub fn no_targets(&self) -> bool {
+        !self.first_run && self.results.is_empty()
+    }
+
+    /// Destinos atualmente considerados offline.
+    pub fn down_count(&self) -> usize {
+        self.results.iter().filter(|r| !r.is_up && !r.is_idle()).count()
+    }
+
+    pub fn degraded_count(&self) -> usize {
+        self.results.iter().filter(|r| r.health == Health::Degraded && !r.is_idle()).count()
+    }
+}
+
+/// Laço principal: checa os destinos, publica o estado em `state_tx` e atende
+/// os `MonitorCommand` até o encerramento. `on_change` é chamado sempre que o
+/// estado visível muda (fim de ciclo, pausa, reconhecimento).
+pub fn run_monitor(
+    state_tx: StateSender,
+    command_tx: mpsc::Sender<MonitorCommand>,
+    command_rx: mpsc::Receiver<MonitorCommand>,
+    on_change: &dyn Fn(),
+) {
+    let startup_config = load_config().ok();
+    let ring_capacity = startup_config
+        .as_ref()
+        .map(AppConfig::recent_sample_capacity)
+        .unwrap_or_else(|| AppConfig::default().recent_sample_capacity());
+    let http_timeout = startup_config
+        .as_ref()
+        .map(AppConfig::http_timeout)
+        .unwrap_or_else(|| AppConfig::default().http_timeout());
+    let http_client = build_http_client(http_timeout);
+    let mut monitor_interval = startup_config
+        .as_ref()
+        .map(AppConfig::monitor_interval)
+        .unwrap_or_else(|| AppConfig::default().monitor_interval());
+
+    if let Some(api) = startup_config.and_then(|cfg| cfg.api) {
+        start_api_server(api, state_tx.subscribe(), command_tx.clone());
+    }
+    spawn_signal_listener(command_tx.clone());
+
+    let mut state = PingerState::new();
+    // Publica só o estado visível; buffers de amostras e linhas de base ficam em `MonitorMemory`
+    let publish = |state: &PingerState| {
+        state_tx.send_replace(Arc::new(state.clone()));
+        write_status_file(state);
+        on_change();
+    };
+    let publish: &dyn Fn(&PingerState) = &publish;
+    let mut last_good_config: Option<AppConfig> = None;
+    let mut history = HistoryStore::open();
+    let mut memory = MonitorMemory {
+        recent_samples: seed_sample_rings(&history, ring_capacity),
+        ..Default::default()
+    };
+    let mut wake = Wake::Timeout;
+    let mut last_full_cycle: Option<Instant> = None;
+    let mut scheduler = Scheduler::default();
+    let mut content_hashes = load_content_hashes();
+    let mut domain_watch = DomainWatch::load();
+    let mut network_watch = NetworkWatch::load();
+    let mut reverse_dns = ReverseDnsWatch::load();
+    let mut update_watch = UpdateWatch::load();
+    
+    loop {
+        let cycle_start = Instant::now();
+
+        if let Some(until) = state.paused_until {
+            let remaining = (until - Local::now()).to_std().unwrap_or_default();
+            if remaining.is_zero() {
+                state.paused_until = None;
+                publish(&state);
+            } else if wake == Wake::Timeout {
+                info!("[CICLO] Monitoramento pausado até {}", until.format("%H:%M:%S"));
+                wake = match wait_for_next_cycle(&command_rx, &mut state, publish, remaining.min(monitor_interval)) {
+                    Wake::Shutdown => break,
+                    next => next,
+                };
+                continue;
+            }
+        }
+
+        let config = match load_config() {
+            Ok(cfg) => {
+                state.config_error = None;
+                if let Some(previous) = &last_good_config {
+                    if serde_json::to_value(previous).ok() != serde_json::to_value(&cfg).ok() {
+                        info!("[CONFIG] Configuração recarregada");
+                        log_event(&format!("Configuração recarregada ({} destinos)", cfg.targets.len()));
+                    }
+                }
+                last_good_config = Some(cfg.clone());
+                cfg
+            }
+            Err(e) => {
+                warn!("[CONFIG] {}", e);
+                let is_new = state.config_error.replace(e.clone()).as_ref() != Some(&e);
+                if is_new {
+                    log_event(&format!("Erro: configuração inválida: {}", e));
+                    send_config_error_notification(&e);
+                }
+                // Continua monitorando com a última lista válida em vez de voltar ao padrão
+                last_good_config
+                    .clone()
+                    .or_else(load_config_backup)
+                    .unwrap_or_default()
+            }
+        };
+        monitor_interval = config.monitor_interval();
+        // Entre os ciclos completos, só os destinos com falha são rechecados
+        let full_cycle = wake == Wake::CheckNow
+            || last_full_cycle.is_none_or(|started| started.elapsed() >= monitor_interval);
+        if full_cycle {
+            last_full_cycle = Some(cycle_start);
+        }
+        let outage_interval = Duration::from_secs(config.outage_check_interval);
+        let check_parallelism = config.max_parallel_checks.max(1);
+        let check_limits = config.check_limits();
+        let check_pacing = CheckPacing {
+            spread: config.check_spread(),
+            jitter: Duration::from_millis(config.check_jitter_ms),
+        };
+        let (known_hosts, failing_hosts): (HashSet<String>, HashSet<String>) = {
+            let s = &state;
+            let known = s.results.iter().map(|r| r.host.clone()).collect();
+            let failing = s
+                .results
+                .iter()
+                .filter(|r| !r.is_up || s.fail_streaks.get(&r.host).copied().unwrap_or(0) > 0)
+                .map(|r| r.host.clone())
+                .collect();
+            (known, failing)
+        };
+
+        let latency_factor = config.latency_alert_factor;
+        let ring_capacity = config.recent_sample_capacity();
+        let report_config = config.report.clone();
+        let influx_config = config.influx.clone();
+        let status_page_config = config.status_page.clone();
+        let metrics_config = config.metrics.clone();
+        let zabbix_config = config.zabbix.clone();
+        let notifiers = NotifierRegistry::from_config(&config, Some(command_tx.clone()));
+        let group_notifications = config.group_notifications;
+        let default_rate_limit = config.max_notifications_per_hour;
+        let rate_limits: HashMap<String, u32> = config
+            .targets
+            .iter()
+            .filter_map(|t| t.max_notifications_per_hour.map(|limit| (t.address.trim().to_string(), limit)))
+            .collect();
+        let group_rules = config.groups.clone();
+        let tags_by_host: HashMap<String, Vec<String>> = config
+            .targets
+            .iter()
+            .map(|t| (t.address.trim().to_string(), t.tags.clone()))
+            .collect();
+        let in_group = |host: &str, rule: &GroupRule| tags_by_host.get(host).is_some_and(|tags| tags.contains(&rule.tag));
+        let global_hook = config.hook.clone().filter(|h| !h.trim().is_empty());
+        let hooks: HashMap<String, String> = config
+            .targets
+            .iter()
+            .filter_map(|t| t.hook.clone().filter(|h| !h.trim().is_empty()).map(|h| (t.address.trim().to_string(), h)))
+            .collect();
+        let client_ref = http_client.as_ref();
+        let expiring_domains = domain_watch.poll(&config.targets, client_ref);
+        network_watch.poll(config.geoip.as_ref(), &config.targets, client_ref);
+        let geoip_enabled = config.geoip.is_some();
+        reverse_dns.poll(&config.targets);
+        let update_available = update_watch.poll(config.update_check.as_ref(), client_ref);
+        if let Some(release) = &update_available {
+            if state.update_available.as_ref().is_none_or(|known| known.version != release.version) {
+                info!("[ATUALIZAÇÃO] Versão {} disponível: {}", release.version, release.url);
+            }
+        }
+        state.update_available = update_available;
+        let targets = config.targets;
+        let configured_hosts: HashSet<String> = targets.iter().filter_map(|t| normalize_target(&t.address)).collect();
+
+        let mut raw_results = Vec::new();
+        let mut sla_by_host: HashMap<String, f64> = HashMap::new();
+        let mut kuma_by_host: HashMap<String, String> = HashMap::new();
+        let mut samples = Vec::new();
+        let mut content_changes: Vec<String> = Vec::new();
+        let mut skipped_hosts: Vec<(String, SkipReason)> = Vec::new();
+        let mut jobs: Vec<CheckJob> = Vec::new();
+        let mut threshold_by_host: HashMap<String, u8> = HashMap::new();
+        let default_threshold = config.fail_threshold.max(1);
+        let mut scheduled_hosts: HashSet<String> = HashSet::new();
+        let mut next_scheduled: Option<chrono::DateTime<Local>> = None;
+        let sample_ts = Local::now().timestamp();
+        let paused_targets = {
+            let now = Local::now();
+            state.paused_targets.retain(|_, until| *until > now);
+            state.paused_targets.clone()
+        };
+
+        // Fora do ciclo completo: só quedas (rechecagem) ou o destino pedido pelo menu
+        let due = |host: &String| match &wake {
+            Wake::CheckTarget(only) => only == host || !known_hosts.contains(host),
+            _ => !known_hosts.contains(host) || failing_hosts.contains(host),
+        };
+
+        // Sem destinos a lista fica vazia: a bandeja mostra o estado neutro
+        if targets.is_empty() {
+            debug!("[MONITOR] Nenhum destino configurado");
+        } else {
+            for target in targets {
+                if let Some(cleaned) = normalize_target(&target.address) {
+                    if let Some(until) = paused_targets.get(&cleaned) {
+                        skipped_hosts.push((cleaned, SkipReason::Paused(until.timestamp())));
+                        continue;
+                    }
+                    if let Some(expr) = target.schedule.as_deref().filter(|e| !e.trim().is_empty()) {
+                        scheduled_hosts.insert(cleaned.clone());
+                        if let Some(next) = scheduler.check(&cleaned, expr, Local::now()) {
+                            next_scheduled = Some(next_scheduled.map_or(next, |n| n.min(next)));
+                            skipped_hosts.push((cleaned, SkipReason::OffSchedule(next.timestamp())));
+                            continue;
+                        }
+                    } else if !full_cycle && !due(&cleaned) {
+                        skipped_hosts.push((cleaned, SkipReason::NotDue));
+                        continue;
+                    }
+                    jobs.push(CheckJob::from_target(cleaned.clone(), &target));
+                    threshold_by_host.insert(cleaned.clone(), target.fail_threshold(default_threshold));
+                    if let Some(sla) = target.sla {
+                        sla_by_host.insert(cleaned.clone(), sla);
+                    }
+                    if let Some(url) = target.kuma_push_url.filter(|u| !u.trim().is_empty()) {
+                        kuma_by_host.insert(cleaned.clone(), url);
+                    }
+                }
+            }
+
+            let outcomes = run_checks(&jobs, client_ref, check_parallelism, check_limits, &check_pacing);
+            // Resultados parciais virariam quedas falsas no histórico
+            if shutdown_requested() {
+                break;
+            }
+            let mut content_dirty = false;
+            for (job, outcome) in jobs.into_iter().zip(outcomes) {
+                // A primeira impressão digital só vira referência
+                if let Some(hash) = &outcome.fingerprint {
+                    match content_hashes.insert(job.host.clone(), hash.clone()) {
+                        Some(previous) if &previous == hash => {}
+                        Some(_) => {
+                            content_changes.push(job.host.clone());
+                            content_dirty = true;
+                        }
+                        None => content_dirty = true,
+                    }
+                }
+                samples.push(HistorySample {
+                    ts: sample_ts,
+                    host: job.host.clone(),
+                    up: outcome.success,
+                    latency_ms: outcome.latency_ms,
+                });
+                raw_results.push((job.host, outcome));
+            }
+            if content_dirty {
+                save_content_hashes(&content_hashes);
+            }
+        }
+
+        scheduler.forget_missing(&scheduled_hosts);
+        history.record(&samples);
+        if let Some(influx) = &influx_config {
+            write_influx(influx, client_ref, &samples);
+        }
+        if let Some(metrics) = &metrics_config {
+            emit_metrics(metrics, client_ref, &samples);
+        }
+        if let Some(zabbix) = &zabbix_config {
+            emit_zabbix(zabbix, &samples);
+        }
+        maybe_send_report(&report_config, &history, sample_ts);
+
+        let mut notifications = Vec::new();
+        for (host, domain, expires) in expiring_domains {
+            log_event(&format!("Domínio {} vence em {}", domain, format_timestamp(expires, "%d/%m/%Y")));
+            notifications.push(MonitorNotice::DomainExpiring { host, domain, expires });
+        }
+        for host in content_changes {
+            log_event(&format!("Conteúdo de {} mudou", host));
+            notifications.push(MonitorNotice::ContentChanged { host });
+        }
+        let mut hook_events: Vec<HookEvent> = Vec::new();
+        let mut derived_all_up = true;
+
+        {
+            let s = &mut state;
+            let mut fail_map = s.fail_streaks.clone();
+            let rings = &mut memory.recent_samples;
+            // Destinos removidos da configuração não guardam mais amostras
+            rings.retain(|host, _| configured_hosts.contains(host));
+            let baselines = &mut memory.latency_baselines;
+            let previous_results = s.results.clone();
+            let mut final_results = Vec::with_capacity(raw_results.len());
+            let flap_since = sample_ts - FLAP_WINDOW_SECS as i64;
+
+            for (host, outcome) in raw_results {
+                let CheckOutcome { success, message: msg, latency_ms, degraded, dns_ms, .. } = outcome;
+                let threshold = threshold_by_host.get(&host).copied().unwrap_or(default_threshold);
+                let entry = fail_map.entry(host.clone()).or_insert(0);
+                let (effective_success, display_msg) = if success {
+                    *entry = 0;
+                    (true, msg)
+                } else {
+                    *entry = entry.saturating_add(1);
+                    if *entry >= threshold {
+                        (false, msg)
+                    } else {
+                        let label = trf(
+                            "check.fail_count",
+                            &[("message", &msg), ("count", entry), ("threshold", &threshold)],
+                        );
+                        (true, label)
+                    }
+                };
+
+                if !effective_success {
+                    derived_all_up = false;
+                }
+
+                let previous = previous_results.iter().find(|prev| prev.host == host);
+                let was_flapping = previous.map(|prev| prev.flapping).unwrap_or(false);
+                let state_changed = !s.first_run
+                    && previous.map(|prev| prev.is_up != effective_success).unwrap_or(false);
+
+                let ring = rings.entry(host.clone()).or_insert_with(|| SampleRing::new(ring_capacity));
+                ring.resize(ring_capacity);
+                ring.push(RecentSample { ts: sample_ts, success, up: effective_success, latency_ms });
+                let changes = ring.transitions_since(flap_since);
+                // Entra em flapping ao atingir o limite; só sai quando a janela esvazia
+                let flapping = changes >= FLAP_TRANSITION_THRESHOLD || (was_flapping && changes > 0);
+
+                if state_changed {
+                    info!("[NOTIF] Estado mudou para {}: {} -> {}", 
+                        host, 
+                        if effective_success {"DOWN"} else {"UP"},
+                        if effective_success {"UP"} else {"DOWN"}
+                    );
+                }
+
+                let outage_secs = previous
+                    .and_then(|prev| prev.down_since)
+                    .filter(|_| effective_success)
+                    .map(|since| sample_ts - since);
+                if state_changed {
+                    let mut event = format!("{} ficou {}", host, if effective_success {"ONLINE"} else {"OFFLINE"});
+                    if let Some(secs) = outage_secs {
+                        event.push_str(&format!(" após {} fora", format_duration(secs)));
+                    } else if !effective_success {
+                        event.push_str(&format!(" ({})", display_msg));
+                    }
+                    log_event(&event);
+                    hook_events.push(HookEvent {
+                        host: host.clone(),
+                        is_up: effective_success,
+                        detail: display_msg.clone(),
+                        latency_ms,
+                        outage_secs,
+                        ts: sample_ts,
+                    });
+                    s.recent_events.push_back(StateEvent {
+                        ts: sample_ts,
+                        host: host.clone(),
+                        is_up: effective_success,
+                        outage_secs,
+                    });
+                    while s.recent_events.len() > RECENT_EVENTS_LIMIT {
+                        s.recent_events.pop_front();
+                    }
+                }
+
+                // Durante o flapping as transições individuais são suprimidas
+                match (was_flapping, flapping) {
+                    (false, true) => {
+                        log_event(&format!("{} começou a oscilar", host));
+                        notifications.push(MonitorNotice::FlapStarted { host: host.clone() })
+                    }
+                    (true, false) => notifications.push(MonitorNotice::FlapEnded {
+                        host: host.clone(),
+                        is_up: effective_success,
+                    }),
+                    // Membros de grupos só alertam pelo grupo
+                    (false, false) if state_changed && !group_rules.iter().any(|rule| in_group(&host, rule)) => {
+                        notifications.push(MonitorNotice::StateChanged {
+                            host: host.clone(),
+                            is_up: effective_success,
+                            latency_ms,
+                            outage_secs,
+                        })
+                    }
+                    _ => {}
+                }
+
+                // Só a amostra atual decide: degradado não passa pela histerese de falhas
+                let health = if !effective_success {
+                    Health::Down
+                } else if success && degraded {
+                    Health::Degraded
+                } else {
+                    Health::Up
+                };
+                let was_degraded = previous.is_some_and(|prev| prev.health == Health::Degraded);
+                let now_degraded = health == Health::Degraded;
+                // Quedas e recuperações já têm alerta próprio; aqui só online -> online
+                let stayed_up = effective_success && previous.is_some_and(|prev| prev.is_up);
+                if !s.first_run && stayed_up && !flapping && was_degraded != now_degraded {
+                    log_event(&format!("{} {}", host, if now_degraded { "degradado" } else { "normalizado" }));
+                    notifications.push(MonitorNotice::DegradedChanged {
+                        host: host.clone(),
+                        degraded: now_degraded,
+                        detail: display_msg.clone(),
+                    });
+                }
+
+                let latency_anomaly = latency_ms.and_then(|sample| {
+                    baselines.entry(host.clone()).or_default().observe(sample, latency_factor)
+                });
+                let was_anomalous = previous.map(|prev| prev.latency_anomaly.is_some()).unwrap_or(false);
+                if let (Some(baseline_ms), Some(sample), false) = (latency_anomaly, latency_ms, was_anomalous) {
+                    info!("[LATÊNCIA] {} em {:.1} ms (referência {:.1} ms)", host, sample, baseline_ms);
+                    notifications.push(MonitorNotice::LatencyAnomaly {
+                        host: host.clone(),
+                        latency_ms: sample,
+                        baseline_ms,
+                    });
+                }
+
+                let uptime = history.uptime_stats(&host, sample_ts);
+                let sla = sla_by_host.get(&host).copied();
+                let down_since = if effective_success {
+                    None
+                } else {
+                    previous.and_then(|prev| prev.down_since).or(Some(sample_ts))
+                };
+
+                let last_outage = history.last_outage(&host, sample_ts);
+                let recent_latency = ring.latencies(SPARKLINE_SAMPLES);
+                let network = network_watch.records.get(&host).filter(|_| geoip_enabled).cloned();
+                let hostname = reverse_dns.hostname(&host);
+                final_results.push(TargetStatus {
+                    host,
+                    is_up: effective_success,
+                    health,
+                    detail: display_msg,
+                    flapping,
+                    latency_anomaly,
+                    uptime,
+                    sla,
+                    down_since,
+                    paused_until: None,
+                    next_check: None,
+                    last_outage,
+                    recent_latency,
+                    network,
+                    hostname,
+                    dns_ms,
+                });
+            }
+
+            // Destinos pausados ou fora da rechecagem mantêm o último estado conhecido
+            for (host, skip) in skipped_hosts {
+                let mut status = previous_results
+                    .iter()
+                    .find(|prev| prev.host == host)
+                    .cloned()
+                    .unwrap_or_else(|| TargetStatus {
+                        host: host.clone(),
+                        is_up: true,
+                        health: Health::Up,
+                        detail: "-".to_string(),
+                        flapping: false,
+                        latency_anomaly: None,
+                        uptime: history.uptime_stats(&host, sample_ts),
+                        sla: sla_by_host.get(&host).copied(),
+                        down_since: None,
+                        paused_until: None,
+                        next_check: None,
+                        last_outage: history.last_outage(&host, sample_ts),
+                        recent_latency: history.recent_latencies(&host, SPARKLINE_SAMPLES),
+                        network: network_watch.records.get(&host).filter(|_| geoip_enabled).cloned(),
+                        hostname: reverse_dns.hostname(&host),
+                        dns_ms: None,
+                    });
+                status.paused_until = None;
+                status.next_check = None;
+                match skip {
+                    SkipReason::Paused(until) => status.paused_until = Some(until),
+                    SkipReason::OffSchedule(next) => status.next_check = Some(next),
+                    SkipReason::NotDue => {}
+                }
+                if !status.is_up && !status.is_idle() {
+                    derived_all_up = false;
+                }
+                final_results.push(status);
+            }
+
+            for rule in &group_rules {
+                let members: Vec<&TargetStatus> = final_results.iter().filter(|r| in_group(&r.host, rule)).collect();
+                let down: Vec<String> = members
+                    .iter()
+                    .filter(|r| !r.is_up && !r.is_idle())
+                    .map(|r| r.host.clone())
+                    .collect();
+                let is_down = !members.is_empty() && down.len() >= rule.min_down.max(1);
+                if is_down == s.groups_down.contains(&rule.name) {
+                    continue;
+                }
+                if is_down {
+                    s.groups_down.insert(rule.name.clone());
+                } else {
+                    s.groups_down.remove(&rule.name);
+                }
+                if !s.first_run {
+                    log_event(&format!(
+                        "Grupo {} {} ({} de {} offline)",
+                        rule.name,
+                        if is_down { "atingiu o limite de quedas" } else { "normalizado" },
+                        down.len(),
+                        members.len()
+                    ));
+                    notifications.push(MonitorNotice::GroupChanged {
+                        name: rule.name.clone(),
+                        is_down,
+                        down,
+                        total: members.len(),
+                    });
+                }
+            }
+            s.groups_down.retain(|name| group_rules.iter().any(|rule| &rule.name == name));
+
+            let valid_hosts: HashSet<String> = final_results.iter().map(|r| r.host.clone()).collect();
+            fail_map.retain(|host, _| valid_hosts.contains(host));
+            rings.retain(|host, _| valid_hosts.contains(host));
+            baselines.retain(|host, _| valid_hosts.contains(host));
+
+            let recovered: Vec<String> = s
+                .acknowledged
+                .iter()
+                .filter(|host| final_results.iter().all(|r| &r.host != *host || r.is_up))
+                .cloned()
+                .collect();
+            for host in recovered {
+                s.acknowledged.remove(&host);
+            }
+
+            s.results = final_results;
+            s.fail_streaks = fail_map;
+            s.update_counter += 1;
+            let now = Local::now();
+            s.last_update_text = now.format("%H:%M:%S").to_string();
+            s.all_up = derived_all_up;
+            s.first_run = false;
+            
+            info!("[CICLO #{}] Checagem concluída às {}. All up: {}", 
+                s.update_counter, 
+                s.last_update_text,
+                s.all_up
+            );
+        }
+
+        if let (Some(client), false) = (client_ref, kuma_by_host.is_empty()) {
+            for r in &state.results {
+                if let Some(url) = kuma_by_host.get(&r.host) {
+                    let latency = samples.iter().find(|s| s.host == r.host).and_then(|s| s.latency_ms);
+                    push_uptime_kuma(client, url, r, latency);
+                }
+            }
+        }
+
+        if let Some(page) = &status_page_config {
+            write_status_page(page, &state.results, &history, sample_ts);
+        }
+
+        publish(&state);
+
+        let alerts: Vec<Alert> = notifications
+            .into_iter()
+            .map(|notice| match notice {
+                MonitorNotice::StateChanged { host, is_up, latency_ms, outage_secs } => {
+                    Alert::state_change(host, is_up, latency_ms, outage_secs)
+                }
+                MonitorNotice::FlapStarted { host } => Alert::flap_started(host),
+                MonitorNotice::FlapEnded { host, is_up } => Alert::flap_ended(host, is_up),
+                MonitorNotice::LatencyAnomaly { host, latency_ms, baseline_ms } => {
+                    Alert::latency_anomaly(host, latency_ms, baseline_ms)
+                }
+                MonitorNotice::GroupChanged { name, is_down, down, total } => Alert::group_change(name, is_down, &down, total),
+                MonitorNotice::DegradedChanged { host, degraded, detail } => Alert::degraded_change(host, degraded, &detail),
+                MonitorNotice::ContentChanged { host } => Alert::content_changed(host),
+                MonitorNotice::DomainExpiring { host, domain, expires } => {
+                    Alert::domain_expiring(host, &domain, expires, Local::now().timestamp())
+                }
+            })
+            .collect();
+        let alerts = {
+            let s = &mut state;
+            if s.muted.is_some() && s.active_mute().is_none() {
+                s.muted = None;
+                log_event("Notificações reativadas");
+            }
+            if s.muted.is_some() {
+                if !alerts.is_empty() {
+                    info!("[NOTIF] Notificações silenciadas; {} alerta(s) descartado(s)", alerts.len());
+                }
+                Vec::new()
+            } else {
+                // Quedas reconhecidas só voltam a notificar na recuperação
+                let alerts: Vec<Alert> = alerts
+                    .into_iter()
+                    .filter(|a| a.transition == Some(true) || !s.acknowledged.contains(&a.host))
+                    .collect();
+                let alerts = notifiers.deliverable(&alerts);
+                apply_rate_limit(&mut memory.sent_alerts, &mut s.suppressed_alerts, alerts, |host| {
+                    rate_limits.get(host).copied().unwrap_or(default_rate_limit)
+                })
+            }
+        };
+        notifiers.dispatch(client_ref, &alerts, group_notifications);
+        for event in &hook_events {
+            if let Some(command) = hooks.get(&event.host).or(global_hook.as_ref()) {
+                run_hook(command, event);
+            }
+        }
+
+        let elapsed = cycle_start.elapsed();
+        let until_full = last_full_cycle
+            .map(|started| monitor_interval.saturating_sub(started.elapsed()))
+            .unwrap_or_default();
+        let has_failures = {
+            let s = &state;
+            s.results
+                .iter()
+                .any(|r| !r.is_idle() && (!r.is_up || s.fail_streaks.get(&r.host).copied().unwrap_or(0) > 0))
+        };
+        let mut sleep_for = if has_failures && !outage_interval.is_zero() {
+            until_full.min(outage_interval.saturating_sub(elapsed))
+        } else {
+            until_full
+        };
+        // Acorda no próximo horário cron em vez de esperar o ciclo inteiro
+        if let Some(next) = next_scheduled {
+            sleep_for = sleep_for.min((next - Local::now()).to_std().unwrap_or_default());
+        }
+        debug!("[CICLO] Tempo de execução: {:?}. Dormindo por {:?}", elapsed, sleep_for);
+        wake = match wait_for_next_cycle(&command_rx, &mut state, publish, sleep_for) {
+            Wake::Shutdown => break,
+            next => next,
+        };
+    }
+
+    // O histórico já é gravado a cada ciclo; falta o log de eventos e o status final
+    info!("[CICLO] Monitor encerrado");
+    log_event("Monitor encerrado");
+    flush_event_log();
+    write_status_file(&state);
+}
+
+/// Descarta alertas de destinos que já atingiram o limite por hora. As transições
+/// continuam no histórico e no menu; apenas a notificação é suprimida.
+pub(crate) fn apply_rate_limit(
+    sent_alerts: &mut HashMap<String, VecDeque<Instant>>,
+    suppressed: &mut HashMap<String, u32>,
+    alerts: Vec<Alert>,
+    limit_for: impl Fn(&str) -> u32,
+) -> Vec<Alert> {
+    let now = Instant::now();
+    let window = Duration::from_secs(RATE_LIMIT_WINDOW_SECS);
+
+    for (host, sent) in sent_alerts.iter_mut() {
+        while sent.front().map(|t| now.duration_since(*t) > window).unwrap_or(false) {
+            sent.pop_front();
+        }
+        if sent.is_empty() {
+            suppressed.remove(host);
+        }
+    }
+    sent_alerts.retain(|_, sent| !sent.is_empty());
+
+    alerts
+        .into_iter()
+        .filter(|alert| {
+            let limit = limit_for(&alert.host);
+            let sent = sent_alerts.entry(alert.host.clone()).or_default();
+            if limit > 0 && sent.len() >= limit as usize {
+                info!("[NOTIF] Limite de {} alertas/h atingido para {}; suprimindo", limit, alert.host);
+                *suppressed.entry(alert.host.clone()).or_insert(0) += 1;
+                false
+            } else {
+                sent.push_back(now);
+                true
+            }
+        })
+        .collect()
+}
+
+/// Aguarda o próximo ciclo atendendo comandos e diz o que o acordou.
+/// Cada comando que altera o estado visível já é publicado aqui, sem esperar o
+/// fim do próximo ciclo.
+pub(crate) fn wait_for_next_cycle(
+    commands: &mpsc::Receiver<MonitorCommand>,
+    state: &mut PingerState,
+    publish: &dyn Fn(&PingerState),
+    timeout: Duration,
+) -> Wake {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match commands.recv_timeout(remaining) {
+            Ok(MonitorCommand::CheckNow) => return Wake::CheckNow,
+            Ok(MonitorCommand::CheckTarget(host)) => return Wake::CheckTarget(host),
+            Ok(MonitorCommand::Shutdown) => return Wake::Shutdown,
+            Ok(MonitorCommand::Pause(duration)) => {
+                let until = chrono::Duration::from_std(duration)
+                    .ok()
+                    .and_then(|d| Local::now().checked_add_signed(d));
+                state.paused_until = until;
+                if let Some(until) = until {
+                    log_event(&format!("Monitoramento pausado até {}", until.format("%d/%m %H:%M")));
+                }
+                publish(state);
+                return Wake::Timeout;
+            }
+            Ok(MonitorCommand::Resume) => {
+                state.paused_until = None;
+                log_event("Monitoramento retomado");
+                publish(state);
+                return Wake::Timeout;
+            }
+            // Pausar ou retomar um destino checa ele na hora, para o menu refletir
+            Ok(MonitorCommand::PauseTarget(host, until)) => {
+                state.paused_targets.insert(host.clone(), until);
+                return Wake::CheckTarget(host);
+            }
+            Ok(MonitorCommand::ResumeTarget(host)) => {
+                state.paused_targets.remove(&host);
+                return Wake::CheckTarget(host);
+            }
+            // Não antecipa o ciclo: só registra e continua aguardando
+            Ok(MonitorCommand::Acknowledge(host)) => {
+                if acknowledge_outage(state, &host) {
+                    publish(state);
+                }
+            }
+            Ok(MonitorCommand::Mute(mute)) => {
+                set_mute(state, mute);
+                publish(state);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => return Wake::Timeout,
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                thread::sleep(remaining);
+                return Wake::Timeout;
+            }
+        }
+    }
+}
+
+/// Liga (`Some`) ou desliga o silenciamento global, registrando no log de eventos.
+pub(crate) fn set_mute(state: &mut PingerState, mute: Option<Mute>) {
+    state.muted = mute;
+    log_event(&match mute {
+        Some(Mute::Until(until)) => format!("Notificações silenciadas até {}", until.format("%d/%m %H:%M")),
+        Some(Mute::UntilResumed) => "Notificações silenciadas até serem reativadas".to_string(),
+        None => "Notificações reativadas".to_string(),
+    });
+}
+
+/// Reconhece a queda em andamento de um destino (ignorado se ele estiver online).
+/// Retorna `true` se o reconhecimento é novo.
+pub(crate) fn acknowledge_outage(state: &mut PingerState, host: &str) -> bool {
+    if state.results.iter().any(|r| r.host == host && !r.is_up) && state.acknowledged.insert(host.to_string()) {
+        info!("[ACK] Queda de {} reconhecida", host);
+        log_event(&format!("Queda de {} reconhecida", host));
+        return true;
+    }
+    false
+}
+
+// --- AGENDAMENTO ---
+/// Decide quais destinos com `schedule` estão devidos em cada ciclo.
+#[derive(Default)]
+pub(crate) struct Scheduler {
+    pub last_run: HashMap<String, chrono::DateTime<Local>>,
+    /// Expressões inválidas já reportadas, para não repetir o log a cada ciclo.
+    pub reported_errors: HashSet<String>,
+}
+
+impl Scheduler {
+    /// `None` quando o destino deve ser checado agora (ou a expressão é
+    /// inválida); senão o próximo horário.
+    pub fn check(&mut self, host: &str, expr: &str, now: chrono::DateTime<Local>) -> Option<chrono::DateTime<Local>> {
+        let schedule = match CronSchedule::parse(expr) {
+            Ok(schedule) => schedule,
+            Err(e) => {
+                if self.reported_errors.insert(host.to_string()) {
+                    warn!("[AGENDA] Expressão inválida para {} ({}); checando sempre", host, e);
+                }
+                return None;
+            }
+        };
+        let due = match self.last_run.get(host) {
+            Some(last) => schedule.next_after(*last).is_some_and(|next| next <= now),
+            None => schedule.matches(&now.naive_local()),
+        };
+        if due {
+            self.last_run.insert(host.to_string(), now);
+            None
+        } else {
+            schedule.next_after(now)
+        }
+    }
+
+    pub fn forget_missing(&mut self, hosts: &HashSet<String>) {
+        self.last_run.retain(|host, _| hosts.contains(host));
+        self.reported_errors.retain(|host| hosts.contains(host));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_counts_per_host() {
+        let (mut sent, mut suppressed) = (HashMap::new(), HashMap::new());
+        let alerts = || vec![Alert::state_change("a".into(), false, None, None), Alert::state_change("b".into(), false, None, None)];
+        let limit = |host: &str| if host == "a" { 1 } else { 0 };
+        assert_eq!(apply_rate_limit(&mut sent, &mut suppressed, alerts(), limit).len(), 2);
+        let kept = apply_rate_limit(&mut sent, &mut suppressed, alerts(), limit);
+        assert_eq!(kept.iter().map(|a| a.host.as_str()).collect::<Vec<_>>(), ["b"]);
+        assert_eq!(suppressed.get("a"), Some(&1));
+    }
+}
@@ -0,0 +1,298 @@
+//! Rede dos destinos: ASN, país e nomes reversos.
+
+use super::*;
+
+pub(crate) const RDAP_IP_URL: &str = "https://rdap.org/ip/";
+/// Idade máxima de um nome reverso (PTR) em cache.
+pub(crate) const PTR_REFRESH_SECS: i64 = 12 * 3600;
+/// Idade máxima de uma consulta de ASN/país antes de refazê-la.
+pub(crate) const GEOIP_REFRESH_SECS: i64 = 7 * DAY_SECS;
+
+/// Rede a que pertence o IP de um destino, em `network_state.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkInfo {
+    pub checked: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ip: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub asn: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl NetworkInfo {
+    /// Resumo para o menu: `AS15169 Google LLC · US (142.250.0.14)`.
+    pub fn label(&self) -> Option<String> {
+        let mut parts: Vec<String> = Vec::new();
+        let owner = match (self.asn, &self.owner) {
+            (Some(asn), Some(owner)) => Some(format!("AS{} {}", asn, owner)),
+            (Some(asn), None) => Some(format!("AS{}", asn)),
+            (None, owner) => owner.clone(),
+        };
+        parts.extend(owner);
+        parts.extend(self.country.clone());
+        if parts.is_empty() {
+            return None;
+        }
+        let mut label = parts.join(" · ");
+        if let Some(ip) = &self.ip {
+            label.push_str(&format!(" ({})", ip));
+        }
+        Some(label)
+    }
+}
+
+/// (ASN, organização, país) encontrados para um IP.
+pub(crate) type NetworkOwner = (Option<u32>, Option<String>, Option<String>);
+
+pub(crate) fn network_state_path() -> PathBuf {
+    get_data_dir().join("network_state.json")
+}
+
+pub(crate) fn load_network_info() -> HashMap<String, NetworkInfo> {
+    fs::read_to_string(network_state_path())
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok())
+        .unwrap_or_default()
+}
+
+/// Primeiro IP do host de um endereço (URL, `host:porta` ou `dns://`).
+pub(crate) fn target_ip(address: &str) -> Result<std::net::IpAddr, String> {
+    use std::net::ToSocketAddrs;
+
+    let (host, _) = split_host_port(address.trim()).ok_or_else(|| format!("endereço inválido: {}", address))?;
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    if let Ok(ip) = host.parse() {
+        return Ok(ip);
+    }
+    (host, 0)
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .map(|addr| addr.ip())
+        .ok_or_else(|| format!("{}: sem endereço", host))
+}
+
+/// ASN, organização e país pelas bases MaxMind configuradas.
+pub(crate) fn mmdb_lookup(cfg: &GeoIpConfig, ip: std::net::IpAddr) -> Result<NetworkOwner, String> {
+    use maxminddb::{geoip2, Reader};
+
+    let (mut asn, mut owner, mut country) = (None, None, None);
+    if let Some(path) = &cfg.asn_db {
+        let reader = Reader::open_readfile(path).map_err(|e| format!("{}: {}", path, e))?;
+        if let Ok(record) = reader.lookup::<geoip2::Asn>(ip) {
+            asn = record.autonomous_system_number;
+            owner = record.autonomous_system_organization.map(str::to_string);
+        }
+    }
+    if let Some(path) = &cfg.country_db {
+        let reader = Reader::open_readfile(path).map_err(|e| format!("{}: {}", path, e))?;
+        if let Ok(record) = reader.lookup::<geoip2::Country>(ip) {
+            country = record.country.or(record.registered_country).and_then(|c| c.iso_code).map(str::to_string);
+        }
+    }
+    Ok((asn, owner, country))
+}
+
+/// Dono e país do bloco de IP pelo RDAP do registro regional (via rdap.org).
+/// O ASN só vem quando o registro o publica (ARIN).
+pub(crate) fn rdap_ip_lookup(client: &Client, ip: std::net::IpAddr) -> Result<NetworkOwner, String> {
+    let body: serde_json::Value = client
+        .get(format!("{}{}", RDAP_IP_URL, ip))
+        .header("Accept", "application/rdap+json")
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.json())
+        .map_err(|e| e.to_string())?;
+    let asn = body
+        .get("arin_originas0_originautnums")
+        .and_then(|list| list.get(0)?.as_u64())
+        .and_then(|asn| u32::try_from(asn).ok());
+    // Nome do registrante no vCard; sem ele, o nome da rede (`GOOGLE`, `RIPE-NCC-...`)
+    let registrant = body.get("entities").and_then(|e| e.as_array()).and_then(|entities| {
+        entities
+            .iter()
+            .filter(|e| e.get("roles").and_then(|r| r.as_array()).is_some_and(|r| r.iter().any(|r| r == "registrant")))
+            .find_map(|e| {
+                e.get("vcardArray")?.get(1)?.as_array()?.iter().find_map(|field| {
+                    (field.get(0)?.as_str()? == "fn").then(|| field.get(3)?.as_str().map(str::to_string))?
+                })
+            })
+    });
+    let owner = registrant.or_else(|| body.get("name").and_then(|n| n.as_str()).map(str::to_string));
+    let country = body.get("country").and_then(|c| c.as_str()).map(str::to_string);
+    Ok((asn, owner, country))
+}
+
+/// Consulta de um destino: resolve o IP e busca a rede na base local ou no RDAP.
+pub(crate) fn lookup_network(cfg: &GeoIpConfig, client: Option<&Client>, address: &str) -> NetworkInfo {
+    let checked = Local::now().timestamp();
+    let found = target_ip(address).and_then(|ip| {
+        let info = if cfg.uses_mmdb() {
+            mmdb_lookup(cfg, ip)
+        } else {
+            rdap_ip_lookup(require_client(client)?, ip)
+        };
+        info.map(|info| (ip, info))
+    });
+    match found {
+        Ok((ip, (asn, owner, country))) => {
+            NetworkInfo { checked, ip: Some(ip.to_string()), asn, owner, country, error: None }
+        }
+        Err(e) => NetworkInfo { checked, ip: None, asn: None, owner: None, country: None, error: Some(e) },
+    }
+}
+
+/// Consultas de rede em segundo plano, refeitas a cada `GEOIP_REFRESH_SECS`
+/// por destino. O cache fica em memória; só a thread da consulta lê a rede e
+/// grava `network_state.json`.
+pub(crate) struct NetworkWatch {
+    pending: Option<mpsc::Receiver<HashMap<String, NetworkInfo>>>,
+    last_run: Option<Instant>,
+    pub records: HashMap<String, NetworkInfo>,
+}
+
+impl NetworkWatch {
+    pub fn load() -> Self {
+        Self { pending: None, last_run: None, records: load_network_info() }
+    }
+
+    pub fn poll(&mut self, cfg: Option<&GeoIpConfig>, targets: &[TargetConfig], client: Option<&Client>) {
+        if let Some(rx) = &self.pending {
+            match rx.try_recv() {
+                Ok(records) => {
+                    self.records = records;
+                    self.pending = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+
+        let Some(cfg) = cfg.cloned() else { return };
+        let retry = Duration::from_secs(RDAP_RETRY_SECS);
+        if self.last_run.is_some_and(|at| at.elapsed() < retry) {
+            return;
+        }
+        let now = Local::now().timestamp();
+        let mut due: Vec<String> = targets
+            .iter()
+            .map(|t| t.address.trim().to_string())
+            .filter(|host| self.records.get(host).is_none_or(|r| now - r.checked >= GEOIP_REFRESH_SECS))
+            .collect();
+        due.dedup();
+        if due.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let client = client.cloned();
+        let mut records = self.records.clone();
+        thread::spawn(move || {
+            for host in due {
+                let info = lookup_network(&cfg, client.as_ref(), &host);
+                match (&info.error, info.label()) {
+                    (Some(e), _) => warn!("[REDE] {}: {}", host, e),
+                    (None, Some(label)) => info!("[REDE] {}: {}", host, label),
+                    (None, None) => {}
+                }
+                records.insert(host, info);
+            }
+            let json = serde_json::to_string_pretty(&records).unwrap_or_default();
+            if let Err(e) = fs::write(network_state_path(), json) {
+                error!("Erro ao gravar a rede dos destinos: {}", e);
+            }
+            let _ = tx.send(records);
+        });
+        self.pending = Some(rx);
+        self.last_run = Some(Instant::now());
+    }
+}
+
+/// IP literal de um destino (`192.168.0.1`, `[::1]:22`, `https://10.0.0.2/`).
+pub(crate) fn target_ip_literal(address: &str) -> Option<std::net::IpAddr> {
+    let (host, _) = split_host_port(address.trim())?;
+    host.trim_start_matches('[').trim_end_matches(']').parse().ok()
+}
+
+/// Nome reverso de um IP, em `reverse_dns.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct PtrRecord {
+    pub checked: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+pub(crate) fn reverse_dns_path() -> PathBuf {
+    get_data_dir().join("reverse_dns.json")
+}
+
+/// Nomes reversos dos destinos que são IPs, refeitos a cada `PTR_REFRESH_SECS`
+/// numa thread para não atrasar o ciclo; ela também grava `reverse_dns.json`.
+pub(crate) struct ReverseDnsWatch {
+    pending: Option<mpsc::Receiver<HashMap<String, PtrRecord>>>,
+    pub records: HashMap<String, PtrRecord>,
+}
+
+impl ReverseDnsWatch {
+    pub fn load() -> Self {
+        let records = fs::read_to_string(reverse_dns_path())
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        Self { pending: None, records }
+    }
+
+    pub fn hostname(&self, address: &str) -> Option<String> {
+        let ip = target_ip_literal(address)?;
+        self.records.get(&ip.to_string())?.name.clone()
+    }
+
+    pub fn poll(&mut self, targets: &[TargetConfig]) {
+        if let Some(rx) = &self.pending {
+            match rx.try_recv() {
+                Ok(records) => {
+                    self.records = records;
+                    self.pending = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+
+        let now = Local::now().timestamp();
+        let mut due: Vec<std::net::IpAddr> = targets
+            .iter()
+            .filter_map(|t| target_ip_literal(&t.address))
+            .filter(|ip| self.records.get(&ip.to_string()).is_none_or(|r| now - r.checked >= PTR_REFRESH_SECS))
+            .collect();
+        due.sort();
+        due.dedup();
+        if due.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        let mut records = self.records.clone();
+        thread::spawn(move || {
+            for ip in due {
+                // Falhas também entram no cache e só são refeitas na próxima rodada
+                let name = reverse_lookup(ip).unwrap_or_else(|e| {
+                    warn!("[DNS] Reverso de {} falhou: {}", ip, e);
+                    None
+                });
+                records.insert(ip.to_string(), PtrRecord { checked: Local::now().timestamp(), name });
+            }
+            let json = serde_json::to_string_pretty(&records).unwrap_or_default();
+            if let Err(e) = fs::write(reverse_dns_path(), json) {
+                error!("Erro ao gravar os nomes reversos: {}", e);
+            }
+            let _ = tx.send(records);
+        });
+        self.pending = Some(rx);
+    }
+}
@@ -0,0 +1,1042 @@
+//! Notificadores e integrações (webhooks, Matrix, ntfy, métricas...).
+
+use super::*;
+
+/// Envia o estado para um monitor "Push" do Uptime Kuma. A query da URL copiada do
+/// Kuma (`?status=up&msg=OK&ping=`) é substituída pelos valores reais.
+pub(crate) fn push_uptime_kuma(client: &Client, push_url: &str, status: &TargetStatus, latency_ms: Option<f64>) {
+    let mut url = match reqwest::Url::parse(push_url.trim()) {
+        Ok(url) => url,
+        Err(e) => {
+            warn!("[KUMA] URL inválida para {}: {}", status.host, e);
+            return;
+        }
+    };
+    url.set_query(None);
+    url.query_pairs_mut()
+        .append_pair("status", if status.is_up { "up" } else { "down" })
+        .append_pair("msg", &status.detail)
+        .append_pair("ping", &latency_ms.map(|l| format!("{:.1}", l)).unwrap_or_default());
+
+    match client.get(url).send() {
+        Ok(resp) if resp.status().is_success() => {}
+        Ok(resp) => warn!("[KUMA] Push de {} recusado: HTTP {}", status.host, resp.status().as_u16()),
+        Err(e) => warn!("[KUMA] Falha no push de {}: {}", status.host, e),
+    }
+}
+
+/// Escapa vírgulas, espaços e `=` em chaves/valores de tag do line protocol.
+pub(crate) fn influx_escape_tag(raw: &str) -> String {
+    raw.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+pub(crate) fn render_influx_lines(measurement: &str, samples: &[HistorySample]) -> String {
+    let mut out = String::new();
+    for sample in samples {
+        let mut fields = format!("up={}i", sample.up as u8);
+        if let Some(lat) = sample.latency_ms {
+            fields.push_str(&format!(",latency_ms={}", lat));
+        }
+        out.push_str(&format!(
+            "{},target={} {} {}\n",
+            influx_escape_tag(measurement),
+            influx_escape_tag(&sample.host),
+            fields,
+            sample.ts.saturating_mul(1_000_000_000),
+        ));
+    }
+    out
+}
+
+pub(crate) fn write_influx(cfg: &InfluxConfig, client: Option<&Client>, samples: &[HistorySample]) {
+    use std::io::Write;
+
+    if samples.is_empty() {
+        return;
+    }
+    let body = render_influx_lines(&cfg.measurement, samples);
+
+    if let Some(file) = &cfg.file {
+        let written = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file)
+            .and_then(|mut f| f.write_all(body.as_bytes()));
+        if let Err(e) = written {
+            error!("[INFLUX] Erro ao gravar {:?}: {}", file, e);
+        }
+    }
+
+    if let (Some(url), Some(client)) = (&cfg.url, client) {
+        let mut req = client.post(url).header("Content-Type", "text/plain; charset=utf-8").body(body);
+        if let Some(token) = &cfg.token {
+            req = req.header("Authorization", format!("Token {}", token));
+        }
+        match req.send() {
+            Ok(resp) if resp.status().is_success() => {}
+            Ok(resp) => warn!("[INFLUX] Escrita recusada: HTTP {}", resp.status().as_u16()),
+            Err(e) => warn!("[INFLUX] Falha na escrita: {}", e),
+        }
+    }
+}
+
+/// Converte o destino em um segmento de nome de métrica statsd (`google.com` -> `google_com`).
+pub(crate) fn statsd_segment(raw: &str) -> String {
+    raw.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+        .collect()
+}
+
+pub(crate) fn render_statsd(prefix: &str, samples: &[HistorySample]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for sample in samples {
+        let base = format!("{}.{}", prefix, statsd_segment(&sample.host));
+        lines.push(format!("{}.up:{}|g", base, sample.up as u8));
+        lines.push(format!("{}.checks:1|c", base));
+        if !sample.up {
+            lines.push(format!("{}.failures:1|c", base));
+        }
+        if let Some(lat) = sample.latency_ms {
+            lines.push(format!("{}.latency:{:.3}|ms", base, lat));
+        }
+    }
+    lines
+}
+
+pub(crate) fn render_otlp(prefix: &str, samples: &[HistorySample]) -> serde_json::Value {
+    // OTLP/JSON: inteiros são strings em `asInt`, pontos flutuantes vão em `asDouble`
+    let point = |sample: &HistorySample, key: &str, value: serde_json::Value| {
+        serde_json::json!({
+            "timeUnixNano": sample.ts.saturating_mul(1_000_000_000).to_string(),
+            "attributes": [{ "key": "target", "value": { "stringValue": sample.host } }],
+            key: value,
+        })
+    };
+    let up_points: Vec<_> = samples
+        .iter()
+        .map(|s| point(s, "asInt", (s.up as u8).to_string().into()))
+        .collect();
+    let latency_points: Vec<_> = samples
+        .iter()
+        .filter_map(|s| s.latency_ms.map(|l| point(s, "asDouble", l.into())))
+        .collect();
+
+    serde_json::json!({
+        "resourceMetrics": [{
+            "resource": { "attributes": [{ "key": "service.name", "value": { "stringValue": "cosmic_pinger" } }] },
+            "scopeMetrics": [{
+                "scope": { "name": "cosmic_pinger", "version": APP_VERSION },
+                "metrics": [
+                    { "name": format!("{}.up", prefix), "gauge": { "dataPoints": up_points } },
+                    { "name": format!("{}.latency", prefix), "unit": "ms", "gauge": { "dataPoints": latency_points } },
+                ],
+            }],
+        }],
+    })
+}
+
+pub(crate) fn emit_metrics(cfg: &MetricsConfig, client: Option<&Client>, samples: &[HistorySample]) {
+    if samples.is_empty() {
+        return;
+    }
+
+    if let Some(addr) = &cfg.statsd {
+        match std::net::UdpSocket::bind("0.0.0.0:0") {
+            Ok(socket) => {
+                for line in render_statsd(&cfg.prefix, samples) {
+                    if let Err(e) = socket.send_to(line.as_bytes(), addr.as_str()) {
+                        warn!("[STATSD] Falha ao enviar para {}: {}", addr, e);
+                        break;
+                    }
+                }
+            }
+            Err(e) => warn!("[STATSD] Falha ao abrir socket UDP: {}", e),
+        }
+    }
+
+    if let (Some(url), Some(client)) = (&cfg.otlp_url, client) {
+        let mut req = client.post(url).json(&render_otlp(&cfg.prefix, samples));
+        for (name, value) in &cfg.otlp_headers {
+            req = req.header(name.as_str(), value.as_str());
+        }
+        match req.send() {
+            Ok(resp) if resp.status().is_success() => {}
+            Ok(resp) => warn!("[OTLP] Exportação recusada: HTTP {}", resp.status().as_u16()),
+            Err(e) => warn!("[OTLP] Falha na exportação: {}", e),
+        }
+    }
+}
+
+/// Itens `sender data` do Zabbix: disponibilidade (0/1) e latência em ms.
+pub(crate) fn render_zabbix(cfg: &ZabbixConfig, samples: &[HistorySample]) -> Vec<serde_json::Value> {
+    let mut items = Vec::new();
+    for sample in samples {
+        let host = cfg.hosts.get(&sample.host).unwrap_or(&cfg.host);
+        // Parâmetro entre aspas: URLs têm vírgulas e colchetes
+        let param = format!("\"{}\"", sample.host.replace('"', "\\\""));
+        let key = |template: &str| template.replace("{target}", &param);
+        items.push(serde_json::json!({
+            "host": host, "key": key(&cfg.up_key), "value": (sample.up as u8).to_string(), "clock": sample.ts,
+        }));
+        if let Some(lat) = sample.latency_ms {
+            items.push(serde_json::json!({
+                "host": host, "key": key(&cfg.latency_key), "value": format!("{:.3}", lat), "clock": sample.ts,
+            }));
+        }
+    }
+    items
+}
+
+/// Envia os itens num único pacote `ZBXD` e devolve o `info` da resposta
+/// (ex.: "processed: 4; failed: 0; ...").
+pub(crate) fn zabbix_send(server: &str, items: Vec<serde_json::Value>) -> Result<String, String> {
+    use std::io::{Read, Write};
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    let address = if split_host_port(server).is_some_and(|(_, port)| port.is_some()) {
+        server.to_string()
+    } else {
+        format!("{}:10051", server)
+    };
+    let addr = address
+        .to_socket_addrs()
+        .map_err(|e| e.to_string())?
+        .next()
+        .ok_or_else(|| format!("{}: sem endereço", server))?;
+    let payload = serde_json::json!({ "request": "sender data", "data": items }).to_string();
+    let mut packet = b"ZBXD\x01".to_vec();
+    packet.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    packet.extend_from_slice(payload.as_bytes());
+
+    let mut stream = TcpStream::connect_timeout(&addr, Duration::from_millis(TCP_CONNECT_TIMEOUT_MS)).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(Duration::from_secs(HTTP_TIMEOUT_SECS))).map_err(|e| e.to_string())?;
+    stream.write_all(&packet).map_err(|e| e.to_string())?;
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(|e| e.to_string())?;
+    let body = response
+        .strip_prefix(b"ZBXD\x01")
+        .and_then(|rest| rest.get(8..))
+        .ok_or_else(|| "resposta do Zabbix inválida".to_string())?;
+    let reply: serde_json::Value = serde_json::from_slice(body).map_err(|e| e.to_string())?;
+    let info = reply.get("info").and_then(|i| i.as_str()).unwrap_or_default().to_string();
+    match reply.get("response").and_then(|r| r.as_str()) {
+        Some("success") => Ok(info),
+        _ => Err(if info.is_empty() { reply.to_string() } else { info }),
+    }
+}
+
+pub(crate) fn emit_zabbix(cfg: &ZabbixConfig, samples: &[HistorySample]) {
+    if samples.is_empty() {
+        return;
+    }
+    match zabbix_send(&cfg.server, render_zabbix(cfg, samples)) {
+        // Itens sem trapper correspondente aparecem como "failed" numa resposta de sucesso
+        Ok(info) if !info.contains("failed: 0") => warn!("[ZABBIX] Itens recusados: {}", info),
+        Ok(info) => debug!("[ZABBIX] {}", info),
+        Err(e) => warn!("[ZABBIX] Falha ao enviar para {}: {}", cfg.server, e),
+    }
+}
+
+/// Texto completo de uma mudança de estado para canais externos (chat, push).
+pub(crate) fn describe_state_change(host: &str, is_up: bool, latency_ms: Option<f64>, outage_secs: Option<i64>) -> String {
+    let mut text = trf(if is_up { "alert.up" } else { "alert.down" }, &[("host", &host)]);
+    if let Some(lat) = latency_ms {
+        text.push_str(&trf("alert.latency", &[("latency", &format!("{:.1}", lat))]));
+    }
+    if let Some(secs) = outage_secs {
+        text.push_str(&trf("alert.outage", &[("duration", &format_duration(secs))]));
+    }
+    text
+}
+
+/// Um alerta pronto para ser entregue aos canais de notificação.
+#[derive(Clone)]
+pub(crate) struct Alert {
+    /// Destino que originou o alerta, usado para os filtros por destino.
+    pub host: String,
+    pub text: String,
+    pub severity: Severity,
+    /// Ícone freedesktop usado pelas notificações de desktop.
+    pub icon: &'static str,
+    /// Novo estado quando o alerta é uma mudança online/offline (agrupável).
+    pub transition: Option<bool>,
+    /// Destino cuja queda pode ser reconhecida a partir da notificação.
+    pub ack_host: Option<String>,
+    /// Duração da queda encerrada, nas recuperações.
+    pub outage_secs: Option<i64>,
+}
+
+impl Alert {
+    pub fn state_change(host: String, is_up: bool, latency_ms: Option<f64>, outage_secs: Option<i64>) -> Self {
+        let text = describe_state_change(&host, is_up, latency_ms, outage_secs);
+        let (severity, icon) = if is_up {
+            (Severity::Normal, "network-transmit-receive")
+        } else {
+            (Severity::Critical, "network-error")
+        };
+        let ack_host = (!is_up).then(|| host.clone());
+        Self { host, text, severity, icon, transition: Some(is_up), ack_host, outage_secs }
+    }
+
+    /// Junta várias mudanças na mesma direção em um único alerta.
+    pub fn grouped(is_up: bool, alerts: &[&Alert]) -> Self {
+        let hosts: Vec<&str> = alerts.iter().map(|a| a.host.as_str()).collect();
+        // Nas recuperações cada destino leva a duração da queda: "a (14 m 32 s)"
+        let labels: Vec<String> = alerts
+            .iter()
+            .map(|a| match a.outage_secs {
+                Some(secs) => format!("{} ({})", a.host, format_duration(secs)),
+                None => a.host.clone(),
+            })
+            .collect();
+        let text = trf(
+            if is_up { "alert.grouped_up" } else { "alert.grouped_down" },
+            &[("count", &hosts.len()), ("hosts", &labels.join(", "))],
+        );
+        let severity = alerts.iter().map(|a| a.severity).fold(Severity::Low, |acc, s| if s > acc { s } else { acc });
+        Self {
+            host: hosts.join(","),
+            text,
+            severity,
+            icon: alerts.first().map(|a| a.icon).unwrap_or("network-error"),
+            transition: Some(is_up),
+            ack_host: None,
+            outage_secs: None,
+        }
+    }
+
+    pub fn flap_started(host: String) -> Self {
+        let text = trf("alert.flap_started", &[("host", &host)]);
+        Self { host, text, severity: Severity::Critical, icon: "network-error", transition: None, ack_host: None, outage_secs: None }
+    }
+
+    pub fn flap_ended(host: String, is_up: bool) -> Self {
+        let text = trf(if is_up { "alert.flap_ended_up" } else { "alert.flap_ended_down" }, &[("host", &host)]);
+        let (severity, icon) = if is_up {
+            (Severity::Normal, "network-transmit-receive")
+        } else {
+            (Severity::Critical, "network-error")
+        };
+        Self { host, text, severity, icon, transition: None, ack_host: None, outage_secs: None }
+    }
+
+    pub fn group_change(name: String, is_down: bool, down: &[String], total: usize) -> Self {
+        let text = trf(
+            if is_down { "alert.group_down" } else { "alert.group_up" },
+            &[("group", &name), ("down", &down.len()), ("total", &total), ("hosts", &down.join(", "))],
+        );
+        let (severity, icon) = if is_down {
+            (Severity::Critical, "network-error")
+        } else {
+            (Severity::Normal, "network-transmit-receive")
+        };
+        Self { host: name, text, severity, icon, transition: None, ack_host: None, outage_secs: None }
+    }
+
+    pub fn degraded_change(host: String, degraded: bool, detail: &str) -> Self {
+        let text = trf(if degraded { "alert.degraded" } else { "alert.degraded_recovered" }, &[("host", &host), ("detail", &detail)]);
+        let icon = if degraded { "network-idle" } else { "network-transmit-receive" };
+        Self { host, text, severity: Severity::Normal, icon, transition: None, ack_host: None, outage_secs: None }
+    }
+
+    pub fn domain_expiring(host: String, domain: &str, expires: i64, now: i64) -> Self {
+        let days = ((expires - now) / DAY_SECS).max(0);
+        let text = trf(
+            "alert.domain_expiring",
+            &[("domain", &domain), ("days", &days), ("date", &format_timestamp(expires, "%d/%m/%Y"))],
+        );
+        let severity = if days <= 7 { Severity::Critical } else { Severity::Normal };
+        Self { host, text, severity, icon: "appointment-soon", transition: None, ack_host: None, outage_secs: None }
+    }
+
+    pub fn content_changed(host: String) -> Self {
+        let text = trf("alert.content_changed", &[("host", &host)]);
+        Self { host, text, severity: Severity::Normal, icon: "document-properties", transition: None, ack_host: None, outage_secs: None }
+    }
+
+    pub fn latency_anomaly(host: String, latency_ms: f64, baseline_ms: f64) -> Self {
+        let text = trf(
+            "alert.latency_anomaly",
+            &[("host", &host), ("latency", &format!("{:.0}", latency_ms)), ("baseline", &format!("{:.0}", baseline_ms))],
+        );
+        Self { host, text, severity: Severity::Normal, icon: "network-idle", transition: None, ack_host: None, outage_secs: None }
+    }
+}
+
+/// Canal de entrega de alertas (desktop, chat, push, ...).
+pub(crate) trait Notifier {
+    /// Nome usado em logs e em `disabled_notifiers` dos destinos.
+    fn name(&self) -> &'static str;
+    fn filter(&self) -> &NotifierFilter;
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String>;
+    /// Canais que precisam de um alerta por destino (ex.: chaves de deduplicação)
+    /// retornam `false` e ficam fora do agrupamento.
+    fn groups(&self) -> bool {
+        true
+    }
+}
+
+pub(crate) fn require_client(client: Option<&Client>) -> Result<&Client, String> {
+    client.ok_or_else(|| "cliente HTTP indisponível".to_string())
+}
+
+/// Converte a resposta HTTP de um canal em sucesso/erro legível.
+pub(crate) fn check_delivery(result: reqwest::Result<reqwest::blocking::Response>) -> Result<(), String> {
+    match result {
+        Ok(resp) if resp.status().is_success() => Ok(()),
+        Ok(resp) => Err(format!("recusado: HTTP {}", resp.status().as_u16())),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Canais configurados e as informações por destino usadas nos filtros.
+pub(crate) struct NotifierRegistry {
+    pub channels: Vec<Box<dyn Notifier>>,
+    /// Canais desativados por destino (`TargetConfig::disabled_notifiers`).
+    pub disabled: HashMap<String, Vec<String>>,
+    /// Canais permitidos por destino (`TargetConfig::notifiers`).
+    pub allowed: HashMap<String, Vec<String>>,
+    pub tags: HashMap<String, Vec<String>>,
+    /// Destinos com `notify: false`.
+    pub silenced: HashSet<String>,
+    pub urgency: HashMap<String, Severity>,
+}
+
+impl NotifierRegistry {
+    pub fn from_config(cfg: &AppConfig, commands: Option<mpsc::Sender<MonitorCommand>>) -> Self {
+        let mut channels: Vec<Box<dyn Notifier>> = Vec::new();
+        if cfg.desktop.enabled {
+            channels.push(Box::new(DesktopNotifier { cfg: cfg.desktop.clone(), commands }));
+        }
+        if let Some(matrix) = &cfg.matrix {
+            channels.push(Box::new(matrix.clone()));
+        }
+        if let Some(ntfy) = &cfg.ntfy {
+            channels.push(Box::new(ntfy.clone()));
+        }
+        if let Some(gotify) = &cfg.gotify {
+            channels.push(Box::new(gotify.clone()));
+        }
+        if let Some(pushover) = &cfg.pushover {
+            channels.push(Box::new(pushover.clone()));
+        }
+        if let Some(pagerduty) = &cfg.pagerduty {
+            channels.push(Box::new(pagerduty.clone()));
+        }
+        if let Some(opsgenie) = &cfg.opsgenie {
+            let tags = cfg.targets.iter().map(|t| (t.address.trim().to_string(), t.tags.clone())).collect();
+            channels.push(Box::new(OpsgenieNotifier { cfg: opsgenie.clone(), tags }));
+        }
+        for webhook in &cfg.webhooks {
+            channels.push(Box::new(webhook.clone()));
+        }
+        if let Some(email) = &cfg.email {
+            channels.push(Box::new(email.clone()));
+        }
+        channels.retain(|channel| !cfg.disabled_notifiers.iter().any(|n| n == channel.name()));
+
+        let mut registry = Self {
+            channels,
+            disabled: HashMap::new(),
+            allowed: HashMap::new(),
+            tags: HashMap::new(),
+            silenced: HashSet::new(),
+            urgency: HashMap::new(),
+        };
+        for target in &cfg.targets {
+            let host = target.address.trim().to_string();
+            if !target.disabled_notifiers.is_empty() {
+                registry.disabled.insert(host.clone(), target.disabled_notifiers.clone());
+            }
+            if !target.notifiers.is_empty() {
+                registry.allowed.insert(host.clone(), target.notifiers.clone());
+            }
+            if !target.notify {
+                registry.silenced.insert(host.clone());
+            }
+            if let Some(urgency) = target.urgency {
+                registry.urgency.insert(host.clone(), urgency);
+            }
+            if !target.tags.is_empty() {
+                registry.tags.insert(host, target.tags.clone());
+            }
+        }
+        registry
+    }
+
+    pub fn accepts(&self, channel: &dyn Notifier, alert: &Alert) -> bool {
+        let disabled = self.disabled.get(&alert.host).map(Vec::as_slice).unwrap_or_default();
+        let allowed = self.allowed.get(&alert.host).map(Vec::as_slice).unwrap_or_default();
+        let tags = self.tags.get(&alert.host).map(Vec::as_slice).unwrap_or_default();
+        !disabled.iter().any(|n| n == channel.name())
+            && (allowed.is_empty() || allowed.iter().any(|n| n == channel.name()))
+            && channel.filter().accepts(alert.severity, tags)
+    }
+
+    /// Aplica `notify` e `urgency` dos destinos antes dos filtros dos canais.
+    pub fn prepare(&self, alerts: &[Alert]) -> Vec<Alert> {
+        alerts
+            .iter()
+            .filter(|alert| !self.silenced.contains(&alert.host))
+            .cloned()
+            .map(|mut alert| {
+                if let Some(urgency) = self.urgency.get(&alert.host) {
+                    alert.severity = *urgency;
+                }
+                alert
+            })
+            .collect()
+    }
+
+    /// Alertas (já com `prepare`) que ao menos um canal entregaria. Roda antes do
+    /// limite por hora, para alertas que nenhum canal aceita não gastarem a cota.
+    pub fn deliverable(&self, alerts: &[Alert]) -> Vec<Alert> {
+        self.prepare(alerts)
+            .into_iter()
+            .filter(|alert| self.channels.iter().any(|channel| self.accepts(channel.as_ref(), alert)))
+            .collect()
+    }
+
+    /// Entrega os alertas de um ciclo. Com `group`, mudanças de estado na mesma
+    /// direção que passaram pelos filtros de um canal viram um único alerta nele.
+    pub fn dispatch(&self, client: Option<&Client>, alerts: &[Alert], group: bool) {
+        let alerts = self.prepare(alerts);
+        for channel in &self.channels {
+            let accepted: Vec<&Alert> = alerts.iter().filter(|a| self.accepts(channel.as_ref(), a)).collect();
+            let mut merged: Vec<Alert> = Vec::new();
+            let mut outgoing: Vec<&Alert> = Vec::new();
+
+            for is_up in [false, true] {
+                let same: Vec<&Alert> = accepted.iter().copied().filter(|a| a.transition == Some(is_up)).collect();
+                if group && channel.groups() && same.len() > 1 {
+                    merged.push(Alert::grouped(is_up, &same));
+                } else {
+                    outgoing.extend(same);
+                }
+            }
+            outgoing.extend(accepted.iter().copied().filter(|a| a.transition.is_none()));
+            outgoing.extend(merged.iter());
+
+            for alert in outgoing {
+                match channel.send(client, alert) {
+                    Ok(()) => info!("[{}] Alerta enviado: {}", channel.name().to_uppercase(), alert.host),
+                    Err(e) => {
+                        warn!("[{}] Falha no envio: {}", channel.name().to_uppercase(), e);
+                        log_event(&format!("Erro: alerta de {} não entregue via {}: {}", alert.host, channel.name(), e));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Notificações de desktop; quedas ganham um botão "Reconhecer" que volta ao monitor.
+pub(crate) struct DesktopNotifier {
+    pub cfg: DesktopConfig,
+    pub commands: Option<mpsc::Sender<MonitorCommand>>,
+}
+
+impl Notifier for DesktopNotifier {
+    fn name(&self) -> &'static str {
+        "desktop"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.cfg.filter
+    }
+
+    fn send(&self, _client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let mut notification = Notification::new();
+        notification
+            .summary(APP_NAME)
+            .body(&alert.text)
+            .icon(alert.icon)
+            .timeout(NOTIFICATION_TIMEOUT_MS);
+        self.show(notification, alert)
+    }
+}
+
+impl DesktopNotifier {
+    #[cfg(target_os = "linux")]
+    pub fn show(&self, mut notification: Notification, alert: &Alert) -> Result<(), String> {
+        notification.urgency(alert.severity.urgency());
+
+        let ack = alert.ack_host.clone().zip(self.commands.clone());
+        if ack.is_some() {
+            notification.action("ack", tr("notify.acknowledge"));
+        }
+        let handle = notification.show().map_err(|e| e.to_string())?;
+
+        if let Some((host, commands)) = ack {
+            // wait_for_action bloqueia até a notificação fechar
+            thread::spawn(move || {
+                handle.wait_for_action(|action| {
+                    if action == "ack" {
+                        let _ = commands.send(MonitorCommand::Acknowledge(host));
+                    }
+                });
+            });
+        }
+        Ok(())
+    }
+
+    // Toasts do Windows e a central de notificações do macOS não devolvem ações
+    #[cfg(not(target_os = "linux"))]
+    pub fn show(&self, notification: Notification, alert: &Alert) -> Result<(), String> {
+        let _ = (&alert.ack_host, &self.commands);
+        notification.show().map(drop).map_err(|e| e.to_string())
+    }
+}
+
+impl Notifier for WebhookConfig {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let client = require_client(client)?;
+        let mut req = match &self.template {
+            Some(serde_json::Value::String(body)) => client.post(&self.url).body(render_alert_template(body, alert)),
+            Some(template) => client.post(&self.url).json(&render_json_template(template, alert)),
+            None => client.post(&self.url).json(&serde_json::json!({
+                "app": APP_NAME,
+                "target": alert.host,
+                "message": alert.text,
+                "severity": alert.severity,
+                "timestamp": Local::now().to_rfc3339(),
+            })),
+        };
+        for (name, value) in &self.headers {
+            req = req.header(name.as_str(), render_alert_template(value, alert));
+        }
+        check_delivery(req.send())
+    }
+}
+
+/// Troca os marcadores de um modelo de webhook pelos dados do alerta. `{status}`
+/// é `down`, `up` ou vazio (alertas que não são mudança de estado).
+pub(crate) fn render_alert_template(template: &str, alert: &Alert) -> String {
+    let status = match alert.transition {
+        Some(true) => "up",
+        Some(false) => "down",
+        None => "",
+    };
+    let outage = alert.outage_secs.map(|secs| secs.to_string()).unwrap_or_default();
+    let now = Local::now();
+    [
+        ("{app}", APP_NAME.to_string()),
+        ("{target}", alert.host.clone()),
+        ("{message}", alert.text.clone()),
+        ("{severity}", alert.severity.as_str().to_string()),
+        ("{status}", status.to_string()),
+        ("{outage_secs}", outage),
+        ("{timestamp}", now.to_rfc3339()),
+        ("{unix}", now.timestamp().to_string()),
+    ]
+    .iter()
+    .fold(template.to_string(), |text, (name, value)| text.replace(name, value))
+}
+
+/// Aplica `render_alert_template` em cada string (chaves e valores) de um JSON,
+/// então aspas e quebras de linha da mensagem nunca quebram o corpo.
+pub(crate) fn render_json_template(template: &serde_json::Value, alert: &Alert) -> serde_json::Value {
+    use serde_json::Value;
+    match template {
+        Value::String(text) => Value::String(render_alert_template(text, alert)),
+        Value::Array(items) => Value::Array(items.iter().map(|item| render_json_template(item, alert)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| (render_alert_template(key, alert), render_json_template(value, alert)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+impl Notifier for EmailConfig {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn send(&self, _client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        use std::io::Write;
+
+        let subject = format!("[{}] {}", APP_NAME, alert.text.lines().next().unwrap_or_default());
+        let mut message = String::new();
+        if let Some(from) = &self.from {
+            message.push_str(&format!("From: {}\r\n", from));
+        }
+        message.push_str(&format!(
+            "To: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.to.join(", "),
+            subject,
+            alert.text
+        ));
+
+        let mut child = SysCommand::new(&self.sendmail)
+            .arg("-t")
+            .stdin(std::process::Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("não foi possível executar {}: {}", self.sendmail, e))?;
+        child
+            .stdin
+            .take()
+            .ok_or("stdin indisponível")?
+            .write_all(message.as_bytes())
+            .map_err(|e| e.to_string())?;
+        let status = child.wait().map_err(|e| e.to_string())?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("{} terminou com {}", self.sendmail, status))
+        }
+    }
+}
+
+/// Prioridades do ntfy: 1 (mínima) a 5 (máxima).
+pub(crate) fn ntfy_priority(severity: Severity) -> u8 {
+    match severity {
+        Severity::Low => 2,
+        Severity::Normal => 3,
+        Severity::Critical => 5,
+    }
+}
+
+impl Notifier for NtfyConfig {
+    fn name(&self) -> &'static str {
+        "ntfy"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let client = require_client(client)?;
+        let url = format!("{}/{}", self.server.trim_end_matches('/'), self.topic.trim_matches('/'));
+        let mut req = client
+            .post(url)
+            .header("Title", APP_NAME)
+            .header("Priority", ntfy_priority(alert.severity).to_string())
+            .header("Tags", if alert.severity == Severity::Critical { "rotating_light" } else { "satellite" })
+            .body(alert.text.clone());
+        if let Some(token) = &self.token {
+            req = req.bearer_auth(token);
+        }
+        check_delivery(req.send())
+    }
+}
+
+impl Notifier for MatrixConfig {
+    fn name(&self) -> &'static str {
+        "matrix"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let client = require_client(client)?;
+        static TXN_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+        let txn = format!(
+            "cp{}-{}",
+            Local::now().timestamp_millis(),
+            TXN_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        );
+        let mut url = reqwest::Url::parse(self.homeserver.trim_end_matches('/'))
+            .map_err(|e| format!("homeserver inválido: {}", e))?;
+        // path_segments_mut codifica `!` e `:` do room id corretamente
+        url.path_segments_mut()
+            .map_err(|_| "homeserver inválido".to_string())?
+            .extend(["_matrix", "client", "v3", "rooms", &self.room_id, "send", "m.room.message", &txn]);
+
+        check_delivery(
+            client
+                .put(url)
+                .bearer_auth(&self.access_token)
+                .json(&serde_json::json!({ "msgtype": "m.text", "body": alert.text }))
+                .send(),
+        )
+    }
+}
+
+impl Notifier for GotifyConfig {
+    fn name(&self) -> &'static str {
+        "gotify"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let client = require_client(client)?;
+        // Gotify: 0-3 silencioso, 4-7 normal, 8+ alta prioridade
+        let priority = match alert.severity {
+            Severity::Low => 2,
+            Severity::Normal => 5,
+            Severity::Critical => 8,
+        };
+        check_delivery(
+            client
+                .post(format!("{}/message", self.server.trim_end_matches('/')))
+                .header("X-Gotify-Key", &self.app_token)
+                .json(&serde_json::json!({ "title": APP_NAME, "message": alert.text, "priority": priority }))
+                .send(),
+        )
+    }
+}
+
+impl Notifier for PushoverConfig {
+    fn name(&self) -> &'static str {
+        "pushover"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let client = require_client(client)?;
+        let priority = match alert.severity {
+            Severity::Low => "-1",
+            Severity::Normal => "0",
+            Severity::Critical => "1",
+        };
+        let mut form = vec![
+            ("token", self.app_token.as_str()),
+            ("user", self.user_key.as_str()),
+            ("title", APP_NAME),
+            ("message", alert.text.as_str()),
+            ("priority", priority),
+        ];
+        if let Some(device) = &self.device {
+            form.push(("device", device.as_str()));
+        }
+        check_delivery(client.post("https://api.pushover.net/1/messages.json").form(&form).send())
+    }
+}
+
+impl Notifier for PagerDutyConfig {
+    fn name(&self) -> &'static str {
+        "pagerduty"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.filter
+    }
+
+    fn groups(&self) -> bool {
+        false
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        // Só quedas e recuperações viram incidentes; avisos soltos ficam de fora
+        let Some(is_up) = alert.transition else {
+            return Ok(());
+        };
+        let severity = match alert.severity {
+            Severity::Low => "info",
+            Severity::Normal => "warning",
+            Severity::Critical => "critical",
+        };
+        let body = serde_json::json!({
+            "routing_key": self.routing_key,
+            "event_action": if is_up { "resolve" } else { "trigger" },
+            "dedup_key": format!("cosmic_pinger:{}", alert.host),
+            "payload": {
+                "summary": alert.text,
+                "source": self.source.as_deref().unwrap_or(&alert.host),
+                "severity": severity,
+                "timestamp": Local::now().to_rfc3339(),
+            },
+        });
+        check_delivery(require_client(client)?.post("https://events.pagerduty.com/v2/enqueue").json(&body).send())
+    }
+}
+
+/// Opsgenie com as tags de cada destino, copiadas da configuração ao montar o registro.
+pub(crate) struct OpsgenieNotifier {
+    pub cfg: OpsgenieConfig,
+    pub tags: HashMap<String, Vec<String>>,
+}
+
+impl Notifier for OpsgenieNotifier {
+    fn name(&self) -> &'static str {
+        "opsgenie"
+    }
+
+    fn filter(&self) -> &NotifierFilter {
+        &self.cfg.filter
+    }
+
+    fn groups(&self) -> bool {
+        false
+    }
+
+    fn send(&self, client: Option<&Client>, alert: &Alert) -> Result<(), String> {
+        let Some(is_up) = alert.transition else {
+            return Ok(());
+        };
+        let client = require_client(client)?;
+        let mut url = reqwest::Url::parse(self.cfg.api_url.trim_end_matches('/'))
+            .map_err(|e| format!("api_url inválida: {}", e))?;
+        let alias = format!("cosmic_pinger:{}", alert.host);
+        let auth = format!("GenieKey {}", self.cfg.api_key);
+
+        let request = if is_up {
+            url.path_segments_mut()
+                .map_err(|_| "api_url inválida".to_string())?
+                .extend(["v2", "alerts", &alias, "close"]);
+            url.set_query(Some("identifierType=alias"));
+            client.post(url).json(&serde_json::json!({ "source": APP_NAME, "note": alert.text }))
+        } else {
+            url.path_segments_mut().map_err(|_| "api_url inválida".to_string())?.extend(["v2", "alerts"]);
+            let priority = match alert.severity {
+                Severity::Low => "P5",
+                Severity::Normal => "P3",
+                Severity::Critical => "P1",
+            };
+            let mut tags = self.cfg.alert_tags.clone();
+            tags.extend(self.tags.get(&alert.host).into_iter().flatten().cloned());
+            client.post(url).json(&serde_json::json!({
+                "message": alert.text.chars().take(130).collect::<String>(),
+                "alias": alias,
+                "description": alert.text,
+                "entity": alert.host,
+                "source": APP_NAME,
+                "priority": priority,
+                "tags": tags,
+            }))
+        };
+        check_delivery(request.header("Authorization", auth).send())
+    }
+}
+
+/// Transição entregue aos hooks pelas variáveis `COSMIC_PINGER_*`.
+pub(crate) struct HookEvent {
+    pub host: String,
+    pub is_up: bool,
+    pub detail: String,
+    pub latency_ms: Option<f64>,
+    pub outage_secs: Option<i64>,
+    pub ts: i64,
+}
+
+/// Roda `command` no shell sem bloquear o ciclo. A saída padrão é descartada
+/// para não poluir os modos texto; passando de `HOOK_TIMEOUT_SECS` o processo
+/// é encerrado.
+pub(crate) fn run_hook(command: &str, event: &HookEvent) {
+    let mut cmd = if cfg!(windows) {
+        let mut cmd = SysCommand::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = SysCommand::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    };
+    cmd.env("COSMIC_PINGER_HOST", &event.host)
+        .env("COSMIC_PINGER_STATE", if event.is_up { "up" } else { "down" })
+        .env("COSMIC_PINGER_DETAIL", &event.detail)
+        .env("COSMIC_PINGER_TIMESTAMP", event.ts.to_string())
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null());
+    if let Some(latency) = event.latency_ms {
+        cmd.env("COSMIC_PINGER_LATENCY_MS", format!("{:.1}", latency));
+    }
+    if let Some(secs) = event.outage_secs {
+        cmd.env("COSMIC_PINGER_OUTAGE_SECS", secs.to_string());
+    }
+
+    let host = event.host.clone();
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("[HOOK] Falha ao executar hook de {}: {}", host, e);
+            log_event(&format!("Erro: hook de {} não executado: {}", host, e));
+            return;
+        }
+    };
+    thread::spawn(move || {
+        let deadline = Instant::now() + Duration::from_secs(HOOK_TIMEOUT_SECS);
+        loop {
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => {
+                    info!("[HOOK] Hook de {} concluído", host);
+                    break;
+                }
+                Ok(Some(status)) => {
+                    warn!("[HOOK] Hook de {} terminou com {}", host, status);
+                    log_event(&format!("Erro: hook de {} terminou com {}", host, status));
+                    break;
+                }
+                Ok(None) if Instant::now() >= deadline => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    warn!("[HOOK] Hook de {} excedeu {} s e foi encerrado", host, HOOK_TIMEOUT_SECS);
+                    log_event(&format!("Erro: hook de {} excedeu {} s", host, HOOK_TIMEOUT_SECS));
+                    break;
+                }
+                Ok(None) => thread::sleep(Duration::from_millis(200)),
+                Err(e) => {
+                    warn!("[HOOK] Falha ao aguardar hook de {}: {}", host, e);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+// --- NOTIFICAÇÕES DA ÁREA DE TRABALHO ---
+pub(crate) fn send_config_error_notification(error: &str) {
+    let body = trf("notify.config_error", &[("error", &error)]);
+    show_notification(&body, "dialog-warning", Severity::Critical);
+}
+
+pub fn show_notification(body: &str, icon: &str, severity: Severity) {
+    let mut notification = Notification::new();
+    notification
+        .summary(APP_NAME)
+        .body(body)
+        .icon(icon)
+        .timeout(NOTIFICATION_TIMEOUT_MS);
+    #[cfg(target_os = "linux")]
+    notification.urgency(severity.urgency());
+    #[cfg(not(target_os = "linux"))]
+    let _ = severity;
+
+    if let Err(e) = notification.show() {
+        error!("Erro ao enviar notificação: {}", e);
+    } else {
+        debug!("[NOTIF] Notificação enviada com sucesso!");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_by_severity_and_tags() {
+        let filter = NotifierFilter { min_severity: Severity::Normal, tags: vec!["prod".into()] };
+        assert!(filter.accepts(Severity::Critical, &["prod".into()]));
+        assert!(!filter.accepts(Severity::Low, &["prod".into()]));
+        assert!(!filter.accepts(Severity::Critical, &["dev".into()]));
+        assert!(NotifierFilter::default().accepts(Severity::Low, &[]));
+    }
+}
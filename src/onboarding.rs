@@ -0,0 +1,123 @@
+//! Assistente da primeira execução.
+
+use super::*;
+
+/// Destino público sugerido no assistente, além do gateway e dos DNS.
+pub(crate) const ONBOARDING_PUBLIC_TARGET: &str = "1.1.1.1";
+/// Stub do systemd-resolved; os servidores reais ficam em `RESOLVED_UPSTREAM_CONF`.
+pub(crate) const RESOLVED_STUB: &str = "127.0.0.53";
+pub(crate) const RESOLVED_UPSTREAM_CONF: &str = "/run/systemd/resolve/resolv.conf";
+
+/// Sem `sites.json` ainda: a bandeja abre o assistente antes de monitorar.
+pub fn needs_onboarding() -> bool {
+    !get_config_path().exists()
+}
+
+/// Gateway IPv4 padrão, lido da tabela de rotas do kernel.
+#[cfg(target_os = "linux")]
+pub(crate) fn default_gateway() -> Option<std::net::Ipv4Addr> {
+    const RTF_GATEWAY: u16 = 0x2;
+    fs::read_to_string("/proc/net/route").ok()?.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let flags = u16::from_str_radix(fields.get(3)?, 16).ok()?;
+        if *fields.get(1)? != "00000000" || flags & RTF_GATEWAY == 0 {
+            return None;
+        }
+        // Endereço em hexadecimal na ordem de bytes da máquina (little-endian no x86/ARM)
+        let raw = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+        Some(std::net::Ipv4Addr::from(raw.to_le_bytes()))
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn default_gateway() -> Option<std::net::Ipv4Addr> {
+    None
+}
+
+/// Servidores DNS do sistema; atrás do systemd-resolved, os servidores de verdade.
+pub(crate) fn system_nameservers() -> Vec<String> {
+    let read = |path: &str| -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                (fields.next() == Some("nameserver")).then(|| fields.next().map(str::to_string)).flatten()
+            })
+            .collect()
+    };
+    let mut servers = read("/etc/resolv.conf");
+    if servers.iter().any(|s| s == RESOLVED_STUB) {
+        servers = read(RESOLVED_UPSTREAM_CONF);
+    }
+    servers.retain(|s| s.parse::<std::net::IpAddr>().is_ok_and(|ip| !ip.is_loopback()));
+    servers.dedup();
+    servers
+}
+
+/// Destino proposto no assistente, com a tag que o identifica depois.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub address: String,
+    pub tag: &'static str,
+}
+
+/// Gateway, DNS e um destino público, sem repetir endereços.
+pub fn onboarding_suggestions() -> Vec<Suggestion> {
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    let mut push = |address: String, tag: &'static str| {
+        if !suggestions.iter().any(|s| s.address == address) {
+            suggestions.push(Suggestion { address, tag });
+        }
+    };
+    if let Some(gateway) = default_gateway() {
+        push(gateway.to_string(), "gateway");
+    }
+    for server in system_nameservers() {
+        push(server, "dns");
+    }
+    push(ONBOARDING_PUBLIC_TARGET.to_string(), "internet");
+    suggestions
+}
+
+/// Destinos prontos da janela de configuração, agrupados por `group`.
+#[derive(Debug, Clone)]
+pub struct Preset {
+    /// Chave de tradução do grupo (`presets.*`).
+    pub group: &'static str,
+    pub name: String,
+    pub address: String,
+    pub tag: &'static str,
+}
+
+/// Resolvedores públicos: (nome, endereço).
+pub(crate) const PUBLIC_RESOLVERS: &[(&str, &str)] = &[
+    ("Cloudflare", "1.1.1.1"),
+    ("Google", "8.8.8.8"),
+    ("Quad9", "9.9.9.9"),
+    ("OpenDNS", "208.67.222.222"),
+];
+
+/// Serviços conhecidos, checados por HTTP.
+pub(crate) const COMMON_SERVICES: &[(&str, &str)] = &[
+    ("Google", "https://www.google.com"),
+    ("Cloudflare", "https://www.cloudflare.com"),
+    ("GitHub", "https://github.com"),
+    ("Wikipedia", "https://www.wikipedia.org"),
+    ("YouTube", "https://www.youtube.com"),
+];
+
+/// Rede local detectada (gateway e DNS do sistema), resolvedores públicos e serviços.
+pub fn target_presets() -> Vec<Preset> {
+    let preset = |group, name: &str, address: &str, tag| Preset { group, name: name.to_string(), address: address.to_string(), tag };
+    let mut presets = Vec::new();
+    if let Some(gateway) = default_gateway() {
+        presets.push(preset("presets.local", "Gateway", &gateway.to_string(), "gateway"));
+    }
+    for server in system_nameservers() {
+        presets.push(preset("presets.local", "DNS", &server, "dns"));
+    }
+    presets.extend(PUBLIC_RESOLVERS.iter().map(|(name, address)| preset("presets.resolvers", name, address, "dns")));
+    presets.extend(COMMON_SERVICES.iter().map(|(name, address)| preset("presets.services", name, address, "internet")));
+    presets
+}
@@ -0,0 +1,592 @@
+//! Backends de ping (ICMP nativo ou o binário do sistema).
+
+use super::*;
+
+/// Falha de um único eco, mapeada para a mensagem exibida no menu.
+pub enum PingFailure {
+    /// Sem resposta dentro do timeout.
+    Offline,
+    /// O backend não conseguiu nem enviar (binário ausente, DNS, permissão).
+    Error,
+}
+
+/// Backend de ping. Cada chamada envia um eco e espera até `wait` pela resposta.
+pub trait Pinger: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Latência em ms, quando o backend consegue medi-la.
+    fn echo(&self, host: &str, wait: Duration) -> Result<Option<f64>, PingFailure>;
+    /// Aviso fixo no menu (chave de tradução) quando o backend é um paliativo.
+    fn warning(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// Usa o binário `ping` do sistema (iputils no Linux).
+pub(crate) struct CommandPinger;
+
+impl Pinger for CommandPinger {
+    fn name(&self) -> &'static str {
+        "ping do sistema"
+    }
+
+    fn echo(&self, host: &str, wait: Duration) -> Result<Option<f64>, PingFailure> {
+        let out = ping_command(host, wait).output().map_err(|_| PingFailure::Error)?;
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        // No Windows o código de saída é 0 mesmo com "host inacessível"
+        if out.status.success() && (cfg!(not(windows)) || stdout.contains("TTL=")) {
+            Ok(parse_ping_latency(&stdout).and_then(|latency| latency.parse().ok()))
+        } else {
+            Err(PingFailure::Offline)
+        }
+    }
+}
+
+/// ICMP nativo via socket: `DGRAM` (sem privilégios, se `ping_group_range`
+/// permitir) ou `RAW` (exige CAP_NET_RAW/administrador).
+pub(crate) struct IcmpSocketPinger {
+    pub kind: socket2::Type,
+}
+
+pub(crate) static ICMP_SEQUENCE: std::sync::atomic::AtomicU16 = std::sync::atomic::AtomicU16::new(0);
+
+impl IcmpSocketPinger {
+    /// Testa se o kernel aceita abrir o socket ICMP deste tipo.
+    pub fn probe(kind: socket2::Type) -> Option<Self> {
+        socket2::Socket::new(socket2::Domain::IPV4, kind, Some(socket2::Protocol::ICMPV4))
+            .ok()
+            .map(|_| Self { kind })
+    }
+
+    pub fn is_raw(&self) -> bool {
+        self.kind == socket2::Type::RAW
+    }
+}
+
+/// Soma de verificação da internet (RFC 1071).
+pub(crate) fn icmp_checksum(data: &[u8]) -> u16 {
+    let mut sum: u32 = data
+        .chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum();
+    while sum >> 16 != 0 {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+impl Pinger for IcmpSocketPinger {
+    fn name(&self) -> &'static str {
+        if self.is_raw() {
+            "ICMP (raw)"
+        } else {
+            "ICMP (datagram)"
+        }
+    }
+
+    fn echo(&self, host: &str, wait: Duration) -> Result<Option<f64>, PingFailure> {
+        use socket2::{Domain, Protocol, SockAddr, Socket};
+        use std::io::Read;
+        use std::net::ToSocketAddrs;
+
+        let addr = (host, 0)
+            .to_socket_addrs()
+            .map_err(|_| PingFailure::Error)?
+            .next()
+            .ok_or(PingFailure::Error)?;
+        let (domain, protocol, request_type, reply_type) = if addr.is_ipv4() {
+            (Domain::IPV4, Protocol::ICMPV4, 8u8, 0u8)
+        } else {
+            (Domain::IPV6, Protocol::ICMPV6, 128u8, 129u8)
+        };
+        let socket = Socket::new(domain, self.kind, Some(protocol)).map_err(|_| PingFailure::Error)?;
+        socket.connect(&SockAddr::from(addr)).map_err(|_| PingFailure::Error)?;
+
+        let ident = (process::id() & 0xffff) as u16;
+        let seq = ICMP_SEQUENCE.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let mut packet = vec![request_type, 0, 0, 0];
+        packet.extend_from_slice(&ident.to_be_bytes());
+        packet.extend_from_slice(&seq.to_be_bytes());
+        packet.extend_from_slice(b"cosmic_pinger");
+        // O kernel calcula o checksum do ICMPv6; no IPv4 ele vai no pacote
+        if addr.is_ipv4() {
+            let checksum = icmp_checksum(&packet);
+            packet[2..4].copy_from_slice(&checksum.to_be_bytes());
+        }
+
+        let started = Instant::now();
+        let deadline = started + wait;
+        socket.send(&packet).map_err(|_| PingFailure::Error)?;
+
+        let mut reader = &socket;
+        let mut buf = [0u8; 1500];
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(PingFailure::Offline);
+            }
+            socket.set_read_timeout(Some(remaining)).map_err(|_| PingFailure::Error)?;
+            // Timeout (WouldBlock/TimedOut) ou erro ICMP do destino: sem resposta
+            let len = reader.read(&mut buf).map_err(|_| PingFailure::Offline)?;
+            let mut reply = &buf[..len];
+            // Sockets raw IPv4 (e DGRAM no macOS) entregam o cabeçalho IP junto
+            if addr.is_ipv4() && reply.first().map(|b| b >> 4) == Some(4) {
+                let header_len = usize::from(reply[0] & 0x0f) * 4;
+                reply = reply.get(header_len..).unwrap_or_default();
+            }
+            if reply.len() < 8 || reply[0] != reply_type {
+                continue;
+            }
+            // Em DGRAM o kernel troca o identificador; só a sequência é confiável
+            let same_ident = !self.is_raw() || reply[4..6] == ident.to_be_bytes();
+            if same_ident && reply[6..8] == seq.to_be_bytes() {
+                return Ok(Some(started.elapsed().as_secs_f64() * 1000.0));
+            }
+        }
+    }
+}
+
+/// ICMP pela IP Helper API do Windows (`IcmpSendEcho`/`Icmp6SendEcho2`):
+/// funciona sem administrador e sem abrir um `ping.exe` por eco.
+#[cfg(windows)]
+pub(crate) struct IcmpHelperPinger;
+
+#[cfg(windows)]
+impl IcmpHelperPinger {
+    /// Testa se a `iphlpapi.dll` entrega um handle ICMP.
+    pub fn probe() -> Option<Self> {
+        use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+        use windows_sys::Win32::NetworkManagement::IpHelper::{IcmpCloseHandle, IcmpCreateFile};
+
+        let handle = unsafe { IcmpCreateFile() };
+        if handle == INVALID_HANDLE_VALUE {
+            return None;
+        }
+        unsafe { IcmpCloseHandle(handle) };
+        Some(Self)
+    }
+}
+
+#[cfg(windows)]
+impl Pinger for IcmpHelperPinger {
+    fn name(&self) -> &'static str {
+        "ICMP (IP Helper)"
+    }
+
+    fn echo(&self, host: &str, wait: Duration) -> Result<Option<f64>, PingFailure> {
+        use std::ffi::c_void;
+        use std::net::{IpAddr, ToSocketAddrs};
+        use windows_sys::Win32::Foundation::INVALID_HANDLE_VALUE;
+        use windows_sys::Win32::NetworkManagement::IpHelper::{
+            Icmp6CreateFile, Icmp6SendEcho2, IcmpCloseHandle, IcmpCreateFile, IcmpSendEcho, ICMPV6_ECHO_REPLY_LH,
+            ICMP_ECHO_REPLY, IP_SUCCESS,
+        };
+        use windows_sys::Win32::Networking::WinSock::{AF_INET6, SOCKADDR_IN6};
+
+        let ip = (host, 0)
+            .to_socket_addrs()
+            .map_err(|_| PingFailure::Error)?
+            .next()
+            .ok_or(PingFailure::Error)?
+            .ip();
+        let payload = b"cosmic_pinger";
+        let timeout = wait.as_millis().clamp(1, u128::from(u32::MAX)) as u32;
+        // A resposta traz o cabeçalho, os dados ecoados e espaço para um erro ICMP
+        let mut reply = vec![0u8; std::mem::size_of::<ICMP_ECHO_REPLY>() + payload.len() + 8 + 64];
+
+        let handle = match ip {
+            IpAddr::V4(_) => unsafe { IcmpCreateFile() },
+            IpAddr::V6(_) => unsafe { Icmp6CreateFile() },
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return Err(PingFailure::Error);
+        }
+        let started = Instant::now();
+        let (count, status) = match ip {
+            IpAddr::V4(v4) => unsafe {
+                let count = IcmpSendEcho(
+                    handle,
+                    // Endereço em ordem de rede, como o `IPAddr` do Windows
+                    u32::from_ne_bytes(v4.octets()),
+                    payload.as_ptr() as *const c_void,
+                    payload.len() as u16,
+                    std::ptr::null(),
+                    reply.as_mut_ptr() as *mut c_void,
+                    reply.len() as u32,
+                    timeout,
+                );
+                (count, std::ptr::read_unaligned(reply.as_ptr() as *const ICMP_ECHO_REPLY).Status)
+            },
+            IpAddr::V6(v6) => unsafe {
+                let mut source: SOCKADDR_IN6 = std::mem::zeroed();
+                source.sin6_family = AF_INET6;
+                let mut destination: SOCKADDR_IN6 = std::mem::zeroed();
+                destination.sin6_family = AF_INET6;
+                destination.sin6_addr.u.Byte = v6.octets();
+                let count = Icmp6SendEcho2(
+                    handle,
+                    std::ptr::null_mut(),
+                    None,
+                    std::ptr::null(),
+                    &source,
+                    &destination,
+                    payload.as_ptr() as *const c_void,
+                    payload.len() as u16,
+                    std::ptr::null(),
+                    reply.as_mut_ptr() as *mut c_void,
+                    reply.len() as u32,
+                    timeout,
+                );
+                (count, std::ptr::read_unaligned(reply.as_ptr() as *const ICMPV6_ECHO_REPLY_LH).Status)
+            },
+        };
+        let elapsed = started.elapsed();
+        unsafe { IcmpCloseHandle(handle) };
+
+        // Zero respostas: timeout ou destino inacessível; outro status é erro ICMP do caminho
+        if count == 0 || status != IP_SUCCESS {
+            return Err(PingFailure::Offline);
+        }
+        Ok(Some(elapsed.as_secs_f64() * 1000.0))
+    }
+}
+
+/// Sem ICMP nativo nem binário `ping` (contêineres, instalações mínimas):
+/// o host responde se alguma porta de `ICMP_FALLBACK_PORTS` aceitar conexão.
+pub(crate) struct TcpPinger;
+
+impl Pinger for TcpPinger {
+    fn name(&self) -> &'static str {
+        "TCP (sem ping)"
+    }
+
+    fn echo(&self, host: &str, wait: Duration) -> Result<Option<f64>, PingFailure> {
+        ICMP_FALLBACK_PORTS
+            .iter()
+            .find_map(|&port| tcp_connect(host, port, wait).ok())
+            .map(Some)
+            .ok_or(PingFailure::Offline)
+    }
+
+    fn warning(&self) -> Option<&'static str> {
+        Some("tray.no_ping")
+    }
+}
+
+/// Procura o executável `ping` no `PATH`.
+pub(crate) fn ping_binary_available() -> bool {
+    let name = if cfg!(windows) { "ping.exe" } else { "ping" };
+    std::env::var_os("PATH")
+        .map(|path| std::env::split_paths(&path).any(|dir| dir.join(name).is_file()))
+        .unwrap_or(false)
+}
+
+pub(crate) static PINGER: OnceLock<Box<dyn Pinger>> = OnceLock::new();
+
+/// Escolhe o melhor backend disponível: ICMP sem privilégios, ICMP raw, o
+/// binário `ping` e, sem nenhum deles, conexões TCP. No Windows o ICMP vem
+/// da IP Helper API, que dispensa privilégios.
+pub fn pinger() -> &'static dyn Pinger {
+    PINGER
+        .get_or_init(|| {
+            #[cfg(windows)]
+            let native = IcmpHelperPinger::probe().map(|icmp| Box::new(icmp) as Box<dyn Pinger>);
+            #[cfg(not(windows))]
+            let native: Option<Box<dyn Pinger>> = None;
+            let icmp = native.or_else(|| {
+                IcmpSocketPinger::probe(socket2::Type::DGRAM)
+                    .or_else(|| IcmpSocketPinger::probe(socket2::Type::RAW))
+                    .map(|icmp| Box::new(icmp) as Box<dyn Pinger>)
+            });
+            let backend: Box<dyn Pinger> = match icmp {
+                Some(icmp) => icmp,
+                None if ping_binary_available() => {
+                    #[cfg(target_os = "linux")]
+                    explain_missing_icmp(&IcmpCapabilities::detect());
+                    Box::new(CommandPinger)
+                }
+                None => {
+                    warn!("[PING] Sem ICMP nativo e sem o comando ping no PATH; usando TCP nas portas {:?}", ICMP_FALLBACK_PORTS);
+                    Box::new(TcpPinger)
+                }
+            };
+            info!("[PING] Backend selecionado: {}", backend.name());
+            backend
+        })
+        .as_ref()
+}
+
+/// O que o kernel permite para ICMP sem o binário `ping`.
+#[cfg(target_os = "linux")]
+pub(crate) struct IcmpCapabilities {
+    /// `net.ipv4.ping_group_range`; o padrão das distros (`1 0`) desativa.
+    pub group_range: Option<(u32, u32)>,
+    pub groups: Vec<u32>,
+    pub cap_net_raw: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl IcmpCapabilities {
+    pub fn detect() -> Self {
+        let group_range = fs::read_to_string("/proc/sys/net/ipv4/ping_group_range")
+            .ok()
+            .and_then(|raw| {
+                let mut bounds = raw.split_whitespace().map(|v| v.parse::<u32>().ok());
+                Some((bounds.next()??, bounds.next()??))
+            });
+        let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+        let field = |name: &str| {
+            status
+                .lines()
+                .find_map(|line| line.strip_prefix(name))
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(str::to_string)
+                .collect::<Vec<_>>()
+        };
+        // Gid: real, efetivo, salvo, fs; o kernel compara o efetivo e os suplementares
+        let mut groups: Vec<u32> = field("Groups:").iter().filter_map(|g| g.parse().ok()).collect();
+        if let Some(egid) = field("Gid:").get(1).and_then(|g| g.parse().ok()) {
+            groups.push(egid);
+        }
+        const CAP_NET_RAW_BIT: u32 = 13;
+        let cap_net_raw = field("CapEff:")
+            .first()
+            .and_then(|caps| u64::from_str_radix(caps, 16).ok())
+            .map(|caps| caps & (1 << CAP_NET_RAW_BIT) != 0)
+            .unwrap_or(false);
+        Self { group_range, groups, cap_net_raw }
+    }
+
+    pub fn group_allowed(&self) -> bool {
+        self.group_range
+            .map(|(low, high)| self.groups.iter().any(|g| (low..=high).contains(g)))
+            .unwrap_or(false)
+    }
+}
+
+/// Explica, uma única vez por instalação, como liberar o ICMP nativo.
+#[cfg(target_os = "linux")]
+pub(crate) fn explain_missing_icmp(caps: &IcmpCapabilities) {
+    let range = caps
+        .group_range
+        .map(|(low, high)| format!("{} {}", low, high))
+        .unwrap_or_else(|| "?".to_string());
+    info!(
+        "[PING] ICMP nativo indisponível (ping_group_range = {}, grupo permitido: {}, CAP_NET_RAW: {})",
+        range,
+        caps.group_allowed(),
+        caps.cap_net_raw
+    );
+
+    let marker = get_data_dir().join("icmp_hint_shown");
+    if marker.exists() {
+        return;
+    }
+    let exe = std::env::current_exe()
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|_| "cosmic_pinger".to_string());
+    let body = trf("notify.icmp_hint", &[("exe", &exe)]);
+    show_notification(&body, "dialog-information", Severity::Low);
+    if let Err(e) = fs::write(&marker, Local::now().to_rfc3339()) {
+        warn!("Falha ao registrar aviso de ICMP: {}", e);
+    }
+}
+
+pub(crate) fn do_ping(host: &str, attempts: u8, wait: Duration) -> CheckOutcome {
+    let mut last_message = "OFFLINE";
+
+    for attempt in 0..attempts {
+        match pinger().echo(host, wait) {
+            Ok(Some(latency)) => return CheckOutcome::up(format!("{:.1} ms", latency), Some(latency)),
+            Ok(None) => return CheckOutcome::up("OK".to_string(), None),
+            Err(PingFailure::Offline) => last_message = "OFFLINE",
+            Err(PingFailure::Error) => last_message = tr("check.error"),
+        }
+
+        if attempt + 1 < attempts {
+            thread::sleep(Duration::from_millis(PING_RETRY_DELAY_MS));
+        }
+    }
+
+    CheckOutcome::down(last_message)
+}
+
+/// Tempo (ms) para abrir uma conexão TCP em `host:port`, tentando cada
+/// endereço resolvido até um aceitar.
+pub(crate) fn tcp_connect(host: &str, port: u16, timeout: Duration) -> Result<f64, String> {
+    use std::net::{TcpStream, ToSocketAddrs};
+
+    // IPv6 literal chega como `[::1]` ou `::1`; `to_socket_addrs` quer sem colchetes
+    let bare = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs = (bare, port).to_socket_addrs().map_err(|e| e.to_string())?;
+    let mut last_error = format!("{}: sem endereço", host);
+    for addr in addrs {
+        let started = Instant::now();
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(_) => return Ok(started.elapsed().as_secs_f64() * 1000.0),
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+/// Hosts que já responderam ao ICMP nesta execução. Para eles uma falha de
+/// ping é queda de verdade, não filtro: o fallback por TCP não se aplica.
+static ICMP_ANSWERED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+pub(crate) fn note_icmp_reply(host: &str) {
+    if let Ok(mut answered) = ICMP_ANSWERED.get_or_init(|| Mutex::new(HashSet::new())).lock() {
+        answered.insert(host.to_ascii_lowercase());
+    }
+}
+
+fn icmp_answered(host: &str) -> bool {
+    ICMP_ANSWERED
+        .get()
+        .and_then(|answered| answered.lock().ok())
+        .is_some_and(|answered| answered.contains(&host.to_ascii_lowercase()))
+}
+
+/// Host que nunca respondeu ao ICMP: se alguma porta de `ICMP_FALLBACK_PORTS`
+/// aceitar conexão em até `timeout`, o ping está filtrado e o host continua
+/// online. Hosts que já responderam ficam com a falha do ping.
+pub(crate) fn icmp_fallback(host: &str, failed: CheckOutcome, timeout: Duration) -> CheckOutcome {
+    if icmp_answered(host) {
+        return failed;
+    }
+    for port in ICMP_FALLBACK_PORTS {
+        if let Ok(latency) = tcp_connect(host, port, timeout) {
+            debug!("[PING] {} sem ICMP, mas TCP {} respondeu", host, port);
+            let message = trf("check.icmp_filtered", &[("port", &port), ("latency", &format!("{:.1}", latency))]);
+            return CheckOutcome::up(message, Some(latency));
+        }
+    }
+    failed
+}
+
+/// Envia `probes` ecos sem parar no primeiro sucesso e resume mín/méd/máx e
+/// perda. Online se ao menos um respondeu; a latência registrada é a média.
+pub(crate) fn do_ping_probes(host: &str, probes: u8, wait: Duration) -> CheckOutcome {
+    let mut latencies: Vec<f64> = Vec::new();
+    let mut received = 0u32;
+    let mut last_message = "OFFLINE";
+
+    for probe in 0..probes {
+        match pinger().echo(host, wait) {
+            Ok(latency) => {
+                received += 1;
+                latencies.extend(latency);
+            }
+            Err(PingFailure::Offline) => last_message = "OFFLINE",
+            Err(PingFailure::Error) => last_message = tr("check.error"),
+        }
+        if probe + 1 < probes {
+            thread::sleep(Duration::from_millis(PING_PROBE_INTERVAL_MS));
+        }
+    }
+
+    if received == 0 {
+        return CheckOutcome { loss_pct: Some(100.0), ..CheckOutcome::down(last_message) };
+    }
+    let loss_pct = (probes as u32 - received) as f64 * 100.0 / probes as f64;
+    let loss = format!("{:.0}", loss_pct);
+    let partial_loss = received < probes as u32;
+    if latencies.is_empty() {
+        let message = trf("check.ping_loss", &[("loss", &loss)]);
+        return CheckOutcome { success: true, message, latency_ms: None, degraded: partial_loss, fingerprint: None, loss_pct: Some(loss_pct), dns_ms: None };
+    }
+    let min = latencies.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = latencies.iter().copied().fold(0.0, f64::max);
+    let avg = latencies.iter().sum::<f64>() / latencies.len() as f64;
+    let message = trf(
+        "check.ping_stats",
+        &[
+            ("avg", &format!("{:.1}", avg)),
+            ("min", &format!("{:.1}", min)),
+            ("max", &format!("{:.1}", max)),
+            ("loss", &loss),
+        ],
+    );
+    CheckOutcome { success: true, message, latency_ms: Some(avg), degraded: partial_loss, fingerprint: None, loss_pct: Some(loss_pct), dns_ms: None }
+}
+
+/// Um único eco ICMP com timeout `wait`, nos argumentos de cada plataforma.
+pub(crate) fn ping_command(host: &str, wait: Duration) -> SysCommand {
+    let mut cmd = SysCommand::new("ping");
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let wait_ms = wait.as_millis().max(1).to_string();
+        cmd.args(["-n", "1", "-w", &wait_ms]).creation_flags(CREATE_NO_WINDOW);
+    }
+    // No macOS (ping BSD) o -W é em milissegundos
+    #[cfg(target_os = "macos")]
+    {
+        let wait_ms = wait.as_millis().max(1).to_string();
+        cmd.args(["-c", "1", "-W", &wait_ms]);
+    }
+    // Busybox e iputils antigos só aceitam segundos inteiros ("bad linger time"
+    // com `-W 0.5`); os backends por socket respeitam os milissegundos
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        let wait_secs = wait.as_millis().div_ceil(1000).max(1).to_string();
+        cmd.args(["-c", "1", "-W", &wait_secs]);
+    }
+    cmd.arg(host);
+    cmd
+}
+
+/// Extrai a latência de `time=12.3 ms` (iputils) ou `tempo=12ms`/`time<1ms`
+/// (Windows, com saída localizada).
+pub(crate) fn parse_ping_latency(stdout: &str) -> Option<&str> {
+    stdout
+        .lines()
+        .flat_map(|line| line.match_indices("ms").map(move |(ms, _)| &line[..ms]))
+        .find_map(|head| {
+            let head = head.trim_end();
+            let start = head.rfind(['=', '<'])? + 1;
+            let value = head[start..].trim();
+            (!value.is_empty() && value.chars().all(|c| c.is_ascii_digit() || c == '.')).then_some(value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latency_from_ping_output() {
+        assert_eq!(parse_ping_latency("64 bytes from 1.1.1.1: icmp_seq=1 ttl=57 time=12.3 ms"), Some("12.3"));
+        assert_eq!(parse_ping_latency("Resposta de 1.1.1.1: bytes=32 tempo=9ms TTL=57"), Some("9"));
+        assert_eq!(parse_ping_latency("Reply from 10.0.0.1: bytes=32 time<1ms TTL=64"), Some("1"));
+        assert_eq!(parse_ping_latency("Request timed out."), None);
+    }
+
+    #[test]
+    fn checksum_rfc1071() {
+        // Echo request sem dados: tipo 8, código 0, id 1, seq 1
+        let packet = [8, 0, 0, 0, 0, 1, 0, 1];
+        let sum = icmp_checksum(&packet);
+        let mut with_sum = packet;
+        with_sum[2..4].copy_from_slice(&sum.to_be_bytes());
+        assert_eq!(icmp_checksum(&with_sum), 0);
+        assert_eq!(icmp_checksum(&[0xff]), 0x00ff);
+    }
+
+    #[test]
+    fn fallback_only_for_hosts_without_icmp_replies() {
+        // 192.0.2.0/24 (TEST-NET-1) não responde: a conexão espera o timeout dado
+        let started = Instant::now();
+        let failed = icmp_fallback("192.0.2.1", CheckOutcome::down("OFFLINE"), Duration::from_millis(50));
+        assert!(!failed.success);
+        assert!(started.elapsed() < Duration::from_millis(TCP_CONNECT_TIMEOUT_MS));
+
+        // Um host que já respondeu ao ping não é nem tentado por TCP
+        note_icmp_reply("Router.Example");
+        let started = Instant::now();
+        let failed = icmp_fallback("router.example", CheckOutcome::down("OFFLINE"), Duration::from_secs(5));
+        assert_eq!(failed.message, "OFFLINE");
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}
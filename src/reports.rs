@@ -0,0 +1,310 @@
+//! Relatórios periódicos e página de status.
+
+use super::*;
+
+/// Uma queda consolidada a partir de amostras consecutivas com falha.
+#[derive(Clone, Copy, Serialize)]
+pub struct Outage {
+    pub start: i64,
+    pub end: i64,
+    pub ongoing: bool,
+}
+
+pub struct TargetReport {
+    pub host: String,
+    pub outages: Vec<Outage>,
+    pub downtime_secs: i64,
+    pub worst_latency_ms: Option<f64>,
+    pub uptime: Option<f64>,
+}
+
+impl HistoryStore {
+    /// Agrupa as amostras de `[from, to)` em quedas e estatísticas por destino.
+    pub fn summarize(&self, from: i64, to: i64) -> Vec<TargetReport> {
+        let mut reports: Vec<TargetReport> = self
+            .samples
+            .iter()
+            .map(|(host, queue)| {
+                let mut outages: Vec<Outage> = Vec::new();
+                let mut worst: Option<f64> = None;
+                let (mut total, mut up) = (0u32, 0u32);
+                let mut open: Option<i64> = None;
+
+                for sample in queue.iter().filter(|s| s.ts >= from && s.ts < to) {
+                    total += 1;
+                    if sample.up {
+                        up += 1;
+                        if let Some(start) = open.take() {
+                            outages.push(Outage { start, end: sample.ts, ongoing: false });
+                        }
+                    } else if open.is_none() {
+                        open = Some(sample.ts);
+                    }
+                    if let Some(lat) = sample.latency_ms {
+                        worst = Some(worst.map_or(lat, |w: f64| w.max(lat)));
+                    }
+                }
+                if let Some(start) = open {
+                    outages.push(Outage { start, end: to, ongoing: true });
+                }
+
+                TargetReport {
+                    host: host.clone(),
+                    downtime_secs: outages.iter().map(|o| o.end - o.start).sum(),
+                    outages,
+                    worst_latency_ms: worst,
+                    uptime: (total > 0).then(|| up as f64 * 100.0 / total as f64),
+                }
+            })
+            .filter(|r| r.uptime.is_some())
+            .collect();
+        reports.sort_by(|a, b| b.downtime_secs.cmp(&a.downtime_secs).then(a.host.cmp(&b.host)));
+        reports
+    }
+
+    /// Quedas e recuperações de `[from, to)` como eventos, do mais recente ao mais antigo.
+    pub fn state_events(&self, from: i64, to: i64) -> Vec<StateEvent> {
+        let mut events: Vec<StateEvent> = Vec::new();
+        for report in self.summarize(from, to) {
+            for outage in &report.outages {
+                events.push(StateEvent { ts: outage.start, host: report.host.clone(), is_up: false, outage_secs: None });
+                if !outage.ongoing {
+                    events.push(StateEvent {
+                        ts: outage.end,
+                        host: report.host.clone(),
+                        is_up: true,
+                        outage_secs: Some(outage.end - outage.start),
+                    });
+                }
+            }
+        }
+        events.sort_by(|a, b| b.ts.cmp(&a.ts).then(a.host.cmp(&b.host)));
+        events
+    }
+}
+
+pub fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    let (h, m, s) = (secs / 3600, (secs % 3600) / 60, secs % 60);
+    if h > 0 {
+        format!("{} h {} m", h, m)
+    } else if m > 0 {
+        format!("{} m {} s", m, s)
+    } else {
+        format!("{} s", s)
+    }
+}
+
+pub fn format_timestamp(ts: i64, fmt: &str) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| dt.with_timezone(&Local).format(fmt).to_string())
+        .unwrap_or_else(|| "?".to_string())
+}
+
+pub fn html_escape(raw: &str) -> String {
+    raw.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub(crate) fn render_report_html(reports: &[TargetReport], from: i64, to: i64) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\"><head><meta charset=\"utf-8\"><title>{app} — {title}</title>\n\
+         <style>body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:4px 8px;text-align:left}}.down{{color:#b00}}</style></head><body>\n\
+         <h1>{app} — {title}</h1>\n<p>{}</p>\n",
+        trf(
+            "report.period",
+            &[("from", &format_timestamp(from, "%d/%m/%Y %H:%M")), ("to", &format_timestamp(to, "%d/%m/%Y %H:%M"))],
+        ),
+        app = APP_NAME,
+        lang = tr("report.html_lang"),
+        title = tr("report.title"),
+    );
+    html.push_str(&format!(
+        "<table><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>\n",
+        tr("report.col_target"),
+        tr("report.col_uptime"),
+        tr("report.col_outages"),
+        tr("report.col_downtime"),
+        tr("report.col_worst_latency"),
+    ));
+    for r in reports {
+        html.push_str(&format!(
+            "<tr><td>{}</td><td>{}</td><td class=\"{}\">{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(&r.host),
+            format_uptime(r.uptime),
+            if r.outages.is_empty() { "" } else { "down" },
+            r.outages.len(),
+            format_duration(r.downtime_secs),
+            r.worst_latency_ms.map(|l| format!("{:.1} ms", l)).unwrap_or_else(|| "—".to_string()),
+        ));
+    }
+    html.push_str("</table>\n");
+
+    for r in reports.iter().filter(|r| !r.outages.is_empty()) {
+        html.push_str(&format!("<h2>{}</h2>\n<ul>\n", html_escape(&r.host)));
+        for o in &r.outages {
+            html.push_str(&format!(
+                "<li>{} → {} ({}){}</li>\n",
+                format_timestamp(o.start, "%d/%m %H:%M:%S"),
+                format_timestamp(o.end, "%d/%m %H:%M:%S"),
+                format_duration(o.end - o.start),
+                if o.ongoing { tr("report.ongoing") } else { "" },
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+/// Página pública: situação geral, uptime por destino e quedas da última
+/// semana. Recarrega sozinha a cada minuto.
+pub(crate) fn render_status_page(cfg: &StatusPageConfig, results: &[TargetStatus], history: &HistoryStore, now: i64) -> String {
+    let title = html_escape(cfg.title.as_deref().unwrap_or(APP_NAME));
+    let down = results.iter().filter(|r| !r.is_up && !r.is_idle()).count();
+    let banner = if down == 0 {
+        format!("<p class=\"banner up\">{}</p>", tr("status_page.all_up"))
+    } else {
+        format!("<p class=\"banner down\">{}</p>", trf("status_page.some_down", &[("count", &down)]))
+    };
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html lang=\"{lang}\"><head><meta charset=\"utf-8\">\
+         <meta http-equiv=\"refresh\" content=\"60\"><title>{title}</title>\n\
+         <style>body{{font-family:sans-serif;margin:2em auto;max-width:50em;padding:0 1em}}table{{border-collapse:collapse;width:100%}}\
+         td,th{{border-bottom:1px solid #ddd;padding:6px 8px;text-align:left}}.up{{color:#080}}.down{{color:#b00}}.degraded{{color:#b80}}.idle{{color:#888}}\
+         .banner{{padding:1em;border-radius:6px;background:#f4f4f4;font-weight:bold}}</style></head><body>\n\
+         <h1>{title}</h1>\n{banner}\n<p>{updated}</p>\n",
+        lang = tr("report.html_lang"),
+        updated = trf("status_page.updated", &[("time", &format_timestamp(now, "%d/%m/%Y %H:%M"))]),
+    );
+    html.push_str(&format!(
+        "<table><tr><th>{}</th><th>{}</th><th>24h</th><th>7d</th><th>30d</th></tr>\n",
+        tr("report.col_target"),
+        tr("status_page.col_status"),
+    ));
+    for r in results {
+        let (class, label) = if r.is_idle() {
+            ("idle", tr("status_page.paused"))
+        } else if r.health == Health::Degraded {
+            ("degraded", tr("status_page.degraded"))
+        } else if r.is_up {
+            ("up", tr("status_page.online"))
+        } else {
+            ("down", tr("status_page.offline"))
+        };
+        let detail = if cfg.show_details { format!(" — {}", html_escape(&r.detail)) } else { String::new() };
+        html.push_str(&format!(
+            "<tr><td>{}</td><td class=\"{}\">{}{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            html_escape(&r.host),
+            class,
+            label,
+            detail,
+            format_uptime(r.uptime.day),
+            format_uptime(r.uptime.week),
+            format_uptime(r.uptime.month),
+        ));
+    }
+    html.push_str("</table>\n");
+
+    html.push_str(&format!("<h2>{}</h2>\n", tr("status_page.incidents")));
+    let mut outages: Vec<(String, Outage)> = history
+        .summarize(now - 7 * DAY_SECS, now + 1)
+        .into_iter()
+        .filter(|r| results.iter().any(|t| t.host == r.host))
+        .flat_map(|r| r.outages.into_iter().map(move |o| (r.host.clone(), o)))
+        .collect();
+    outages.sort_by_key(|(_, o)| std::cmp::Reverse(o.start));
+    if outages.is_empty() {
+        html.push_str(&format!("<p>{}</p>\n", tr("status_page.no_incidents")));
+    } else {
+        html.push_str("<ul>\n");
+        for (host, o) in outages {
+            html.push_str(&format!(
+                "<li>{}: {} → {} ({}){}</li>\n",
+                html_escape(&host),
+                format_timestamp(o.start, "%d/%m %H:%M"),
+                format_timestamp(o.end, "%d/%m %H:%M"),
+                format_duration(o.end - o.start),
+                if o.ongoing { tr("report.ongoing") } else { "" },
+            ));
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</body></html>\n");
+    html
+}
+
+/// Grava a página via arquivo temporário + rename, para o servidor web nunca
+/// entregar um arquivo pela metade.
+pub(crate) fn write_status_page(cfg: &StatusPageConfig, results: &[TargetStatus], history: &HistoryStore, now: i64) {
+    let html = render_status_page(cfg, results, history, now);
+    let tmp = cfg.path.with_extension("html.tmp");
+    if let Err(e) = fs::write(&tmp, html).and_then(|_| fs::rename(&tmp, &cfg.path)) {
+        warn!("[STATUS] Erro ao gravar página de status em {:?}: {}", cfg.path, e);
+    }
+}
+
+pub(crate) fn report_state_path() -> PathBuf {
+    get_data_dir().join("report_state.json")
+}
+
+/// Gera o relatório se o período configurado já passou desde o último envio.
+/// Na primeira execução apenas marca o início do período.
+pub(crate) fn maybe_send_report(cfg: &ReportConfig, history: &HistoryStore, now: i64) {
+    let Some(period) = cfg.period.secs() else {
+        return;
+    };
+    let path = report_state_path();
+    let last = fs::read_to_string(&path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<serde_json::Value>(&c).ok())
+        .and_then(|v| v.get("last_report").and_then(|t| t.as_i64()));
+
+    let mark = |ts: i64| {
+        if let Err(e) = fs::write(&path, serde_json::json!({ "last_report": ts }).to_string()) {
+            error!("Erro ao gravar estado do relatório: {}", e);
+        }
+    };
+    let Some(last) = last else {
+        mark(now);
+        return;
+    };
+    if now - last < period {
+        return;
+    }
+
+    let from = now - period;
+    let reports = history.summarize(from, now);
+    info!("[RELATÓRIO] Gerando resumo de {} destinos", reports.len());
+
+    let mut html_path = None;
+    if cfg.html {
+        let dir = get_data_dir().join("reports");
+        let file = dir.join(format!("report-{}.html", format_timestamp(now, "%Y-%m-%d")));
+        match fs::create_dir_all(&dir).and_then(|_| fs::write(&file, render_report_html(&reports, from, now))) {
+            Ok(()) => html_path = Some(file),
+            Err(e) => error!("Erro ao gravar relatório HTML: {}", e),
+        }
+    }
+
+    if cfg.notify {
+        let outages: usize = reports.iter().map(|r| r.outages.len()).sum();
+        let period = tr(if cfg.period == ReportPeriod::Weekly { "report.weekly" } else { "report.daily" });
+        let mut body = trf("report.summary", &[("period", &period), ("count", &outages)]);
+        for r in reports.iter().filter(|r| !r.outages.is_empty()).take(3) {
+            body.push_str(&trf(
+                "report.summary_line",
+                &[("host", &r.host), ("duration", &format_duration(r.downtime_secs)), ("count", &r.outages.len())],
+            ));
+        }
+        if let Some(file) = &html_path {
+            body.push_str(&trf("report.details", &[("path", &file.display())]));
+        }
+        show_notification(&body, "x-office-document", Severity::Low);
+    }
+
+    mark(now);
+}
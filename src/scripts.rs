@@ -0,0 +1,117 @@
+//! Scripts Rhai de checagem (feature `scripting`).
+
+use super::*;
+
+/// Caminho de um script: absoluto ou relativo à pasta `scripts/` ao lado do `sites.json`.
+#[cfg(feature = "scripting")]
+pub(crate) fn script_path(name: &str) -> PathBuf {
+    let path = PathBuf::from(name.trim());
+    if path.is_absolute() {
+        return path;
+    }
+    get_config_path().parent().map_or(path.clone(), |dir| dir.join("scripts").join(&path))
+}
+
+/// Motor Rhai com `http_get(url)` (mapa com `status` e `body`) e `ping(host)`
+/// (`true` se respondeu). O limite de operações corta laços infinitos.
+#[cfg(feature = "scripting")]
+pub(crate) fn script_engine(client: Option<&Client>) -> rhai::Engine {
+    let mut engine = rhai::Engine::new();
+    engine.set_max_operations(SCRIPT_MAX_OPERATIONS);
+    let client = client.cloned();
+    engine.register_fn("http_get", move |url: &str| -> Result<rhai::Map, Box<rhai::EvalAltResult>> {
+        let client = require_client(client.as_ref())?;
+        let resp = client.get(url).send().map_err(|e| e.to_string())?;
+        let mut out = rhai::Map::new();
+        out.insert("status".into(), (resp.status().as_u16() as rhai::INT).into());
+        out.insert("body".into(), resp.text().unwrap_or_default().into());
+        Ok(out)
+    });
+    engine.register_fn("ping", |host: &str| pinger().echo(host, Duration::from_millis(PING_WAIT_MS)).is_ok());
+    engine
+}
+
+#[cfg(feature = "scripting")]
+pub(crate) fn dynamic_f64(value: &rhai::Dynamic) -> Option<f64> {
+    value.as_float().ok().or_else(|| value.as_int().ok().map(|v| v as f64))
+}
+
+/// Executa o script com `target`, `success`, `degraded`, `message` e
+/// `latency_ms` no escopo. O script decide o resultado devolvendo `true`/`false`, um mapa com
+/// esses campos ou só alterando as variáveis; erros contam como falha.
+#[cfg(feature = "scripting")]
+pub(crate) fn run_script(name: &str, target: &str, outcome: CheckOutcome, client: Option<&Client>) -> CheckOutcome {
+    let path = script_path(name);
+    let engine = script_engine(client);
+    let mut scope = rhai::Scope::new();
+    scope.push("target", target.to_string());
+    scope.push("success", outcome.success);
+    scope.push("degraded", outcome.degraded);
+    scope.push("message", outcome.message.clone());
+    scope.push("latency_ms", outcome.latency_ms.map_or(rhai::Dynamic::UNIT, rhai::Dynamic::from_float));
+
+    let result = match engine.eval_file_with_scope::<rhai::Dynamic>(&mut scope, path.clone()) {
+        Ok(result) => result,
+        Err(err) => {
+            warn!("[SCRIPT] {:?} falhou para {}: {}", path, target, err);
+            return CheckOutcome::down(&trf("check.script_error", &[("error", &err)]));
+        }
+    };
+    let mut success = scope.get_value::<bool>("success").unwrap_or(outcome.success);
+    let mut degraded = scope.get_value::<bool>("degraded").unwrap_or(outcome.degraded);
+    let mut message = scope.get_value::<String>("message").unwrap_or(outcome.message);
+    let mut latency_ms = scope.get("latency_ms").and_then(dynamic_f64);
+    if let Some(flag) = result.clone().try_cast::<bool>() {
+        success = flag;
+    } else if let Some(map) = result.try_cast::<rhai::Map>() {
+        if let Some(flag) = map.get("success").and_then(|v| v.as_bool().ok()) {
+            success = flag;
+        }
+        if let Some(flag) = map.get("degraded").and_then(|v| v.as_bool().ok()) {
+            degraded = flag;
+        }
+        if let Some(text) = map.get("message") {
+            message = text.to_string();
+        }
+        if let Some(value) = map.get("latency_ms") {
+            latency_ms = dynamic_f64(value);
+        }
+    }
+    if message.is_empty() {
+        message = tr("check.script_no_result").to_string();
+    }
+    debug!("[SCRIPT] {} -> {} ({})", target, success, message);
+    CheckOutcome { success, message, latency_ms: latency_ms.filter(|_| success), degraded: degraded && success, fingerprint: None, loss_pct: None, dns_ms: None }
+}
+
+#[cfg(not(feature = "scripting"))]
+pub(crate) fn run_script(name: &str, _target: &str, outcome: CheckOutcome, _client: Option<&Client>) -> CheckOutcome {
+    warn!("[SCRIPT] {} ignorado: compilado sem a feature `scripting`", name);
+    outcome
+}
+
+/// `script://nome.rhai`: o script é a checagem inteira.
+#[cfg(feature = "scripting")]
+pub(crate) struct ScriptCheck;
+
+#[cfg(feature = "scripting")]
+impl Check for ScriptCheck {
+    fn name(&self) -> &'static str {
+        "script"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["script"]
+    }
+
+    fn run(&self, target: &str, ctx: &CheckContext) -> CheckOutcome {
+        let name = target.split_once("://").map_or(target, |(_, rest)| rest);
+        let started = Instant::now();
+        let blank = CheckOutcome::down("");
+        let mut outcome = run_script(name, target, blank, ctx.http_client);
+        if outcome.success && outcome.latency_ms.is_none() {
+            outcome.latency_ms = Some(started.elapsed().as_secs_f64() * 1000.0);
+        }
+        outcome
+    }
+}
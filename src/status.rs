@@ -0,0 +1,227 @@
+//! Resumo do estado para as barras de status: linha do modo texto e JSON do
+//! modo `--statusbar`.
+
+use super::*;
+
+/// Modelo padrão do modo texto (`--format`).
+pub const DEFAULT_TEXT_FORMAT: &str = "{icon} {up}/{total}";
+
+/// Opções do modo texto (`--text`) para polybar/i3blocks.
+pub struct TextOutput {
+    pub format: String,
+    pub color: ColorStyle,
+}
+
+impl Default for TextOutput {
+    fn default() -> Self {
+        TextOutput { format: DEFAULT_TEXT_FORMAT.to_string(), color: ColorStyle::None }
+    }
+}
+
+/// Como colorir a linha do modo texto.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorStyle {
+    None,
+    /// `%{F#rrggbb}...%{F-}`
+    Polybar,
+    /// `<span color='#rrggbb'>...</span>` (i3blocks com `markup=pango`)
+    Pango,
+    Ansi,
+}
+
+impl ColorStyle {
+    pub fn parse(raw: &str) -> Result<ColorStyle, String> {
+        match raw.trim() {
+            "none" => Ok(ColorStyle::None),
+            "polybar" => Ok(ColorStyle::Polybar),
+            "pango" | "i3blocks" => Ok(ColorStyle::Pango),
+            "ansi" => Ok(ColorStyle::Ansi),
+            other => Err(format!("Estilo de cor inválido: '{}' (use none, polybar, pango ou ansi)", other)),
+        }
+    }
+}
+
+/// Resumo do estado atual compartilhado pelos modos de barra de status.
+pub struct StatusSummary {
+    pub text: String,
+    pub tooltip: String,
+    /// `starting`, `empty`, `ok`, `degraded`, `down` ou `paused`.
+    pub class: &'static str,
+}
+
+pub fn status_summary(s: &PingerState) -> StatusSummary {
+    let total = s.results.len();
+    let down = s.down_count();
+    let (text, class) = if s.paused_until.is_some() {
+        (tr("status.paused").to_string(), "paused")
+    } else if s.first_run {
+        ("🟡 …".to_string(), "starting")
+    } else if s.no_targets() {
+        (tr("status.empty").to_string(), "empty")
+    } else if down == 0 && s.degraded_count() > 0 {
+        (format!("🟡 {}/{}", total, total), "degraded")
+    } else if down == 0 {
+        (format!("🟢 {}/{}", total, total), "ok")
+    } else {
+        (format!("🔴 {}/{}", total - down, total), "down")
+    };
+    let mut lines = vec![trf("tray.last_check", &[("time", &s.last_update_text)])];
+    for r in &s.results {
+        let icon = match r.health {
+            _ if r.paused_until.is_some() => "⏸️",
+            _ if r.next_check.is_some() => "🕒",
+            Health::Up => "🟢",
+            Health::Degraded => "🟡",
+            Health::Down => "🔴",
+        };
+        lines.push(format!("{} {} ({})", icon, r.display_host(), r.detail));
+    }
+    StatusSummary { text, tooltip: lines.join("\n"), class }
+}
+
+/// Linha JSON do modo `--statusbar` (Waybar, i3status-rs).
+pub fn statusbar_line(s: &PingerState) -> String {
+    let summary = status_summary(s);
+    serde_json::json!({
+        "text": summary.text,
+        "tooltip": summary.tooltip,
+        "class": summary.class,
+        "alt": summary.class,
+    })
+    .to_string()
+}
+
+/// Linha do modo texto a partir do modelo em `--format`. Marcadores: `{icon}`,
+/// `{status}`, `{up}`, `{down}`, `{total}` e `{last_check}`.
+pub fn render_text_line(s: &PingerState, output: &TextOutput) -> String {
+    let summary = status_summary(s);
+    let total = s.results.len();
+    let down = s.down_count();
+    let (icon, color) = match summary.class {
+        "ok" => ("●", "#2ecc71"),
+        "degraded" => ("●", "#f39c12"),
+        "down" => ("●", "#e74c3c"),
+        "paused" => ("⏸", "#95a5a6"),
+        "empty" => ("○", "#95a5a6"),
+        _ => ("○", "#f1c40f"),
+    };
+    let line = output
+        .format
+        .replace("{icon}", icon)
+        .replace("{status}", summary.class)
+        .replace("{up}", &(total - down).to_string())
+        .replace("{down}", &down.to_string())
+        .replace("{total}", &total.to_string())
+        .replace("{last_check}", &s.last_update_text);
+
+    match output.color {
+        ColorStyle::None => line,
+        ColorStyle::Polybar => format!("%{{F{}}}{}%{{F-}}", color, line),
+        ColorStyle::Pango => format!("<span color='{}'>{}</span>", color, html_escape(&line)),
+        ColorStyle::Ansi => {
+            let code = match summary.class {
+                "ok" => 32,
+                "down" => 31,
+                "paused" => 90,
+                _ => 33,
+            };
+            format!("\x1b[{}m{}\x1b[0m", code, line)
+        }
+    }
+}
+
+/// Bolinha do resultado de uma checagem avulsa.
+pub fn outcome_icon(outcome: &CheckOutcome) -> &'static str {
+    if !outcome.success {
+        "🔴"
+    } else if outcome.degraded {
+        "🟡"
+    } else {
+        "🟢"
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn test_status(host: &str, health: Health) -> TargetStatus {
+    TargetStatus {
+        host: host.to_string(),
+        is_up: health != Health::Down,
+        health,
+        detail: "12 ms".to_string(),
+        flapping: false,
+        latency_anomaly: None,
+        uptime: UptimeStats::default(),
+        sla: None,
+        down_since: None,
+        paused_until: None,
+        next_check: None,
+        last_outage: None,
+        recent_latency: Vec::new(),
+        network: None,
+        hostname: None,
+        dns_ms: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(results: Vec<TargetStatus>) -> PingerState {
+        let mut s = PingerState::new();
+        s.first_run = false;
+        s.all_up = results.iter().all(|r| r.is_up);
+        s.last_update_text = "12:00".to_string();
+        s.results = results;
+        s
+    }
+
+    #[test]
+    fn summary_classes() {
+        assert_eq!(status_summary(&PingerState::new()).class, "starting");
+        assert_eq!(status_summary(&state(vec![])).class, "empty");
+        assert_eq!(status_summary(&state(vec![test_status("a", Health::Up)])).class, "ok");
+        assert_eq!(status_summary(&state(vec![test_status("a", Health::Degraded)])).class, "degraded");
+        let summary = status_summary(&state(vec![test_status("a", Health::Up), test_status("b", Health::Down)]));
+        assert_eq!((summary.class, summary.text.as_str()), ("down", "🔴 1/2"));
+        assert!(summary.tooltip.ends_with("🔴 b (12 ms)"));
+
+        let mut paused = state(vec![test_status("a", Health::Down)]);
+        paused.paused_until = Some(Local::now());
+        assert_eq!(status_summary(&paused).class, "paused");
+    }
+
+    #[test]
+    fn idle_targets_do_not_count_as_down() {
+        let mut idle = test_status("b", Health::Down);
+        idle.next_check = Some(0);
+        let summary = status_summary(&state(vec![test_status("a", Health::Up), idle]));
+        assert_eq!(summary.class, "ok");
+        assert!(summary.tooltip.ends_with("🕒 b (12 ms)"));
+    }
+
+    #[test]
+    fn text_line_placeholders_and_colors() {
+        let s = state(vec![test_status("a", Health::Up), test_status("b", Health::Down)]);
+        let output = |format: &str, color| TextOutput { format: format.to_string(), color };
+        assert_eq!(render_text_line(&s, &TextOutput::default()), "● 1/2");
+        assert_eq!(render_text_line(&s, &output("{status} {down} {last_check}", ColorStyle::None)), "down 1 12:00");
+        assert_eq!(render_text_line(&s, &output("{up}", ColorStyle::Polybar)), "%{F#e74c3c}1%{F-}");
+        assert_eq!(render_text_line(&s, &output("<{up}>", ColorStyle::Pango)), "<span color='#e74c3c'>&lt;1&gt;</span>");
+        assert_eq!(render_text_line(&s, &output("{up}", ColorStyle::Ansi)), "\x1b[31m1\x1b[0m");
+    }
+
+    #[test]
+    fn statusbar_json() {
+        let line: serde_json::Value = serde_json::from_str(&statusbar_line(&state(vec![test_status("a", Health::Up)]))).unwrap();
+        assert_eq!(line["class"], "ok");
+        assert_eq!(line["alt"], "ok");
+        assert_eq!(line["text"], "🟢 1/1");
+    }
+
+    #[test]
+    fn color_styles() {
+        assert_eq!(ColorStyle::parse(" i3blocks "), Ok(ColorStyle::Pango));
+        assert!(ColorStyle::parse("rgb").is_err());
+    }
+}
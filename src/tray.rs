@@ -0,0 +1,514 @@
+//! Conteúdo da bandeja (título, ícone, dica e menu) montado a partir do
+//! estado. O StatusNotifier do Linux e os ícones nativos das outras
+//! plataformas só traduzem este modelo e executam as `TrayAction`.
+
+use super::*;
+
+/// Lado, em pixels, dos ícones gerados em pixmap.
+pub const TRAY_ICON_SIZE: usize = 32;
+
+/// O que um item do menu pede; quem executa é o frontend.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrayAction {
+    CheckNow,
+    CheckTarget(String),
+    /// Restaura o backup do `sites.json` e checa de novo.
+    RestoreBackup,
+    Configure,
+    /// Dashboard da API local, ou a configuração se a API estiver desligada.
+    OpenDashboard,
+    ShowCertificate(String),
+    OpenUrl(String),
+    CopyAddress(String),
+    /// Pausa o destino por uma hora.
+    PauseTarget(String),
+    ResumeTarget(String),
+    Watch(String),
+    Diagnose(String),
+    ShowHistory(String),
+    Acknowledge(String),
+    /// Silencia os alertas por uma hora.
+    MuteHour,
+    MuteUntilResumed,
+    Unmute,
+    OpenEventLog,
+    Quit,
+}
+
+/// Item do menu da bandeja, independente da biblioteca de bandeja.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TrayMenuItem {
+    /// Texto informativo, desabilitado.
+    Label(String),
+    Action(String, TrayAction),
+    Submenu(String, Vec<TrayMenuItem>),
+    Separator,
+}
+
+/// `Cosmic Pinger`, ou com o número de destinos offline.
+pub fn tray_title(s: &PingerState) -> String {
+    match s.down_count() {
+        0 => APP_NAME.to_string(),
+        down => trf("tray.title_down", &[("app", &APP_NAME), ("count", &down)]),
+    }
+}
+
+/// Nome do ícone simbólico do estado (`assets/icons`).
+pub fn tray_icon_name(s: &PingerState) -> &'static str {
+    if s.paused_until.is_some() {
+        "cosmic-pinger-paused-symbolic"
+    } else if s.first_run {
+        "cosmic-pinger-starting-symbolic"
+    } else if s.no_targets() {
+        "cosmic-pinger-empty-symbolic"
+    } else if s.all_up && s.degraded_count() > 0 {
+        "cosmic-pinger-degraded-symbolic"
+    } else if s.all_up {
+        "cosmic-pinger-ok-symbolic"
+    } else {
+        "cosmic-pinger-down-symbolic"
+    }
+}
+
+/// Destinos offline pedem atenção; o painel destaca (ou anima) o ícone até a recuperação.
+pub fn tray_needs_attention(s: &PingerState) -> bool {
+    !s.first_run && s.paused_until.is_none() && s.down_count() > 0
+}
+
+/// Linhas da dica do ícone, abaixo do nome e da versão.
+pub fn tray_tooltip(s: &PingerState) -> String {
+    let mut status_txt = if let Some(until) = s.paused_until {
+        trf("tray.tip_paused", &[("time", &until.format("%H:%M"))])
+    } else if s.first_run {
+        tr("tray.tip_starting").to_string()
+    } else if s.no_targets() {
+        tr("tray.tip_empty").to_string()
+    } else if s.all_up {
+        trf("tray.tip_ok", &[("count", &s.results.len())])
+    } else {
+        tr("tray.tip_down").to_string()
+    };
+    let degraded = s.degraded_count();
+    if degraded > 0 && s.paused_until.is_none() && !s.first_run {
+        status_txt.push('\n');
+        status_txt.push_str(&trf("tray.tip_degraded", &[("count", &degraded)]));
+    }
+    match s.active_mute() {
+        Some(Mute::Until(until)) => {
+            status_txt.push('\n');
+            status_txt.push_str(&trf("tray.tip_muted", &[("time", &until.format("%H:%M"))]));
+        }
+        Some(Mute::UntilResumed) => {
+            status_txt.push('\n');
+            status_txt.push_str(tr("tray.tip_muted_resume"));
+        }
+        None => {}
+    }
+    status_txt
+}
+
+/// Ícone colorido em ARGB (`TRAY_ICON_SIZE` de lado), com o contador de
+/// destinos offline. Para hosts que não resolvem o ícone simbólico.
+pub fn tray_pixmap(s: &PingerState) -> Vec<u8> {
+    let (r, g, b) = if s.first_run {
+        (255, 255, 0) // Amarelo
+    } else if s.no_targets() {
+        (128, 128, 128) // Cinza
+    } else if s.all_up && s.degraded_count() > 0 {
+        (255, 170, 0) // Âmbar
+    } else if s.all_up {
+        (0, 255, 0) // Verde
+    } else {
+        (255, 0, 0) // Vermelho
+    };
+
+    // Byte 0 = Alpha (255 = Visível), depois R, G e B
+    let mut data = [255, r, g, b].repeat(TRAY_ICON_SIZE * TRAY_ICON_SIZE);
+    let down = s.down_count();
+    if down > 0 {
+        draw_down_badge(&mut data, TRAY_ICON_SIZE, down);
+    }
+    data
+}
+
+/// Só o contador, num fundo transparente, para hosts que sobrepõem overlay
+/// ao ícone simbólico. Vazio sem destinos offline.
+pub fn tray_badge_pixmap(s: &PingerState) -> Vec<u8> {
+    let down = s.down_count();
+    if down == 0 {
+        return Vec::new();
+    }
+    let mut data = vec![0; TRAY_ICON_SIZE * TRAY_ICON_SIZE * 4];
+    draw_down_badge(&mut data, TRAY_ICON_SIZE, down);
+    data
+}
+
+/// Fonte 3x5 para os dígitos do contador de destinos offline (bit 2 = coluna esquerda).
+const BADGE_DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b111, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+const BADGE_SCALE: usize = 2;
+
+/// Desenha `count` (limitado a 99) em branco numa imagem ARGB quadrada,
+/// alinhado ao canto inferior direito e sobre um fundo vermelho.
+pub(crate) fn draw_down_badge(data: &mut [u8], size: usize, count: usize) {
+    let digits: Vec<usize> = count
+        .min(99)
+        .to_string()
+        .bytes()
+        .map(|b| (b - b'0') as usize)
+        .collect();
+    let glyph_w = 3 * BADGE_SCALE;
+    let glyph_h = 5 * BADGE_SCALE;
+    let text_w = digits.len() * glyph_w + (digits.len() - 1) * BADGE_SCALE;
+    let badge_w = text_w + 2 * BADGE_SCALE;
+    let badge_h = glyph_h + 2 * BADGE_SCALE;
+    let left = size.saturating_sub(badge_w);
+    let top = size.saturating_sub(badge_h);
+
+    let mut paint = |x: usize, y: usize, (r, g, b): (u8, u8, u8)| {
+        if x < size && y < size {
+            let i = (y * size + x) * 4;
+            data[i..i + 4].copy_from_slice(&[255, r, g, b]);
+        }
+    };
+
+    for y in top..top + badge_h {
+        for x in left..left + badge_w {
+            paint(x, y, (200, 0, 0));
+        }
+    }
+    for (pos, digit) in digits.iter().enumerate() {
+        let origin_x = left + BADGE_SCALE + pos * (glyph_w + BADGE_SCALE);
+        let origin_y = top + BADGE_SCALE;
+        for (row, bits) in BADGE_DIGITS[*digit].iter().enumerate() {
+            for col in 0..3 {
+                if bits & (0b100 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..BADGE_SCALE {
+                    for dx in 0..BADGE_SCALE {
+                        paint(origin_x + col * BADGE_SCALE + dx, origin_y + row * BADGE_SCALE + dy, (255, 255, 255));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Menu completo da bandeja: cabeçalho, um submenu por destino, eventos
+/// recentes e as ações globais.
+pub fn tray_menu(s: &PingerState) -> Vec<TrayMenuItem> {
+    let mut items = Vec::new();
+
+    // Usa o timestamp armazenado - simples e estável
+    let mut update_label = trf("tray.last_check", &[("time", &s.last_update_text)]);
+    if let Some(until) = s.paused_until {
+        update_label.push_str(&trf("tray.paused_until", &[("time", &until.format("%H:%M"))]));
+    }
+    items.push(TrayMenuItem::Label(update_label));
+    items.push(TrayMenuItem::Separator);
+
+    if s.config_error.is_some() {
+        items.push(TrayMenuItem::Label(tr("tray.config_invalid").into()));
+        items.push(TrayMenuItem::Action(tr("tray.restore_backup").into(), TrayAction::RestoreBackup));
+        items.push(TrayMenuItem::Separator);
+    }
+    if s.no_targets() {
+        items.push(TrayMenuItem::Label(tr("tray.tip_empty").into()));
+        items.push(TrayMenuItem::Action(tr("tray.add_targets").into(), TrayAction::Configure));
+        items.push(TrayMenuItem::Separator);
+    }
+    if let Some(key) = pinger().warning() {
+        items.push(TrayMenuItem::Label(tr(key).into()));
+        items.push(TrayMenuItem::Separator);
+    }
+
+    for r in &s.results {
+        items.push(target_submenu(s, r));
+    }
+
+    let mut events: Vec<TrayMenuItem> = s
+        .recent_events
+        .iter()
+        .rev()
+        .map(|event| {
+            let mut label = format!(
+                "{} {} {}",
+                format_timestamp(event.ts, "%d/%m %H:%M"),
+                if event.is_up { "🟢" } else { "🔴" },
+                event.host
+            );
+            if let Some(secs) = event.outage_secs {
+                label.push_str(&trf("tray.event_outage", &[("duration", &format_duration(secs))]));
+            }
+            TrayMenuItem::Label(label)
+        })
+        .collect();
+    if events.is_empty() {
+        events.push(TrayMenuItem::Label(tr("tray.no_events").into()));
+    }
+    items.push(TrayMenuItem::Separator);
+    items.push(TrayMenuItem::Submenu(tr("tray.recent_events").into(), events));
+
+    items.push(TrayMenuItem::Separator);
+
+    if s.active_mute().is_some() {
+        items.push(TrayMenuItem::Action(tr("tray.unmute").into(), TrayAction::Unmute));
+    } else {
+        items.push(TrayMenuItem::Action(tr("tray.mute_hour").into(), TrayAction::MuteHour));
+        items.push(TrayMenuItem::Action(tr("tray.mute_resume").into(), TrayAction::MuteUntilResumed));
+    }
+
+    if let Some(release) = &s.update_available {
+        items.push(TrayMenuItem::Action(
+            trf("tray.update_available", &[("version", &release.version)]),
+            TrayAction::OpenUrl(release.url.clone()),
+        ));
+    }
+
+    items.push(TrayMenuItem::Action(tr("tray.open_event_log").into(), TrayAction::OpenEventLog));
+    items.push(TrayMenuItem::Action(tr("tray.configure").into(), TrayAction::Configure));
+    items.push(TrayMenuItem::Action(tr("tray.quit").into(), TrayAction::Quit));
+    items
+}
+
+/// Submenu de um destino: detalhes desabilitados e as ações sobre ele.
+fn target_submenu(s: &PingerState, r: &TargetStatus) -> TrayMenuItem {
+    let degraded = r.health == Health::Degraded;
+    let icon = if r.flapping {"🟠"} else if degraded {"🟡"} else if r.is_up {"🟢"} else {"🔴"};
+    let mut note = String::new();
+    if r.flapping {
+        note.push_str(tr("tray.note_flapping"));
+    }
+    if degraded {
+        note.push_str(tr("tray.note_degraded"));
+    }
+    if let Some(baseline) = r.latency_anomaly {
+        note.push_str(&trf("tray.note_slow", &[("baseline", &format!("{:.0}", baseline))]));
+    }
+    if r.sla_breached() {
+        note.push_str(tr("tray.note_sla"));
+    }
+    if let (false, Some(since)) = (r.is_up, r.down_since) {
+        let duration = format_duration(Local::now().timestamp() - since);
+        note.push_str(&trf("tray.note_down_for", &[("duration", &duration)]));
+    }
+    let icon = if r.sla_breached() && r.is_up && !r.flapping {"🟡"} else {icon};
+    let acknowledged = s.acknowledged.contains(&r.host);
+    let icon = if acknowledged {"🔕"} else {icon};
+    if acknowledged {
+        note.push_str(tr("tray.note_acknowledged"));
+    }
+    let icon = if r.paused_until.is_some() {"⏸️"} else if r.next_check.is_some() {"🕒"} else {icon};
+    if let Some(until) = r.paused_until {
+        note.push_str(&trf("tray.note_paused", &[("time", &format_timestamp(until, "%H:%M"))]));
+    } else if let Some(next) = r.next_check {
+        note.push_str(&trf("tray.note_next_check", &[("time", &format_timestamp(next, "%d/%m %H:%M"))]));
+    }
+
+    let mut details = vec![
+        trf("tray.uptime_day", &[("uptime", &format_uptime(r.uptime.day))]),
+        trf("tray.uptime_week", &[("uptime", &format_uptime(r.uptime.week))]),
+        trf("tray.uptime_month", &[("uptime", &format_uptime(r.uptime.month))]),
+    ];
+    if let Some(sla) = r.sla {
+        let mark = if r.sla_breached() {" ❗"} else {" ✔"};
+        details.push(trf("tray.sla", &[("percent", &format!("{:.2}", sla)), ("mark", &mark)]));
+    }
+    details.push(match r.last_outage {
+        Some(outage) if outage.ongoing => trf(
+            "tray.last_outage_ongoing",
+            &[
+                ("start", &format_timestamp(outage.start, "%d/%m %H:%M")),
+                ("duration", &format_duration(outage.end - outage.start)),
+            ],
+        ),
+        Some(outage) => trf(
+            "tray.last_outage",
+            &[
+                ("start", &format_timestamp(outage.start, "%d/%m %H:%M")),
+                ("end", &format_timestamp(outage.end, "%H:%M")),
+                ("duration", &format_duration(outage.end - outage.start)),
+            ],
+        ),
+        None => tr("tray.no_outage").to_string(),
+    });
+    if let Some(ms) = r.dns_ms {
+        details.push(trf("tray.dns_time", &[("ms", &format!("{:.1}", ms))]));
+    }
+    if let Some(network) = r.network.as_ref().and_then(|n| n.label()) {
+        details.push(trf("tray.network", &[("network", &network)]));
+    }
+    if let Some(count) = s.suppressed_alerts.get(&r.host) {
+        details.push(trf("tray.suppressed", &[("count", count)]));
+    }
+
+    let mut submenu: Vec<TrayMenuItem> = details.into_iter().map(TrayMenuItem::Label).collect();
+    submenu.push(TrayMenuItem::Separator);
+    let host = &r.host;
+    let action = |key: &'static str, action: TrayAction| TrayMenuItem::Action(tr(key).into(), action);
+    submenu.push(action("tray.check_now", TrayAction::CheckTarget(host.clone())));
+    if host.starts_with("https://") {
+        submenu.push(action("tray.certificate", TrayAction::ShowCertificate(host.clone())));
+    }
+    if host.starts_with("http://") || host.starts_with("https://") {
+        submenu.push(action("tray.open_browser", TrayAction::OpenUrl(host.clone())));
+    }
+    submenu.push(action("tray.copy_address", TrayAction::CopyAddress(host.clone())));
+    if r.paused_until.is_some() {
+        submenu.push(action("tray.resume", TrayAction::ResumeTarget(host.clone())));
+    } else {
+        submenu.push(action("tray.pause_hour", TrayAction::PauseTarget(host.clone())));
+    }
+    submenu.push(action("tray.watch", TrayAction::Watch(host.clone())));
+    submenu.push(action("tray.diagnose", TrayAction::Diagnose(host.clone())));
+    submenu.push(action("tray.show_history", TrayAction::ShowHistory(host.clone())));
+    if !r.is_up && !acknowledged {
+        submenu.push(action("tray.acknowledge", TrayAction::Acknowledge(host.clone())));
+    }
+
+    let mut trend = sparkline(&r.recent_latency);
+    if !trend.is_empty() {
+        trend.insert(0, ' ');
+    }
+    TrayMenuItem::Submenu(format!("{} {} ({}){}{}", icon, r.display_host(), r.detail, trend, note), submenu)
+}
+
+/// Texto da notificação "Histórico": as últimas checagens do destino em 24 h.
+pub fn target_history_text(host: &str) -> String {
+    let since = Local::now().timestamp() - 24 * 3600;
+    let samples: Vec<HistorySample> = HistoryStore::read_since(since)
+        .into_iter()
+        .filter(|sample| sample.host == host)
+        .collect();
+    if samples.is_empty() {
+        return trf("history.empty", &[("host", &host)]);
+    }
+    let lines: Vec<String> = samples
+        .iter()
+        .rev()
+        .take(10)
+        .map(|sample| {
+            let latency = sample.latency_ms.map(|ms| format!(" {:.0} ms", ms)).unwrap_or_default();
+            format!(
+                "{} {}{}",
+                format_timestamp(sample.ts, "%H:%M"),
+                if sample.up { "🟢" } else { "🔴" },
+                latency
+            )
+        })
+        .collect();
+    trf("history.header", &[("host", &host), ("lines", &lines.join("\n"))])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::status::test_status;
+
+    fn state(results: Vec<TargetStatus>) -> PingerState {
+        let mut s = PingerState::new();
+        s.first_run = false;
+        s.all_up = results.iter().all(|r| r.is_up);
+        s.results = results;
+        s
+    }
+
+    fn actions(items: &[TrayMenuItem]) -> Vec<TrayAction> {
+        items
+            .iter()
+            .flat_map(|item| match item {
+                TrayMenuItem::Action(_, action) => vec![action.clone()],
+                TrayMenuItem::Submenu(_, items) => actions(items),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn icon_and_attention_follow_the_state() {
+        assert_eq!(tray_icon_name(&PingerState::new()), "cosmic-pinger-starting-symbolic");
+        assert!(!tray_needs_attention(&PingerState::new()));
+        assert_eq!(tray_icon_name(&state(vec![])), "cosmic-pinger-empty-symbolic");
+        assert_eq!(tray_icon_name(&state(vec![test_status("a", Health::Degraded)])), "cosmic-pinger-degraded-symbolic");
+        let down = state(vec![test_status("a", Health::Down)]);
+        assert_eq!(tray_icon_name(&down), "cosmic-pinger-down-symbolic");
+        assert!(tray_needs_attention(&down));
+        assert_eq!(tray_title(&state(vec![test_status("a", Health::Up)])), APP_NAME);
+    }
+
+    #[test]
+    fn target_actions_depend_on_the_target() {
+        let mut down = test_status("https://h", Health::Down);
+        down.paused_until = None;
+        let mut paused = test_status("10.0.0.1", Health::Up);
+        paused.paused_until = Some(0);
+        let s = state(vec![down, paused]);
+        let found = actions(&tray_menu(&s));
+        assert!(found.contains(&TrayAction::ShowCertificate("https://h".into())));
+        assert!(found.contains(&TrayAction::OpenUrl("https://h".into())));
+        assert!(found.contains(&TrayAction::Acknowledge("https://h".into())));
+        assert!(found.contains(&TrayAction::PauseTarget("https://h".into())));
+        assert!(found.contains(&TrayAction::ResumeTarget("10.0.0.1".into())));
+        assert!(!found.contains(&TrayAction::OpenUrl("10.0.0.1".into())));
+        assert!(!found.contains(&TrayAction::Acknowledge("10.0.0.1".into())));
+        assert_eq!(found.last(), Some(&TrayAction::Quit));
+
+        let mut acknowledged = s.clone();
+        acknowledged.acknowledged.insert("https://h".into());
+        assert!(!actions(&tray_menu(&acknowledged)).contains(&TrayAction::Acknowledge("https://h".into())));
+    }
+
+    #[test]
+    fn menu_offers_restore_and_unmute_when_relevant() {
+        let mut s = state(vec![]);
+        s.config_error = Some("json".into());
+        s.muted = Some(Mute::UntilResumed);
+        let found = actions(&tray_menu(&s));
+        assert!(found.contains(&TrayAction::RestoreBackup));
+        assert!(found.contains(&TrayAction::Configure));
+        assert!(found.contains(&TrayAction::Unmute));
+        assert!(!found.contains(&TrayAction::MuteHour));
+    }
+
+    #[test]
+    fn pixmap_carries_the_down_badge() {
+        let size = TRAY_ICON_SIZE;
+        let pixel = |data: &[u8], x: usize, y: usize| data[(y * size + x) * 4..(y * size + x) * 4 + 4].to_vec();
+        let ok = tray_pixmap(&state(vec![test_status("a", Health::Up)]));
+        assert_eq!(ok.len(), size * size * 4);
+        assert_eq!(pixel(&ok, size - 1, size - 1), [255, 0, 255, 0]);
+        assert!(tray_badge_pixmap(&state(vec![test_status("a", Health::Up)])).is_empty());
+
+        let down = state(vec![test_status("a", Health::Down)]);
+        let icon = tray_pixmap(&down);
+        assert_eq!(pixel(&icon, 0, 0), [255, 255, 0, 0]);
+        assert_eq!(pixel(&icon, size - 1, size - 1), [255, 200, 0, 0]);
+        let badge = tray_badge_pixmap(&down);
+        assert_eq!(pixel(&badge, 0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(&badge, size - 1, size - 1), [255, 200, 0, 0]);
+    }
+
+    #[test]
+    fn badge_caps_at_two_digits() {
+        let mut one = vec![0; 32 * 32 * 4];
+        let mut many = vec![0; 32 * 32 * 4];
+        draw_down_badge(&mut one, 32, 7);
+        draw_down_badge(&mut many, 32, 150);
+        let painted = |data: &[u8]| data.chunks(4).filter(|px| px[0] == 255).count();
+        assert!(painted(&many) > painted(&one));
+        let mut capped = vec![0; 32 * 32 * 4];
+        draw_down_badge(&mut capped, 32, 99);
+        assert_eq!(many, capped);
+    }
+}
@@ -0,0 +1,139 @@
+//! Consulta de novas versões.
+
+use super::*;
+
+/// Release publicada, como lida da API do GitHub.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub url: String,
+}
+
+/// Última consulta de releases, em `update_state.json`.
+#[derive(Default, Serialize, Deserialize)]
+pub(crate) struct UpdateState {
+    pub checked: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latest: Option<ReleaseInfo>,
+}
+
+pub(crate) fn update_state_path() -> PathBuf {
+    get_data_dir().join("update_state.json")
+}
+
+/// `v1.2.3`, `1.2` ou `1.2.3-rc1` em números comparáveis; o sufixo é ignorado.
+pub(crate) fn parse_version(raw: &str) -> Option<Vec<u64>> {
+    let core = raw.trim().trim_start_matches(['v', 'V']).split(['-', '+']).next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+pub(crate) fn is_newer_version(candidate: &str, current: &str) -> bool {
+    match (parse_version(candidate), parse_version(current)) {
+        (Some(mut candidate), Some(mut current)) => {
+            // 1.2 == 1.2.0
+            let len = candidate.len().max(current.len());
+            candidate.resize(len, 0);
+            current.resize(len, 0);
+            candidate > current
+        }
+        _ => false,
+    }
+}
+
+/// Release mais recente da lista (rascunhos e, sem `prereleases`, pré-lançamentos
+/// ficam de fora). Aceita também um objeto único (`/releases/latest`).
+pub(crate) fn latest_release(client: &Client, cfg: &UpdateCheckConfig) -> Result<Option<ReleaseInfo>, String> {
+    let body: serde_json::Value = client
+        .get(&cfg.url)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .and_then(|resp| resp.error_for_status())
+        .and_then(|resp| resp.json())
+        .map_err(|e| e.to_string())?;
+    let releases = match &body {
+        serde_json::Value::Array(items) => items.clone(),
+        single => vec![single.clone()],
+    };
+    let flag = |release: &serde_json::Value, name: &str| release.get(name).and_then(|v| v.as_bool()).unwrap_or(false);
+    Ok(releases
+        .iter()
+        .filter(|release| !flag(release, "draft") && (cfg.prereleases || !flag(release, "prerelease")))
+        .filter_map(|release| {
+            let version = release.get("tag_name")?.as_str()?.to_string();
+            let url = release.get("html_url").and_then(|u| u.as_str()).unwrap_or_default().to_string();
+            parse_version(&version).map(|_| ReleaseInfo { version, url })
+        })
+        .reduce(|best, release| if is_newer_version(&release.version, &best.version) { release } else { best }))
+}
+
+/// Consulta as releases em segundo plano a cada `interval_hours`. O resultado
+/// sobrevive a reinícios para não repetir a consulta a cada abertura.
+pub(crate) struct UpdateWatch {
+    pending: Option<mpsc::Receiver<UpdateState>>,
+    state: UpdateState,
+}
+
+impl UpdateWatch {
+    pub fn load() -> Self {
+        let state = fs::read_to_string(update_state_path())
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+            .unwrap_or_default();
+        Self { pending: None, state }
+    }
+
+    /// A release disponível, se for mais nova que a versão em execução.
+    pub fn poll(&mut self, cfg: Option<&UpdateCheckConfig>, client: Option<&Client>) -> Option<ReleaseInfo> {
+        let cfg = cfg.filter(|cfg| cfg.enabled)?;
+        if let Some(rx) = &self.pending {
+            match rx.try_recv() {
+                Ok(state) => {
+                    self.state = state;
+                    self.pending = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {}
+                Err(mpsc::TryRecvError::Disconnected) => self.pending = None,
+            }
+        }
+
+        let interval = cfg.interval_hours.max(1) as i64 * 3600;
+        if let (None, Some(client)) = (&self.pending, client) {
+            if Local::now().timestamp() - self.state.checked >= interval {
+                let (tx, rx) = mpsc::channel();
+                let (client, cfg) = (client.clone(), cfg.clone());
+                let previous = self.state.latest.clone();
+                thread::spawn(move || {
+                    let latest = match latest_release(&client, &cfg) {
+                        Ok(latest) => latest,
+                        Err(e) => {
+                            // Falhas esperam o próximo intervalo, sem insistir
+                            warn!("[ATUALIZAÇÃO] Falha ao consultar releases: {}", e);
+                            previous
+                        }
+                    };
+                    let state = UpdateState { checked: Local::now().timestamp(), latest };
+                    if let Err(e) = fs::write(update_state_path(), serde_json::to_string_pretty(&state).unwrap_or_default()) {
+                        error!("Erro ao gravar estado das atualizações: {}", e);
+                    }
+                    let _ = tx.send(state);
+                });
+                self.pending = Some(rx);
+            }
+        }
+        self.state.latest.clone().filter(|release| is_newer_version(&release.version, APP_VERSION))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn version_comparison() {
+        assert!(is_newer_version("v1.2.1", "1.2.0"));
+        assert!(is_newer_version("1.10", "1.9.9"));
+        assert!(!is_newer_version("1.2", "1.2.0"));
+        assert!(!is_newer_version("1.2.0-rc1", "1.2.0"));
+        assert!(!is_newer_version("nightly", "1.0.0"));
+    }
+}
@@ -0,0 +1,45 @@
+//! Checagem de terceiros instalada pela API pública, como um frontend faria.
+//! Fica num binário de teste próprio porque o registro é global ao processo.
+
+use cosmic_pinger_core::{check_target, checks, install_checks, Check, CheckContext, CheckOutcome, CheckRegistry};
+
+struct EchoCheck;
+
+impl Check for EchoCheck {
+    fn name(&self) -> &'static str {
+        "echo"
+    }
+
+    fn schemes(&self) -> &'static [&'static str] {
+        &["echo"]
+    }
+
+    fn run(&self, target: &str, _ctx: &CheckContext) -> CheckOutcome {
+        match target.strip_prefix("echo://") {
+            Some("down") => CheckOutcome::down("echo: down"),
+            Some(rest) => CheckOutcome::up(format!("echo: {}", rest), Some(1.0)),
+            None => CheckOutcome::down("echo: sem esquema"),
+        }
+    }
+}
+
+#[test]
+fn custom_scheme_resolves_through_check_target() {
+    let mut registry = CheckRegistry::builtin();
+    registry.register(Box::new(EchoCheck));
+    assert!(install_checks(registry).is_ok());
+
+    let outcome = check_target("echo://ola", 1, 1, None, None);
+    assert!(outcome.success);
+    assert_eq!(outcome.message, "echo: ola");
+    assert_eq!(outcome.latency_ms, Some(1.0));
+    assert!(!check_target("ECHO://down", 1, 1, None, None).success);
+
+    // Os tipos embutidos continuam no registro instalado
+    assert_eq!(checks().resolve("tcp://localhost:1").map(|c| c.name()), Some("tcp"));
+
+    // Depois de inicializado, o registro não é mais trocado
+    let rejected = install_checks(CheckRegistry::builtin()).unwrap_err();
+    assert!(rejected.resolve("echo://x").is_none());
+    assert_eq!(checks().resolve("echo://x").map(|c| c.name()), Some("echo"));
+}